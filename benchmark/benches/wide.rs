@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use graphql_benchmark::{run, run_concurrent};
+use wide::{Q, S, S_REQUEST_EXTENSION, S_RESOLVE_EXTENSION};
+
+pub fn bench(c: &mut Criterion) {
+    c.bench_function("wide run", |b| b.iter(|| run(&S, black_box(Q))));
+    c.bench_function("wide run with request extension", |b| {
+        b.iter(|| run(&S_REQUEST_EXTENSION, black_box(Q)))
+    });
+    c.bench_function("wide run with resolve extension", |b| {
+        b.iter(|| run(&S_RESOLVE_EXTENSION, black_box(Q)))
+    });
+    c.bench_function("wide run concurrent", |b| {
+        b.iter(|| run_concurrent(&S, black_box(Q), CONCURRENCY))
+    });
+    c.bench_function("wide run concurrent with resolve extension", |b| {
+        b.iter(|| run_concurrent(&S_RESOLVE_EXTENSION, black_box(Q), CONCURRENCY))
+    });
+}
+
+/// The number of requests executed at the same time by the concurrent benchmarks.
+const CONCURRENCY: usize = 16;
+
+criterion_group!(wide, bench);
+criterion_main!(wide);
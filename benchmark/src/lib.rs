@@ -18,6 +18,24 @@ where
     task::block_on(async { s.execute(q).await.into_result().unwrap() })
 }
 
+/// Execute the query `n` times concurrently on the threads of the executor.
+pub fn run_concurrent<Query, Mutation, Subscription>(
+    s: &'static Schema<Query, Mutation, Subscription>,
+    q: &'static str,
+    n: usize,
+) where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    task::block_on(async {
+        let tasks = (0..n)
+            .map(|_| task::spawn(async move { s.execute(q).await.into_result().unwrap() }))
+            .collect::<Vec<_>>();
+        futures::future::join_all(tasks).await;
+    })
+}
+
 pub fn parse(q: &str) -> ExecutableDocument {
     parse_query(q).unwrap()
 }
@@ -0,0 +1,83 @@
+use async_graphql::extensions::{ApolloTracing, Extension, ExtensionContext, ExtensionFactory};
+use async_graphql::*;
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn items(&self) -> Vec<Item> {
+        (0..100).map(Item).collect()
+    }
+}
+
+pub struct Item(i32);
+
+#[Object]
+impl Item {
+    async fn a(&self) -> i32 {
+        self.0
+    }
+
+    async fn b(&self) -> i32 {
+        self.0
+    }
+
+    async fn c(&self) -> i32 {
+        self.0
+    }
+
+    async fn d(&self) -> i32 {
+        self.0
+    }
+
+    async fn e(&self) -> i32 {
+        self.0
+    }
+
+    async fn f(&self) -> i32 {
+        self.0
+    }
+
+    async fn g(&self) -> i32 {
+        self.0
+    }
+
+    async fn h(&self) -> i32 {
+        self.0
+    }
+
+    async fn i(&self) -> i32 {
+        self.0
+    }
+
+    async fn j(&self) -> i32 {
+        self.0
+    }
+}
+
+/// An extension that only uses the request level callbacks.
+pub struct RequestOnly;
+
+impl ExtensionFactory for RequestOnly {
+    fn create(&self) -> Box<dyn Extension> {
+        Box::new(RequestOnly)
+    }
+}
+
+impl Extension for RequestOnly {
+    fn execution_end(&self, _ctx: &ExtensionContext<'_>) {}
+}
+
+pub type WideSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+lazy_static::lazy_static! {
+    pub static ref S: WideSchema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    pub static ref S_REQUEST_EXTENSION: WideSchema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .extension(RequestOnly)
+        .finish();
+    pub static ref S_RESOLVE_EXTENSION: WideSchema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .extension(ApolloTracing)
+        .finish();
+}
+
+pub const Q: &str = "{ items { a b c d e f g h i j } }";
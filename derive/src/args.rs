@@ -1,7 +1,36 @@
 use darling::ast::{Data, Fields};
 use darling::util::Ignored;
 use darling::{FromDeriveInput, FromField, FromMeta, FromVariant};
-use syn::{Attribute, Generics, Ident, Lit, LitStr, Meta, Type, Visibility};
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Generics, Ident, Lit, LitStr, Meta, Type, Visibility};
+
+/// An attribute of the other derive of an input/output pair, it is only allowed with `input_name`.
+#[derive(Default)]
+pub struct PairAttribute(Option<Span>);
+
+impl FromMeta for PairAttribute {
+    fn from_meta(item: &Meta) -> darling::Result<Self> {
+        Ok(PairAttribute(Some(item.span())))
+    }
+}
+
+impl PairAttribute {
+    pub fn is_some(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Returns an error if the attribute is used by a derive that is not a part of a pair.
+    pub fn check(&self, name: &str, derive: &str, is_pair: bool) -> Result<(), Error> {
+        match self.0 {
+            Some(span) if !is_pair => Err(Error::new(
+                span,
+                format!("`{}` is only supported on {}", name, derive),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
 
 #[derive(FromMeta)]
 #[darling(default)]
@@ -71,6 +100,28 @@ pub struct SimpleObjectField {
     pub guard: Option<Meta>,
     #[darling(default)]
     pub post_guard: Option<Meta>,
+    #[darling(default)]
+    pub shareable: bool,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
+    #[darling(default)]
+    pub override_from: Option<String>,
+
+    // for InputObject
+    #[darling(default)]
+    pub default: PairAttribute,
+    #[darling(default)]
+    pub default_with: PairAttribute,
+    #[darling(default)]
+    pub validator: PairAttribute,
+    #[darling(default)]
+    pub process_with: PairAttribute,
+    #[darling(default)]
+    pub secret: PairAttribute,
+    #[darling(default)]
+    pub input_deprecation: PairAttribute,
 }
 
 #[derive(FromDeriveInput)]
@@ -89,6 +140,18 @@ pub struct SimpleObject {
     pub cache_control: CacheControl,
     #[darling(default)]
     pub extends: bool,
+    #[darling(default)]
+    pub shareable: bool,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
+    #[darling(default)]
+    pub interface_object: bool,
+    #[darling(default)]
+    pub unresolvable: Option<String>,
+    #[darling(default)]
+    pub input_name: PairAttribute,
 }
 
 #[derive(FromMeta, Default)]
@@ -99,6 +162,12 @@ pub struct Argument {
     pub default: Option<DefaultValue>,
     pub default_with: Option<LitStr>,
     pub validator: Option<Meta>,
+    pub process_with: Option<String>,
+    pub secret: bool,
+    pub deprecation: Option<String>,
+    pub inaccessible: bool,
+    #[darling(multiple, rename = "tag")]
+    pub tags: Vec<String>,
     pub key: bool, // for entity
 }
 
@@ -109,6 +178,12 @@ pub struct Object {
     pub name: Option<String>,
     pub cache_control: CacheControl,
     pub extends: bool,
+    pub shareable: bool,
+    pub inaccessible: bool,
+    #[darling(multiple, rename = "tag")]
+    pub tags: Vec<String>,
+    pub interface_object: bool,
+    pub unresolvable: Option<String>,
 }
 
 #[derive(FromMeta, Default)]
@@ -124,6 +199,11 @@ pub struct ObjectField {
     pub requires: Option<String>,
     pub guard: Option<Meta>,
     pub post_guard: Option<Meta>,
+    pub shareable: bool,
+    pub inaccessible: bool,
+    #[darling(multiple, rename = "tag")]
+    pub tags: Vec<String>,
+    pub override_from: Option<String>,
 }
 
 #[derive(FromDeriveInput)]
@@ -140,6 +220,10 @@ pub struct Enum {
     pub name: Option<String>,
     #[darling(default)]
     pub remote: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromVariant)]
@@ -153,6 +237,10 @@ pub struct EnumItem {
     pub name: Option<String>,
     #[darling(default)]
     pub deprecation: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromDeriveInput)]
@@ -167,6 +255,10 @@ pub struct Union {
     pub internal: bool,
     #[darling(default)]
     pub name: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromVariant)]
@@ -196,7 +288,42 @@ pub struct InputObjectField {
     #[darling(default)]
     pub validator: Option<Meta>,
     #[darling(default)]
+    pub process_with: Option<String>,
+    #[darling(default)]
+    pub secret: bool,
+    #[darling(default)]
+    pub deprecation: Option<String>,
+    /// The deprecation of the input field of a pair, `deprecation` only applies to the output field.
+    #[darling(default)]
+    pub input_deprecation: Option<String>,
+    #[darling(default)]
     pub flatten: bool,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
+
+    // for SimpleObject
+    #[darling(default)]
+    pub skip: PairAttribute,
+    #[darling(default)]
+    pub owned: PairAttribute,
+    #[darling(default)]
+    pub cache_control: PairAttribute,
+    #[darling(default)]
+    pub external: PairAttribute,
+    #[darling(default)]
+    pub provides: PairAttribute,
+    #[darling(default)]
+    pub requires: PairAttribute,
+    #[darling(default)]
+    pub guard: PairAttribute,
+    #[darling(default)]
+    pub post_guard: PairAttribute,
+    #[darling(default)]
+    pub shareable: PairAttribute,
+    #[darling(default)]
+    pub override_from: PairAttribute,
 }
 
 #[derive(FromDeriveInput)]
@@ -211,6 +338,24 @@ pub struct InputObject {
     pub internal: bool,
     #[darling(default)]
     pub name: Option<String>,
+    #[darling(default)]
+    pub input_name: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
+
+    // for SimpleObject
+    #[darling(default)]
+    pub cache_control: PairAttribute,
+    #[darling(default)]
+    pub extends: PairAttribute,
+    #[darling(default)]
+    pub shareable: PairAttribute,
+    #[darling(default)]
+    pub interface_object: PairAttribute,
+    #[darling(default)]
+    pub unresolvable: PairAttribute,
 }
 
 #[derive(FromMeta)]
@@ -224,6 +369,16 @@ pub struct InterfaceFieldArgument {
     pub default: Option<DefaultValue>,
     #[darling(default)]
     pub default_with: Option<LitStr>,
+    #[darling(default)]
+    pub process_with: Option<String>,
+    #[darling(default)]
+    pub secret: bool,
+    #[darling(default)]
+    pub deprecation: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromMeta)]
@@ -245,6 +400,10 @@ pub struct InterfaceField {
     pub provides: Option<String>,
     #[darling(default)]
     pub requires: Option<String>,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromVariant)]
@@ -267,8 +426,16 @@ pub struct Interface {
     pub name: Option<String>,
     #[darling(default, multiple, rename = "field")]
     pub fields: Vec<InterfaceField>,
+    #[darling(default, multiple, rename = "implements")]
+    pub implements: Vec<String>,
     #[darling(default)]
     pub extends: bool,
+    #[darling(default)]
+    pub node: bool,
+    #[darling(default)]
+    pub inaccessible: bool,
+    #[darling(default, multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromMeta, Default)]
@@ -276,6 +443,10 @@ pub struct Interface {
 pub struct Scalar {
     pub internal: bool,
     pub name: Option<String>,
+    pub specified_by_url: Option<String>,
+    pub inaccessible: bool,
+    #[darling(multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromMeta, Default)]
@@ -293,6 +464,12 @@ pub struct SubscriptionFieldArgument {
     pub default: Option<DefaultValue>,
     pub default_with: Option<LitStr>,
     pub validator: Option<Meta>,
+    pub process_with: Option<String>,
+    pub secret: bool,
+    pub deprecation: Option<String>,
+    pub inaccessible: bool,
+    #[darling(multiple, rename = "tag")]
+    pub tags: Vec<String>,
 }
 
 #[derive(FromMeta, Default)]
@@ -303,6 +480,7 @@ pub struct SubscriptionField {
     pub deprecation: Option<String>,
     pub guard: Option<Meta>,
     pub post_guard: Option<Meta>,
+    pub filter: Option<String>,
 }
 
 #[derive(FromMeta, Default)]
@@ -1,5 +1,5 @@
 use crate::args;
-use crate::utils::{get_crate_name, get_rustdoc, GeneratorResult};
+use crate::utils::{generate_tags, get_crate_name, get_rustdoc, GeneratorResult};
 use darling::ast::Data;
 use inflector::Inflector;
 use proc_macro::TokenStream;
@@ -20,6 +20,8 @@ pub fn generate(enum_args: &args::Enum) -> GeneratorResult<TokenStream> {
     let desc = get_rustdoc(&enum_args.attrs)?
         .map(|s| quote! { Some(#s) })
         .unwrap_or_else(|| quote! {None});
+    let inaccessible = enum_args.inaccessible;
+    let tags = generate_tags(&enum_args.tags);
 
     let mut enum_items = Vec::new();
     let mut items = Vec::new();
@@ -52,6 +54,9 @@ pub fn generate(enum_args: &args::Enum) -> GeneratorResult<TokenStream> {
             .map(|s| quote! { Some(#s) })
             .unwrap_or_else(|| quote! {None});
 
+        let item_inaccessible = variant.inaccessible;
+        let item_tags = generate_tags(&variant.tags);
+
         enum_items.push(item_ident);
         items.push(quote! {
             #crate_name::resolver_utils::EnumItem {
@@ -64,6 +69,8 @@ pub fn generate(enum_args: &args::Enum) -> GeneratorResult<TokenStream> {
                 name: #gql_item_name,
                 description: #item_desc,
                 deprecation: #item_deprecation,
+                inaccessible: #item_inaccessible,
+                tags: #item_tags,
             });
         });
     }
@@ -132,6 +139,8 @@ pub fn generate(enum_args: &args::Enum) -> GeneratorResult<TokenStream> {
                             #(#schema_enum_items)*
                             enum_items
                         },
+                        inaccessible: #inaccessible,
+                        tags: #tags,
                     }
                 })
             }
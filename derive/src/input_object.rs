@@ -1,6 +1,7 @@
 use crate::args;
 use crate::utils::{
-    generate_default, generate_validator, get_crate_name, get_rustdoc, GeneratorResult,
+    generate_default, generate_process_with, generate_tags, generate_validator, get_crate_name,
+    get_rustdoc, GeneratorResult,
 };
 use darling::ast::Data;
 use inflector::Inflector;
@@ -21,8 +22,47 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
         }
     };
 
+    // The attributes of the output type are only allowed with `input_name`, which derives both.
+    let is_pair = object_args.input_name.is_some();
+    object_args
+        .cache_control
+        .check("cache_control", "SimpleObject", is_pair)?;
+    object_args
+        .extends
+        .check("extends", "SimpleObject", is_pair)?;
+    object_args
+        .shareable
+        .check("shareable", "SimpleObject", is_pair)?;
+    object_args
+        .interface_object
+        .check("interface_object", "SimpleObject", is_pair)?;
+    object_args
+        .unresolvable
+        .check("unresolvable", "SimpleObject", is_pair)?;
+
     let mut struct_fields = Vec::new();
     for field in &s.fields {
+        for (attr, name) in &[
+            (&field.skip, "skip"),
+            (&field.owned, "owned"),
+            (&field.cache_control, "cache_control"),
+            (&field.external, "external"),
+            (&field.provides, "provides"),
+            (&field.requires, "requires"),
+            (&field.guard, "guard"),
+            (&field.post_guard, "post_guard"),
+            (&field.shareable, "shareable"),
+            (&field.override_from, "override_from"),
+        ] {
+            attr.check(name, "SimpleObject", is_pair)?;
+        }
+        if field.input_deprecation.is_some() && !is_pair {
+            return Err(Error::new_spanned(
+                &field.ident,
+                "`input_deprecation` is only supported with `input_name`, use `deprecation`",
+            )
+            .into());
+        }
         let vis = &field.vis;
         let ty = &field.ty;
         let ident = &field.ident;
@@ -38,13 +78,16 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
     }
 
     let gql_typename = object_args
-        .name
+        .input_name
         .clone()
+        .or_else(|| object_args.name.clone())
         .unwrap_or_else(|| ident.to_string());
 
     let desc = get_rustdoc(&object_args.attrs)?
         .map(|s| quote! { Some(#s) })
         .unwrap_or_else(|| quote! {None});
+    let inaccessible = object_args.inaccessible;
+    let tags = generate_tags(&object_args.tags);
 
     let mut get_fields = Vec::new();
     let mut put_fields = Vec::new();
@@ -65,9 +108,9 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
 
             schema_fields.push(quote! {
                 #crate_name::static_assertions::assert_impl_one!(#ty: #crate_name::InputObjectType);
-                #ty::create_type_info(registry);
+                <#ty as #crate_name::InputValueType>::create_input_type_info(registry);
                 if let Some(#crate_name::registry::MetaType::InputObject{ input_fields, .. }) =
-                    registry.types.remove(&*<#ty as #crate_name::Type>::type_name()) {
+                    registry.types.remove(&*<#ty as #crate_name::InputValueType>::input_type_name()) {
                     fields.extend(input_fields);
                 }
             });
@@ -96,6 +139,18 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
         let desc = get_rustdoc(&field.attrs)?
             .map(|s| quote! { Some(#s) })
             .unwrap_or_else(|| quote! {None});
+        // With `input_name`, `deprecation` belongs to the output field.
+        let deprecation = if is_pair {
+            &field.input_deprecation
+        } else {
+            &field.deprecation
+        };
+        let deprecation = deprecation
+            .as_ref()
+            .map(|s| quote! { Some(#s) })
+            .unwrap_or_else(|| quote! {None});
+        let field_inaccessible = field.inaccessible;
+        let field_tags = generate_tags(&field.tags);
         let default = generate_default(&field.default, &field.default_with)?;
         let schema_default = default
             .as_ref()
@@ -104,18 +159,37 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
             })
             .unwrap_or_else(|| quote! {None});
 
+        let secret = field.secret;
+        let parse_value = if secret {
+            quote! {
+                #crate_name::InputValueType::parse(obj.get(#name).cloned())
+                    .map_err(#crate_name::InputValueError::redact)?
+            }
+        } else {
+            quote! { #crate_name::InputValueType::parse(obj.get(#name).cloned())? }
+        };
+
+        let process_with = generate_process_with(&field.process_with)?;
+        let mutability = process_with.as_ref().map(|_| quote!(mut));
+
         if let Some(default) = default {
             get_fields.push(quote! {
-                let #ident: #ty = {
+                let #mutability #ident: #ty = {
                     match obj.get(#name) {
-                        Some(value) => #crate_name::InputValueType::parse(Some(value.clone()))?,
+                        Some(_) => #parse_value,
                         None => #default,
                     }
                 };
             });
         } else {
             get_fields.push(quote! {
-                let #ident:#ty = #crate_name::InputValueType::parse(obj.get(#name).cloned())?;
+                let #mutability #ident: #ty = #parse_value;
+            });
+        }
+
+        if let Some(process_with) = process_with {
+            get_fields.push(quote! {
+                #process_with(&mut #ident);
             });
         }
 
@@ -131,35 +205,67 @@ pub fn generate(object_args: &args::InputObject) -> GeneratorResult<TokenStream>
             fields.insert(#name.to_string(), #crate_name::registry::MetaInputValue {
                 name: #name,
                 description: #desc,
-                ty: <#ty as #crate_name::Type>::create_type_info(registry),
+                ty: <#ty as #crate_name::InputValueType>::create_input_type_info(registry),
                 default_value: #schema_default,
                 validator: #validator,
+                deprecation: #deprecation,
+                is_secret: #secret,
+                inaccessible: #field_inaccessible,
+                tags: #field_tags,
             });
         })
     }
 
-    let expanded = quote! {
-        #[allow(clippy::all, clippy::pedantic)]
-        impl #crate_name::Type for #ident {
-            fn type_name() -> ::std::borrow::Cow<'static, str> {
+    let meta_type = quote! {
+        #crate_name::registry::MetaType::InputObject {
+            name: #gql_typename.to_string(),
+            description: #desc,
+            input_fields: {
+                let mut fields = #crate_name::indexmap::IndexMap::new();
+                #(#schema_fields)*
+                fields
+            },
+            inaccessible: #inaccessible,
+            tags: #tags,
+        }
+    };
+
+    // With `input_name`, the `Type` implementation comes from the output type derived for the same
+    // struct, and the input type is registered under its own name.
+    let (type_impl, input_type_info) = if object_args.input_name.is_some() {
+        let input_type_info = quote! {
+            fn input_type_name() -> ::std::borrow::Cow<'static, str> {
                 ::std::borrow::Cow::Borrowed(#gql_typename)
             }
 
-            fn create_type_info(registry: &mut #crate_name::registry::Registry) -> String {
-                registry.create_type::<Self, _>(|registry| #crate_name::registry::MetaType::InputObject {
-                    name: #gql_typename.to_string(),
-                    description: #desc,
-                    input_fields: {
-                        let mut fields = #crate_name::indexmap::IndexMap::new();
-                        #(#schema_fields)*
-                        fields
-                    }
-                })
+            fn create_input_type_info(registry: &mut #crate_name::registry::Registry) -> String {
+                registry.create_input_type::<Self, _>(|registry| #meta_type)
             }
-        }
+        };
+        (None, input_type_info)
+    } else {
+        let type_impl = quote! {
+            #[allow(clippy::all, clippy::pedantic)]
+            impl #crate_name::Type for #ident {
+                fn type_name() -> ::std::borrow::Cow<'static, str> {
+                    ::std::borrow::Cow::Borrowed(#gql_typename)
+                }
+
+                fn create_type_info(registry: &mut #crate_name::registry::Registry) -> String {
+                    registry.create_type::<Self, _>(|registry| #meta_type)
+                }
+            }
+        };
+        (Some(type_impl), quote!())
+    };
+
+    let expanded = quote! {
+        #type_impl
 
         #[allow(clippy::all, clippy::pedantic)]
         impl #crate_name::InputValueType for #ident {
+            #input_type_info
+
             fn parse(value: Option<#crate_name::Value>) -> #crate_name::InputValueResult<Self> {
                 if let Some(#crate_name::Value::Object(obj)) = value {
                     #(#get_fields)*
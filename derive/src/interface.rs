@@ -1,14 +1,17 @@
 use crate::args;
 use crate::args::{InterfaceField, InterfaceFieldArgument};
 use crate::output_type::OutputType;
-use crate::utils::{generate_default, get_crate_name, get_rustdoc, GeneratorResult};
+use crate::utils::{
+    generate_default, generate_process_with, generate_tags, get_crate_name, get_rustdoc,
+    GeneratorResult,
+};
 use darling::ast::{Data, Style};
 use inflector::Inflector;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use std::collections::HashSet;
-use syn::{Error, Type};
+use syn::{Error, LitStr, Type};
 
 pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream> {
     let crate_name = get_crate_name(interface_args.internal);
@@ -37,8 +40,10 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
 
     let mut registry_types = Vec::new();
     let mut possible_types = Vec::new();
+    let mut implements = Vec::new();
     let mut get_introspection_typename = Vec::new();
     let mut collect_all_fields = Vec::new();
+    let mut node_fetchers = Vec::new();
 
     for variant in s {
         let enum_name = &variant.ident;
@@ -99,6 +104,12 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
             collect_all_fields.push(quote! {
                 #ident::#enum_name(obj) => obj.collect_all_fields(ctx, fields)
             });
+
+            node_fetchers.push(quote! {
+                if id.type_name() == <#p as #crate_name::Type>::type_name() {
+                    return Ok(<#p as #crate_name::relay::NodeType>::fetch(ctx, id.id()).await?.map(#ident::#enum_name));
+                }
+            });
         } else {
             return Err(Error::new_spanned(ty, "Invalid type").into());
         }
@@ -108,6 +119,26 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
     let mut schema_fields = Vec::new();
     let mut resolvers = Vec::new();
 
+    // A `Node` interface has an `id` field, which is added if it is not declared.
+    let node_id_field =
+        if interface_args.node && !interface_args.fields.iter().any(|field| field.name == "id") {
+            Some(InterfaceField {
+                name: "id".to_string(),
+                ty: LitStr::new("ID", Span::call_site()),
+                method: None,
+                desc: Some("The ID of the object.".to_string()),
+                args: Vec::new(),
+                deprecation: None,
+                external: false,
+                provides: None,
+                requires: None,
+                inaccessible: false,
+                tags: Vec::new(),
+            })
+        } else {
+            None
+        };
+
     for InterfaceField {
         name,
         method,
@@ -118,7 +149,9 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
         external,
         provides,
         requires,
-    } in &interface_args.fields
+        inaccessible,
+        tags,
+    } in interface_args.fields.iter().chain(&node_id_field)
     {
         let (name, method_name) = if let Some(method) = method {
             (name.to_string(), Ident::new(method, Span::call_site()))
@@ -143,6 +176,7 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
             Some(provides) => quote! { Some(#provides) },
             None => quote! { None },
         };
+        let field_tags = generate_tags(tags);
 
         decl_params.push(quote! { ctx: &'ctx #crate_name::Context<'ctx> });
         use_params.push(quote! { ctx });
@@ -153,6 +187,11 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
             ty,
             default,
             default_with,
+            process_with,
+            secret,
+            deprecation,
+            inaccessible,
+            tags,
         } in args
         {
             let ident = Ident::new(name, Span::call_site());
@@ -169,14 +208,33 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
                 Some(default) => quote! { Some(|| -> #ty { #default }) },
                 None => quote! { None },
             };
-            get_params.push(quote! {
-                let #ident: #ty = ctx.param_value(#name, #get_default)?;
-            });
+            let get_value = if *secret {
+                quote! { ctx.secret_param_value(#name, #get_default)? }
+            } else {
+                quote! { ctx.param_value(#name, #get_default)? }
+            };
+            match generate_process_with(process_with)? {
+                Some(process_with) => get_params.push(quote! {
+                    let #ident: #ty = {
+                        let mut value: #ty = #get_value;
+                        #process_with(&mut value);
+                        value
+                    };
+                }),
+                None => get_params.push(quote! {
+                    let #ident: #ty = #get_value;
+                }),
+            }
 
             let desc = desc
                 .as_ref()
                 .map(|s| quote! {Some(#s)})
                 .unwrap_or_else(|| quote! {None});
+            let deprecation = deprecation
+                .as_ref()
+                .map(|s| quote! {Some(#s)})
+                .unwrap_or_else(|| quote! {None});
+            let tags = generate_tags(tags);
             let schema_default = default
                 .as_ref()
                 .map(|value| {
@@ -187,9 +245,13 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
                 args.insert(#name, #crate_name::registry::MetaInputValue {
                     name: #name,
                     description: #desc,
-                    ty: <#ty as #crate_name::Type>::create_type_info(registry),
+                    ty: <#ty as #crate_name::InputValueType>::create_input_type_info(registry),
                     default_value: #schema_default,
                     validator: None,
+                    deprecation: #deprecation,
+                    is_secret: #secret,
+                    inaccessible: #inaccessible,
+                    tags: #tags,
                 });
             });
         }
@@ -240,6 +302,10 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
                 external: #external,
                 provides: #provides,
                 requires: #requires,
+                shareable: false,
+                inaccessible: #inaccessible,
+                tags: #field_tags,
+                override_from: None,
             });
         });
 
@@ -257,6 +323,18 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
         });
     }
 
+    for interface in &interface_args.implements {
+        let interface = syn::parse_str::<Type>(interface)?;
+        implements.push(quote! {
+            #crate_name::static_assertions::assert_impl_one!(#interface: #crate_name::InterfaceType);
+            <#interface as #crate_name::Type>::create_type_info(registry);
+            registry.add_implements(#gql_typename, &<#interface as #crate_name::Type>::type_name());
+        });
+    }
+
+    let inaccessible = interface_args.inaccessible;
+    let tags = generate_tags(&interface_args.tags);
+
     let introspection_type_name = if get_introspection_typename.is_empty() {
         quote! { unreachable!() }
     } else {
@@ -267,6 +345,21 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
         }
     };
 
+    let node_impl = if interface_args.node {
+        quote! {
+            #[allow(clippy::all, clippy::pedantic)]
+            #[#crate_name::async_trait::async_trait]
+            impl #generics #crate_name::relay::NodeInterface for #ident #generics {
+                async fn fetch_node(ctx: &#crate_name::Context<'_>, id: &#crate_name::relay::GlobalId) -> #crate_name::FieldResult<::std::option::Option<Self>> {
+                    #(#node_fetchers)*
+                    Ok(None)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let expanded = quote! {
         #(#type_into_impls)*
 
@@ -288,6 +381,7 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
             fn create_type_info(registry: &mut #crate_name::registry::Registry) -> String {
                 registry.create_type::<Self, _>(|registry| {
                     #(#registry_types)*
+                    #(#implements)*
 
                     #crate_name::registry::MetaType::Interface {
                         name: #gql_typename.to_string(),
@@ -304,6 +398,8 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
                         },
                         extends: #extends,
                         keys: None,
+                        inaccessible: #inaccessible,
+                        tags: #tags,
                     }
                 })
             }
@@ -336,6 +432,8 @@ pub fn generate(interface_args: &args::Interface) -> GeneratorResult<TokenStream
         }
 
         impl #generics #crate_name::InterfaceType for #ident #generics {}
+
+        #node_impl
     };
     Ok(expanded.into())
 }
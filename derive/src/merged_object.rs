@@ -82,6 +82,11 @@ pub fn generate(object_args: &args::MergedObject) -> GeneratorResult<TokenStream
                         cache_control,
                         extends: #extends,
                         keys: None,
+                        resolvable: true,
+                        shareable: false,
+                        inaccessible: false,
+                        interface_object: false,
+                        tags: ::std::default::Default::default(),
                     }
                 })
             }
@@ -80,6 +80,11 @@ pub fn generate(object_args: &args::MergedSubscription) -> GeneratorResult<Token
                         cache_control: Default::default(),
                         extends: false,
                         keys: None,
+                        resolvable: true,
+                        shareable: false,
+                        inaccessible: false,
+                        interface_object: false,
+                        tags: ::std::default::Default::default(),
                     }
                 })
             }
@@ -1,12 +1,13 @@
 use crate::args;
 use crate::output_type::OutputType;
 use crate::utils::{
-    generate_default, generate_guards, generate_post_guards, generate_validator, get_cfg_attrs,
-    get_crate_name, get_param_getter_ident, get_rustdoc, parse_graphql_attrs, remove_graphql_attrs,
-    GeneratorResult,
+    generate_default, generate_guards, generate_post_guards, generate_process_with, generate_tags,
+    generate_validator, get_cfg_attrs, get_crate_name, get_param_getter_ident, get_rustdoc,
+    get_type_argument, parse_graphql_attrs, remove_graphql_attrs, GeneratorResult,
 };
 use inflector::Inflector;
 use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::ext::IdentExt;
 use syn::{Block, Error, FnArg, ImplItem, ItemImpl, Pat, ReturnType, Type, TypeReference};
@@ -42,6 +43,7 @@ pub fn generate(
     let mut resolvers = Vec::new();
     let mut schema_fields = Vec::new();
     let mut find_entities = Vec::new();
+    let mut batch_find_entities = Vec::new();
     let mut add_keys = Vec::new();
     let mut create_entity_types = Vec::new();
 
@@ -122,7 +124,14 @@ pub fn generate(
                     method.sig.inputs.insert(1, arg);
                 }
 
-                let entity_type = ty.value_type();
+                // A lookup function returning `Vec<Option<T>>` finds a batch of entities of type `T`,
+                // each of its arguments is a `Vec` with a value for every representation.
+                let value_type = ty.value_type();
+                let batch_entity_type = get_type_argument(&value_type, "Vec")
+                    .and_then(|ty| get_type_argument(ty, "Option"))
+                    .cloned();
+                let is_batch = batch_entity_type.is_some();
+                let entity_type = batch_entity_type.unwrap_or(value_type);
                 let mut key_pat = Vec::new();
                 let mut key_getter = Vec::new();
                 let mut use_keys = Vec::new();
@@ -140,10 +149,27 @@ pub fn generate(
                 }
 
                 for (ident, ty, args::Argument { name, key, .. }) in &args {
+                    let ty = if is_batch {
+                        match get_type_argument(&Type::Path(ty.clone()), "Vec") {
+                            Some(ty) => ty.clone(),
+                            None => return Err(Error::new_spanned(
+                                ty,
+                                "The arguments of a batch entity lookup function must be `Vec`.",
+                            )
+                            .into()),
+                        }
+                    } else {
+                        Type::Path(ty.clone())
+                    };
                     let is_key = all_key || *key;
-                    let name = name
-                        .clone()
-                        .unwrap_or_else(|| ident.ident.unraw().to_string().to_camel_case());
+                    let name = name.clone().unwrap_or_else(|| {
+                        let name = ident.ident.unraw().to_string().to_camel_case();
+                        if is_batch {
+                            name.to_singular()
+                        } else {
+                            name
+                        }
+                    });
 
                     if is_key {
                         if !keys_str.is_empty() {
@@ -166,7 +192,7 @@ pub fn generate(
                         // requires
                         requires_getter.push(quote! {
                             let #ident: #ty = #crate_name::InputValueType::parse(params.get(#name).cloned()).
-                                map_err(|err| err.into_error(ctx.item.pos, <#ty as #crate_name::Type>::qualified_type_name()))?;
+                                map_err(|err| err.into_error(ctx.item.pos, <#ty as #crate_name::InputValueType>::qualified_input_type_name()))?;
                         });
                         use_keys.push(ident);
                     }
@@ -192,21 +218,65 @@ pub fn generate(
                     )
                     .expect("invalid result type");
                 }
-                let do_find = quote! { self.#field_ident(ctx, #(#use_keys),*).await.map_err(|err| err.into_error(ctx.item.pos))? };
 
-                find_entities.push((
-                    args.len(),
-                    quote! {
-                        #(#cfg_attrs)*
-                        if typename == &<#entity_type as #crate_name::Type>::type_name() {
-                            if let (#(#key_pat),*) = (#(#key_getter),*) {
-                                #(#requires_getter)*
-                                let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
-                                return #crate_name::OutputValueType::resolve(&#do_find, &ctx_obj, ctx.item).await;
+                if is_batch {
+                    let batch_values = use_keys
+                        .iter()
+                        .map(|ident| {
+                            Ident::new(&format!("__{}_values", ident.ident), Span::call_site())
+                        })
+                        .collect::<Vec<_>>();
+
+                    batch_find_entities.push((
+                        args.len(),
+                        quote! {
+                            #(#cfg_attrs)*
+                            if typename == &<#entity_type as #crate_name::Type>::type_name() {
+                                #(let mut #batch_values = ::std::vec::Vec::with_capacity(params.len());)*
+                                let mut found = true;
+                                for params in params {
+                                    let params = match params {
+                                        #crate_name::Value::Object(params) => params,
+                                        _ => return Err(#crate_name::QueryError::EntityNotFound.into_error(ctx.item.pos)),
+                                    };
+                                    if let (#(#key_pat),*) = (#(#key_getter),*) {
+                                        #(#requires_getter)*
+                                        #(#batch_values.push(#use_keys);)*
+                                    } else {
+                                        found = false;
+                                        break;
+                                    }
+                                }
+                                if found {
+                                    let values = self.#field_ident(ctx, #(#batch_values),*).await.map_err(|err| err.into_error(ctx.item.pos))?;
+                                    if values.len() != params.len() {
+                                        return Err(#crate_name::QueryError::EntityNotFound.into_error(ctx.item.pos));
+                                    }
+                                    let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
+                                    return #crate_name::futures::future::try_join_all(
+                                        values.iter().map(|value| #crate_name::OutputValueType::resolve(value, &ctx_obj, ctx.item))
+                                    ).await;
+                                }
                             }
-                        }
-                    },
-                ));
+                        },
+                    ));
+                } else {
+                    let do_find = quote! { self.#field_ident(ctx, #(#use_keys),*).await.map_err(|err| err.into_error(ctx.item.pos))? };
+
+                    find_entities.push((
+                        args.len(),
+                        quote! {
+                            #(#cfg_attrs)*
+                            if typename == &<#entity_type as #crate_name::Type>::type_name() {
+                                if let (#(#key_pat),*) = (#(#key_getter),*) {
+                                    #(#requires_getter)*
+                                    let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
+                                    return #crate_name::OutputValueType::resolve(&#do_find, &ctx_obj, ctx.item).await;
+                                }
+                            }
+                        },
+                    ));
+                }
             } else if !method_args.skip {
                 if method.sig.asyncness.is_none() {
                     return Err(Error::new_spanned(&method, "Must be asynchronous").into());
@@ -233,6 +303,13 @@ pub fn generate(
                     Some(provides) => quote! { Some(#provides) },
                     None => quote! { None },
                 };
+                let shareable = method_args.shareable;
+                let inaccessible = method_args.inaccessible;
+                let tags = generate_tags(&method_args.tags);
+                let override_from = match &method_args.override_from {
+                    Some(from) => quote! { Some(#from) },
+                    None => quote! { None },
+                };
                 let ty = match &method.sig.output {
                     ReturnType::Type(_, ty) => OutputType::parse(ty)?,
                     ReturnType::Default => {
@@ -323,6 +400,11 @@ pub fn generate(
                         default,
                         default_with,
                         validator,
+                        process_with,
+                        secret,
+                        deprecation,
+                        inaccessible,
+                        tags,
                         ..
                     },
                 ) in args
@@ -334,6 +416,11 @@ pub fn generate(
                         .as_ref()
                         .map(|s| quote! {Some(#s)})
                         .unwrap_or_else(|| quote! {None});
+                    let deprecation = deprecation
+                        .as_ref()
+                        .map(|s| quote! {Some(#s)})
+                        .unwrap_or_else(|| quote! {None});
+                    let tags = generate_tags(&tags);
                     let default = generate_default(&default, &default_with)?;
                     let schema_default = default
                         .as_ref()
@@ -354,9 +441,13 @@ pub fn generate(
                         args.insert(#name, #crate_name::registry::MetaInputValue {
                             name: #name,
                             description: #desc,
-                            ty: <#ty as #crate_name::Type>::create_type_info(registry),
+                            ty: <#ty as #crate_name::InputValueType>::create_input_type_info(registry),
                             default_value: #schema_default,
                             validator: #validator,
+                            deprecation: #deprecation,
+                            is_secret: #secret,
+                            inaccessible: #inaccessible,
+                            tags: #tags,
                         });
                     });
 
@@ -367,9 +458,24 @@ pub fn generate(
                         Some(default) => quote! { Some(|| -> #ty { #default }) },
                         None => quote! { None },
                     };
+                    let get_value = if secret {
+                        quote! { ctx.secret_param_value(#name, #default) }
+                    } else {
+                        quote! { ctx.param_value(#name, #default) }
+                    };
+                    let get_value = match generate_process_with(&process_with)? {
+                        Some(process_with) => quote! {
+                            {
+                                let mut value: #ty = #get_value?;
+                                #process_with(&mut value);
+                                Ok(value)
+                            }
+                        },
+                        None => get_value,
+                    };
                     let param_getter_name = get_param_getter_ident(&ident.ident.to_string());
                     get_params.push(quote! {
-                        let #param_getter_name = || -> #crate_name::Result<#ty> { ctx.param_value(#name, #default) };
+                        let #param_getter_name = || -> #crate_name::Result<#ty> { #get_value };
                         let #ident: #ty = #param_getter_name()?;
                     });
                 }
@@ -392,6 +498,10 @@ pub fn generate(
                         external: #external,
                         provides: #provides,
                         requires: #requires,
+                        shareable: #shareable,
+                        inaccessible: #inaccessible,
+                        tags: #tags,
+                        override_from: #override_from,
                     });
                 });
 
@@ -468,8 +578,33 @@ pub fn generate(
         }
     };
 
+    let shareable = object_args.shareable;
+    let inaccessible = object_args.inaccessible;
+    let interface_object = object_args.interface_object;
+    let tags = generate_tags(&object_args.tags);
+    let (keys, resolvable) = match &object_args.unresolvable {
+        Some(fields) => (quote! { Some(::std::vec![#fields.to_string()]) }, false),
+        None => (quote! { None }, true),
+    };
+
     find_entities.sort_by(|(a, _), (b, _)| b.cmp(a));
     let find_entities_iter = find_entities.iter().map(|(_, code)| code);
+    let find_batch_entities = if batch_find_entities.is_empty() {
+        None
+    } else {
+        batch_find_entities.sort_by(|(a, _), (b, _)| b.cmp(a));
+        let batch_find_entities_iter = batch_find_entities.iter().map(|(_, code)| code);
+        Some(quote! {
+            async fn find_entities(&self, ctx: &#crate_name::Context<'_>, params: &[#crate_name::Value]) -> #crate_name::Result<::std::vec::Vec<#crate_name::serde_json::Value>> {
+                if let Some(#crate_name::Value::Object(first)) = params.first() {
+                    if let Some(#crate_name::Value::String(typename)) = first.get("__typename") {
+                        #(#batch_find_entities_iter)*
+                    }
+                }
+                #crate_name::futures::future::try_join_all(params.iter().map(|params| self.find_entity(ctx, params))).await
+            }
+        })
+    };
 
     let expanded = quote! {
         #item_impl
@@ -491,7 +626,12 @@ pub fn generate(
                     },
                     cache_control: #cache_control,
                     extends: #extends,
-                    keys: None,
+                    keys: #keys,
+                    resolvable: #resolvable,
+                    shareable: #shareable,
+                    inaccessible: #inaccessible,
+                    interface_object: #interface_object,
+                    tags: #tags,
                 });
                 #(#create_entity_types)*
                 #(#add_keys)*
@@ -524,6 +664,8 @@ pub fn generate(
                 #(#find_entities_iter)*
                 Err(#crate_name::QueryError::EntityNotFound.into_error(ctx.item.pos))
             }
+
+            #find_batch_entities
         }
 
         #[allow(clippy::all, clippy::pedantic)]
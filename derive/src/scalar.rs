@@ -1,5 +1,5 @@
 use crate::args;
-use crate::utils::{get_crate_name, get_rustdoc, GeneratorResult};
+use crate::utils::{generate_tags, get_crate_name, get_rustdoc, GeneratorResult};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{Error, ItemImpl, Type};
@@ -24,6 +24,13 @@ pub fn generate(
     let desc = get_rustdoc(&item_impl.attrs)?
         .map(|s| quote! { Some(#s) })
         .unwrap_or_else(|| quote! {None});
+    let specified_by_url = scalar_args
+        .specified_by_url
+        .as_ref()
+        .map(|s| quote! { Some(#s) })
+        .unwrap_or_else(|| quote! {None});
+    let inaccessible = scalar_args.inaccessible;
+    let tags = generate_tags(&scalar_args.tags);
     let self_ty = &item_impl.self_ty;
     let generic = &item_impl.generics;
     let where_clause = &item_impl.generics.where_clause;
@@ -42,6 +49,9 @@ pub fn generate(
                     name: #gql_typename.to_string(),
                     description: #desc,
                     is_valid: |value| <#self_ty as #crate_name::ScalarType>::is_valid(value),
+                    specified_by_url: #specified_by_url,
+                    inaccessible: #inaccessible,
+                    tags: #tags,
                 })
             }
         }
@@ -1,6 +1,7 @@
 use crate::args;
 use crate::utils::{
-    generate_guards, generate_post_guards, get_crate_name, get_rustdoc, GeneratorResult,
+    generate_guards, generate_post_guards, generate_tags, get_crate_name, get_rustdoc,
+    GeneratorResult,
 };
 use darling::ast::Data;
 use inflector::Inflector;
@@ -38,7 +39,19 @@ pub fn generate(object_args: &args::SimpleObject) -> GeneratorResult<TokenStream
     let mut resolvers = Vec::new();
     let mut schema_fields = Vec::new();
 
+    // The attributes of the input type are only allowed with `input_name`, which derives both.
+    let is_pair = object_args.input_name.is_some();
     for field in &s.fields {
+        for (attr, name) in &[
+            (&field.default, "default"),
+            (&field.default_with, "default_with"),
+            (&field.validator, "validator"),
+            (&field.process_with, "process_with"),
+            (&field.secret, "secret"),
+            (&field.input_deprecation, "input_deprecation"),
+        ] {
+            attr.check(name, "InputObject", is_pair)?;
+        }
         if field.skip {
             continue;
         }
@@ -68,6 +81,13 @@ pub fn generate(object_args: &args::SimpleObject) -> GeneratorResult<TokenStream
             Some(provides) => quote! { Some(#provides) },
             None => quote! { None },
         };
+        let shareable = field.shareable;
+        let inaccessible = field.inaccessible;
+        let tags = generate_tags(&field.tags);
+        let override_from = match &field.override_from {
+            Some(from) => quote! { Some(#from) },
+            None => quote! { None },
+        };
         let vis = &field.vis;
         let ty = &field.ty;
 
@@ -93,6 +113,10 @@ pub fn generate(object_args: &args::SimpleObject) -> GeneratorResult<TokenStream
                 external: #external,
                 provides: #provides,
                 requires: #requires,
+                shareable: #shareable,
+                inaccessible: #inaccessible,
+                tags: #tags,
+                override_from: #override_from,
             });
         });
 
@@ -148,6 +172,15 @@ pub fn generate(object_args: &args::SimpleObject) -> GeneratorResult<TokenStream
         }
     };
 
+    let shareable = object_args.shareable;
+    let inaccessible = object_args.inaccessible;
+    let interface_object = object_args.interface_object;
+    let tags = generate_tags(&object_args.tags);
+    let (keys, resolvable) = match &object_args.unresolvable {
+        Some(fields) => (quote! { Some(::std::vec![#fields.to_string()]) }, false),
+        None => (quote! { None }, true),
+    };
+
     let expanded = quote! {
         #[allow(clippy::all, clippy::pedantic)]
         impl #generics #ident #where_clause {
@@ -171,7 +204,12 @@ pub fn generate(object_args: &args::SimpleObject) -> GeneratorResult<TokenStream
                     },
                     cache_control: #cache_control,
                     extends: #extends,
-                    keys: None,
+                    keys: #keys,
+                    resolvable: #resolvable,
+                    shareable: #shareable,
+                    inaccessible: #inaccessible,
+                    interface_object: #interface_object,
+                    tags: #tags,
                 })
             }
         }
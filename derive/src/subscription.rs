@@ -2,9 +2,9 @@ use crate::args;
 use crate::args::SubscriptionField;
 use crate::output_type::OutputType;
 use crate::utils::{
-    generate_default, generate_guards, generate_validator, get_cfg_attrs, get_crate_name,
-    get_param_getter_ident, get_rustdoc, parse_graphql_attrs, remove_graphql_attrs,
-    GeneratorResult,
+    generate_default, generate_guards, generate_process_with, generate_tags, generate_validator,
+    get_cfg_attrs, get_crate_name, get_param_getter_ident, get_rustdoc, parse_graphql_attrs,
+    remove_graphql_attrs, GeneratorResult,
 };
 use inflector::Inflector;
 use proc_macro::TokenStream;
@@ -155,6 +155,11 @@ pub fn generate(
                     default,
                     default_with,
                     validator,
+                    process_with,
+                    secret,
+                    deprecation,
+                    inaccessible,
+                    tags,
                 },
             ) in args
             {
@@ -165,6 +170,11 @@ pub fn generate(
                     .as_ref()
                     .map(|s| quote! {Some(#s)})
                     .unwrap_or_else(|| quote! {None});
+                let deprecation = deprecation
+                    .as_ref()
+                    .map(|s| quote! {Some(#s)})
+                    .unwrap_or_else(|| quote! {None});
+                let tags = generate_tags(&tags);
                 let default = generate_default(&default, &default_with)?;
 
                 let validator = match &validator {
@@ -186,9 +196,13 @@ pub fn generate(
                     args.insert(#name, #crate_name::registry::MetaInputValue {
                         name: #name,
                         description: #desc,
-                        ty: <#ty as #crate_name::Type>::create_type_info(registry),
+                        ty: <#ty as #crate_name::InputValueType>::create_input_type_info(registry),
                         default_value: #schema_default,
                         validator: #validator,
+                        deprecation: #deprecation,
+                        is_secret: #secret,
+                        inaccessible: #inaccessible,
+                        tags: #tags,
                     });
                 });
 
@@ -198,10 +212,25 @@ pub fn generate(
                     Some(default) => quote! { Some(|| -> #ty { #default }) },
                     None => quote! { None },
                 };
+                let get_value = if secret {
+                    quote! { ctx.secret_param_value(#name, #default) }
+                } else {
+                    quote! { ctx.param_value(#name, #default) }
+                };
+                let get_value = match generate_process_with(&process_with)? {
+                    Some(process_with) => quote! {
+                        {
+                            let mut value: #ty = #get_value?;
+                            #process_with(&mut value);
+                            Ok(value)
+                        }
+                    },
+                    None => get_value,
+                };
                 let param_getter_name = get_param_getter_ident(&ident.ident.to_string());
                 get_params.push(quote! {
-                    let #param_getter_name = || -> #crate_name::Result<#ty> { ctx.param_value(#name, #default) };
-                    let #ident: #ty = ctx.param_value(#name, #default)?;
+                    let #param_getter_name = || -> #crate_name::Result<#ty> { #get_value };
+                    let #ident: #ty = #param_getter_name()?;
                 });
             }
 
@@ -242,6 +271,10 @@ pub fn generate(
                     external: false,
                     requires: None,
                     provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: ::std::default::Default::default(),
+                    override_from: None,
                 });
             });
 
@@ -258,7 +291,25 @@ pub fn generate(
                 None => None,
             };
             let guard = guard.map(|guard| quote! {
-                #guard.check(ctx).await.map_err(|err| err.into_error_with_path(ctx.item.pos, ctx.path_node.as_ref()))?;
+                #guard.check(ctx).await.map_err(|err| err.into_error_with_path(ctx.item.pos, ctx.path_node.as_ref()))
+            });
+            // The guard is checked again for every event, so the stream ends with an error as
+            // soon as it fails.
+            let event_guard = guard.as_ref().map(|guard| {
+                quote! {
+                    let res = #guard;
+                    if let Err(err) = res {
+                        return Some(Err(err));
+                    }
+                }
+            });
+            let guard = guard.map(|guard| quote! { #guard?; });
+            let filter = generate_process_with(&field.filter)?.map(|filter| {
+                quote! {
+                    if !#filter(ctx, &msg) {
+                        return None;
+                    }
+                }
             });
             if field.post_guard.is_some() {
                 return Err(Error::new_spanned(
@@ -277,7 +328,7 @@ pub fn generate(
                 let pos = ctx.item.pos;
                 let schema_env = ctx.schema_env.clone();
                 let query_env = ctx.query_env.clone();
-                let stream = #crate_name::futures::StreamExt::then(#create_field_stream, {
+                let stream = #crate_name::futures::StreamExt::filter_map(#create_field_stream, {
                     let field_name = field_name.clone();
                     move |msg| {
                         let schema_env = schema_env.clone();
@@ -285,6 +336,9 @@ pub fn generate(
                         let field = field.clone();
                         let field_name = field_name.clone();
                         async move {
+                            #event_guard
+                            #filter
+
                             let resolve_id = #crate_name::ResolveId {
                                 parent: Some(0),
                                 current: 1,
@@ -302,20 +356,23 @@ pub fn generate(
                             );
                             let ctx_extension = #crate_name::extensions::ExtensionContext {
                                 schema_data: &schema_env.data,
+                                registry: &schema_env.registry,
                                 query_data: &query_env.ctx_data,
                             };
 
-                            #crate_name::extensions::Extension::execution_start(&mut *query_env.extensions.lock(), &ctx_extension);
+                            query_env.extensions.execution_start(&ctx_extension);
 
                             #[allow(bare_trait_objects)]
-                            let ri = #crate_name::extensions::ResolveInfo {
+                            let return_type = <<#stream_ty as #crate_name::futures::stream::Stream>::Item as #crate_name::Type>::qualified_type_name();
+                            let ri = #crate_name::extensions::ResolveInfo::new(
                                 resolve_id,
-                                path_node: ctx_selection_set.path_node.as_ref().unwrap(),
-                                parent_type: #gql_typename,
-                                return_type: &<<#stream_ty as #crate_name::futures::stream::Stream>::Item as #crate_name::Type>::qualified_type_name(),
-                            };
+                                ctx_selection_set.path_node.as_ref().unwrap(),
+                                #gql_typename,
+                                field.node.name.node.as_str(),
+                                &return_type,
+                            );
 
-                            #crate_name::extensions::Extension::resolve_start(&mut *query_env.extensions.lock(), &ctx_extension, &ri);
+                            let resolve_states = query_env.extensions.resolve_start(&ctx_extension, &ri);
 
                             let res = #crate_name::OutputValueType::resolve(&msg, &ctx_selection_set, &*field)
                                 .await
@@ -325,9 +382,9 @@ pub fn generate(
                                     })
                                 });
 
-                            #crate_name::extensions::Extension::resolve_end(&mut *query_env.extensions.lock(), &ctx_extension, &ri);
-                            #crate_name::extensions::Extension::execution_end(&mut *query_env.extensions.lock(), &ctx_extension);
-                            res
+                            query_env.extensions.resolve_end(&ctx_extension, &ri, resolve_states);
+                            query_env.extensions.execution_end(&ctx_extension);
+                            Some(res)
                         }
                     }
                 });
@@ -383,6 +440,11 @@ pub fn generate(
                     cache_control: ::std::default::Default::default(),
                     extends: false,
                     keys: None,
+                    resolvable: true,
+                    shareable: false,
+                    inaccessible: false,
+                    interface_object: false,
+                    tags: ::std::default::Default::default(),
                 })
             }
         }
@@ -1,5 +1,5 @@
 use crate::args;
-use crate::utils::{get_crate_name, get_rustdoc, GeneratorResult};
+use crate::utils::{generate_tags, get_crate_name, get_rustdoc, GeneratorResult};
 use darling::ast::{Data, Style};
 use proc_macro::TokenStream;
 use quote::quote;
@@ -24,6 +24,8 @@ pub fn generate(union_args: &args::Union) -> GeneratorResult<TokenStream> {
     let desc = get_rustdoc(&union_args.attrs)?
         .map(|s| quote! { Some(#s) })
         .unwrap_or_else(|| quote! {None});
+    let inaccessible = union_args.inaccessible;
+    let tags = generate_tags(&union_args.tags);
 
     let mut registry_types = Vec::new();
     let mut possible_types = Vec::new();
@@ -150,7 +152,9 @@ pub fn generate(union_args: &args::Union) -> GeneratorResult<TokenStream> {
                             let mut possible_types = #crate_name::indexmap::IndexSet::new();
                             #(#possible_types)*
                             possible_types
-                        }
+                        },
+                        inaccessible: #inaccessible,
+                        tags: #tags,
                     }
                 })
             }
@@ -3,7 +3,10 @@ use darling::FromMeta;
 use proc_macro2::{Span, TokenStream, TokenTree};
 use proc_macro_crate::crate_name;
 use quote::quote;
-use syn::{Attribute, Error, Expr, Ident, Lit, LitStr, Meta, NestedMeta};
+use syn::{
+    Attribute, Error, Expr, GenericArgument, Ident, Lit, LitStr, Meta, NestedMeta, PathArguments,
+    Type,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -292,6 +295,40 @@ pub fn generate_default(
     }
 }
 
+pub fn generate_process_with(
+    process_with: &Option<String>,
+) -> GeneratorResult<Option<TokenStream>> {
+    match process_with {
+        Some(process_with) => {
+            let expr = syn::parse_str::<Expr>(process_with)?;
+            Ok(Some(quote! { #expr }))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn generate_tags(tags: &[String]) -> TokenStream {
+    quote! { ::std::vec![#(#tags),*] }
+}
+
+/// Returns `T` if `ty` is `Name<T>`.
+pub fn get_type_argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
 pub fn get_param_getter_ident(name: &str) -> Ident {
     Ident::new(&format!("__{}_getter", name), Span::call_site())
 }
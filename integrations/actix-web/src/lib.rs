@@ -1,8 +1,10 @@
 //! Async-graphql integration with Actix-web
 #![forbid(unsafe_code)]
 
+mod sse;
 mod subscription;
 
+pub use sse::{sse_error, SseResponse, SseToken};
 pub use subscription::WSSubscription;
 
 use actix_web::dev::{Payload, PayloadStream};
@@ -0,0 +1,152 @@
+use actix_web::dev::{Payload, PayloadStream};
+use actix_web::http::StatusCode;
+use actix_web::{web, Error, FromRequest, HttpRequest, HttpResponse, Responder, Result};
+use async_graphql::http::{SseError, SseEvent, SSE_CONTENT_TYPE, SSE_TOKEN_HEADER};
+use futures::future::{self, Ready};
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+
+/// Responder for a GraphQL over SSE event stream.
+///
+/// # Examples
+///
+/// ```no_run
+/// use actix_web::{web, App, HttpServer};
+/// use async_graphql::*;
+/// use async_graphql::http::sse_stream;
+/// use async_graphql_actix_web::{Request, SseResponse};
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// type MySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+///
+/// async fn index_sse(schema: web::Data<MySchema>, req: Request) -> impl actix_web::Responder {
+///     SseResponse(sse_stream(&schema, req.into_inner()))
+/// }
+///
+/// #[actix_rt::main]
+/// async fn main() -> std::io::Result<()> {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     HttpServer::new(move || {
+///         App::new()
+///             .data(schema.clone())
+///             .service(web::resource("/sse").route(web::post().to(index_sse)))
+///     })
+///     .bind("127.0.0.1:8000")?
+///     .run()
+///     .await
+/// }
+/// ```
+pub struct SseResponse<S>(pub S);
+
+impl<S> Responder for SseResponse<S>
+where
+    S: Stream<Item = SseEvent> + 'static,
+{
+    type Error = Error;
+    type Future = Ready<Result<HttpResponse>>;
+
+    fn respond_to(self, _req: &HttpRequest) -> Self::Future {
+        future::ok(
+            HttpResponse::build(StatusCode::OK)
+                .content_type(SSE_CONTENT_TYPE)
+                .header("cache-control", "no-cache")
+                .streaming(Box::pin(
+                    self.0
+                        .map(|event| Ok::<_, Error>(web::Bytes::from(event.to_string()))),
+                )),
+        )
+    }
+}
+
+/// Extractor for the stream token of the single connection mode of GraphQL over SSE.
+///
+/// The token is read from the `x-graphql-event-stream-token` header or from the `token` query
+/// parameter, the request is rejected with `404 Not Found` without a token.
+///
+/// # Examples
+///
+/// ```no_run
+/// use actix_web::{web, HttpResponse, Result};
+/// use async_graphql::*;
+/// use async_graphql::http::SseConnections;
+/// use async_graphql_actix_web::{sse_error, Request, SseResponse, SseToken};
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// type MySchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+///
+/// async fn reserve(connections: web::Data<SseConnections>) -> HttpResponse {
+///     HttpResponse::Created().body(connections.reserve())
+/// }
+///
+/// async fn connect(
+///     connections: web::Data<SseConnections>,
+///     token: SseToken,
+/// ) -> Result<SseResponse<impl futures::Stream<Item = http::SseEvent>>> {
+///     Ok(SseResponse(connections.connect(&token.0).map_err(sse_error)?))
+/// }
+///
+/// async fn execute(
+///     schema: web::Data<MySchema>,
+///     connections: web::Data<SseConnections>,
+///     token: SseToken,
+///     req: Request,
+/// ) -> Result<HttpResponse> {
+///     connections
+///         .execute(&schema, &token.0, req.into_inner())
+///         .map_err(sse_error)?;
+///     Ok(HttpResponse::Accepted().finish())
+/// }
+///
+/// let resource = web::resource("/graphql/stream")
+///     .route(web::put().to(reserve))
+///     .route(web::get().to(connect))
+///     .route(web::post().to(execute));
+/// ```
+pub struct SseToken(pub String);
+
+impl FromRequest for SseToken {
+    type Error = Error;
+    type Future = Ready<Result<SseToken>>;
+    type Config = ();
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload<PayloadStream>) -> Self::Future {
+        let token = req
+            .headers()
+            .get(SSE_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string)
+            .or_else(|| {
+                web::Query::<HashMap<String, String>>::from_query(req.query_string())
+                    .ok()
+                    .and_then(|query| query.into_inner().remove("token"))
+            });
+        future::ready(
+            token
+                .map(SseToken)
+                .ok_or_else(|| actix_web::error::ErrorNotFound("Missing event stream token")),
+        )
+    }
+}
+
+/// Convert an error of the single connection mode of GraphQL over SSE to an actix-web error with
+/// the status code of the protocol.
+pub fn sse_error(err: SseError) -> Error {
+    let status = StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    actix_web::error::InternalError::new(err, status).into()
+}
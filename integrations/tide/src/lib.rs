@@ -8,6 +8,10 @@
 #![allow(clippy::needless_doctest_main)]
 #![forbid(unsafe_code)]
 
+mod sse;
+
+pub use sse::{sse_endpoint, SseEndpoint};
+
 use async_graphql::http::MultipartOptions;
 use async_graphql::{ObjectType, ParseRequestError, Schema, SubscriptionType};
 use async_trait::async_trait;
@@ -63,7 +67,7 @@ impl<Query, Mutation, Subscription> Clone for Endpoint<Query, Mutation, Subscrip
     fn clone(&self) -> Self {
         Self {
             schema: self.schema.clone(),
-            opts: self.opts,
+            opts: self.opts.clone(),
             batch: self.batch,
         }
     }
@@ -82,9 +86,9 @@ where
         respond(
             self.schema
                 .execute_batch(if self.batch {
-                    receive_batch_request_opts(request, self.opts).await
+                    receive_batch_request_opts(request, self.opts.clone()).await
                 } else {
-                    receive_request_opts(request, self.opts)
+                    receive_request_opts(request, self.opts.clone())
                         .await
                         .map(Into::into)
                 }?)
@@ -0,0 +1,181 @@
+use crate::receive_request_opts;
+use async_graphql::http::{
+    MultipartOptions, SseConnections, SseError, SseEvent, SSE_CONTENT_TYPE, SSE_TOKEN_HEADER,
+};
+use async_graphql::{ObjectType, Schema, SubscriptionType};
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use tide::http::{headers, Method};
+use tide::{Body, Request, Response, StatusCode};
+
+/// Create a new GraphQL over SSE endpoint with the schema.
+///
+/// The endpoint is in the distinct connections mode, use
+/// [`SseEndpoint::single_connection`](struct.SseEndpoint.html#method.single_connection) to switch
+/// to the single connection mode.
+pub fn sse_endpoint<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+) -> SseEndpoint<Query, Mutation, Subscription> {
+    SseEndpoint {
+        schema,
+        opts: MultipartOptions::default(),
+        connections: None,
+    }
+}
+
+/// A GraphQL over SSE endpoint.
+///
+/// This is created with the [`sse_endpoint`](fn.sse_endpoint.html) function.
+///
+/// In the distinct connections mode it responds to each `GET` or `POST` GraphQL request with a
+/// `text/event-stream` of its responses. In the single connection mode it handles the `PUT`,
+/// `GET`, `POST` and `DELETE` requests of the protocol as described in
+/// `async_graphql::http::SseConnections`.
+#[non_exhaustive]
+pub struct SseEndpoint<Query, Mutation, Subscription> {
+    /// The schema of the endpoint.
+    pub schema: Schema<Query, Mutation, Subscription>,
+    /// The multipart options of the endpoint.
+    pub opts: MultipartOptions,
+    /// The connections of the single connection mode.
+    pub connections: Option<SseConnections>,
+}
+
+impl<Query, Mutation, Subscription> SseEndpoint<Query, Mutation, Subscription> {
+    /// Set the multipart options of the endpoint.
+    #[must_use]
+    pub fn multipart_opts(self, opts: MultipartOptions) -> Self {
+        Self { opts, ..self }
+    }
+
+    /// Use the single connection mode with the connections.
+    #[must_use]
+    pub fn single_connection(self, connections: SseConnections) -> Self {
+        Self {
+            connections: Some(connections),
+            ..self
+        }
+    }
+}
+
+// Manual impl to remove bounds on generics
+impl<Query, Mutation, Subscription> Clone for SseEndpoint<Query, Mutation, Subscription> {
+    fn clone(&self) -> Self {
+        Self {
+            schema: self.schema.clone(),
+            opts: self.opts,
+            connections: self.connections.clone(),
+        }
+    }
+}
+
+/// The body of tide must be `Sync`, the stream is only polled through `&mut` so the mutex is
+/// never locked.
+struct SyncStream(Mutex<BoxStream<'static, io::Result<String>>>);
+
+impl Stream for SyncStream {
+    type Item = io::Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.0.get_mut() {
+            Ok(stream) => stream.poll_next_unpin(cx),
+            Err(_) => Poll::Ready(None),
+        }
+    }
+}
+
+fn event_stream(stream: impl Stream<Item = SseEvent> + Send + 'static) -> Response {
+    let stream = SyncStream(Mutex::new(
+        stream.map(|event| Ok(event.to_string())).boxed(),
+    ));
+    let mut response = Response::new(StatusCode::Ok);
+    response.insert_header(headers::CONTENT_TYPE, SSE_CONTENT_TYPE);
+    response.insert_header(headers::CACHE_CONTROL, "no-cache");
+    response.set_body(Body::from_reader(stream.into_async_read(), None));
+    response
+}
+
+fn error_response(err: SseError) -> tide::Result {
+    let status = StatusCode::try_from(err.status_code()).unwrap_or(StatusCode::BadRequest);
+    Err(tide::Error::new(status, err))
+}
+
+fn sse_token<State>(request: &Request<State>) -> tide::Result<String> {
+    if let Some(token) = request
+        .header(SSE_TOKEN_HEADER)
+        .and_then(|values| values.get(0))
+    {
+        return Ok(token.as_str().to_string());
+    }
+    request
+        .query::<HashMap<String, String>>()
+        .ok()
+        .and_then(|mut query| query.remove("token"))
+        .ok_or_else(|| tide::Error::from_str(StatusCode::NotFound, "Missing event stream token"))
+}
+
+#[async_trait]
+impl<Query, Mutation, Subscription, TideState> tide::Endpoint<TideState>
+    for SseEndpoint<Query, Mutation, Subscription>
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+    TideState: Clone + Send + Sync + 'static,
+{
+    async fn call(&self, request: Request<TideState>) -> tide::Result {
+        let connections = match &self.connections {
+            Some(connections) => connections,
+            None => {
+                let request = receive_request_opts(request, self.opts).await?;
+                return Ok(event_stream(async_graphql::http::sse_stream(
+                    &self.schema,
+                    request,
+                )));
+            }
+        };
+
+        match request.method() {
+            Method::Put => {
+                let mut response = Response::new(StatusCode::Created);
+                response.set_body(connections.reserve());
+                Ok(response)
+            }
+            Method::Get => match connections.connect(&sse_token(&request)?) {
+                Ok(stream) => Ok(event_stream(stream)),
+                Err(err) => error_response(err),
+            },
+            Method::Post => {
+                let token = sse_token(&request)?;
+                let request = receive_request_opts(request, self.opts).await?;
+                match connections.execute(&self.schema, &token, request) {
+                    Ok(()) => Ok(Response::new(StatusCode::Accepted)),
+                    Err(err) => error_response(err),
+                }
+            }
+            Method::Delete => {
+                let token = sse_token(&request)?;
+                let res = match request
+                    .query::<HashMap<String, String>>()
+                    .ok()
+                    .and_then(|mut query| query.remove("operationId"))
+                {
+                    Some(operation_id) => connections.stop(&token, &operation_id),
+                    None => Err(SseError::MissingOperationId),
+                };
+                match res {
+                    Ok(()) => Ok(Response::new(StatusCode::Ok)),
+                    Err(err) => error_response(err),
+                }
+            }
+            _ => Ok(Response::new(StatusCode::MethodNotAllowed)),
+        }
+    }
+}
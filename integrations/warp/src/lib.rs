@@ -7,9 +7,11 @@
 mod batch_request;
 mod error;
 mod request;
+mod sse;
 mod subscription;
 
 pub use batch_request::{graphql_batch, graphql_batch_opts, BatchResponse};
 pub use error::BadRequest;
 pub use request::{graphql, graphql_opts, Response};
+pub use sse::{graphql_sse, graphql_sse_single_connection};
 pub use subscription::{graphql_subscription, graphql_subscription_with_data};
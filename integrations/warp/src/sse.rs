@@ -0,0 +1,183 @@
+use crate::graphql;
+use async_graphql::http::{SseConnections, SseError, SseEvent, SSE_CONTENT_TYPE, SSE_TOKEN_HEADER};
+use async_graphql::{ObjectType, Schema, SubscriptionType};
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use warp::http::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use warp::http::StatusCode;
+use warp::reply::Response as WarpResponse;
+use warp::{Filter, Rejection, Reply};
+
+fn event_stream(stream: impl Stream<Item = SseEvent> + Send + 'static) -> WarpResponse {
+    let mut resp = WarpResponse::new(hyper::Body::wrap_stream(
+        stream.map(|event| Ok::<_, Infallible>(event.to_string())),
+    ));
+    resp.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(SSE_CONTENT_TYPE));
+    resp.headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    resp
+}
+
+fn error_response(err: SseError) -> WarpResponse {
+    let status = StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
+    warp::reply::with_status(err.to_string(), status).into_response()
+}
+
+/// GraphQL over SSE filter, in the distinct connections mode.
+///
+/// It responds to each `GET` or `POST` GraphQL request with a `text/event-stream` of its
+/// responses.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use async_graphql_warp::*;
+/// use futures::{Stream, StreamExt};
+/// use std::time::Duration;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {}
+///
+/// struct SubscriptionRoot;
+///
+/// #[Subscription]
+/// impl SubscriptionRoot {
+///     async fn tick(&self) -> impl Stream<Item = String> {
+///         tokio::time::interval(Duration::from_secs(1)).map(|n| format!("{}", n.elapsed().as_secs_f32()))
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+///     let filter = async_graphql_warp::graphql_sse(schema);
+///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
+/// }
+/// ```
+pub fn graphql_sse<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+) -> impl Filter<Extract = (WarpResponse,), Error = Rejection> + Clone
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    graphql(schema).map(
+        |(schema, request): (
+            Schema<Query, Mutation, Subscription>,
+            async_graphql::Request,
+        )| event_stream(async_graphql::http::sse_stream(&schema, request)),
+    )
+}
+
+/// The stream token, from the header or from the `token` query parameter.
+fn sse_token() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::<String>(SSE_TOKEN_HEADER)
+        .or(warp::query::<HashMap<String, String>>().and_then(
+            |mut query: HashMap<String, String>| async move {
+                query.remove("token").ok_or_else(warp::reject::not_found)
+            },
+        ))
+        .unify()
+}
+
+/// GraphQL over SSE filter, in the single connection mode.
+///
+/// It handles the `PUT`, `GET`, `POST` and `DELETE` requests of the protocol as described in
+/// `async_graphql::http::SseConnections`.
+///
+/// ```no_run
+/// use async_graphql::*;
+/// use async_graphql::http::SseConnections;
+/// use async_graphql_warp::*;
+/// use warp::Filter;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let filter = warp::path!("graphql" / "stream")
+///         .and(async_graphql_warp::graphql_sse_single_connection(schema, SseConnections::new()));
+///     warp::serve(filter).run(([0, 0, 0, 0], 8000)).await;
+/// }
+/// ```
+pub fn graphql_sse_single_connection<Query, Mutation, Subscription>(
+    schema: Schema<Query, Mutation, Subscription>,
+    connections: SseConnections,
+) -> impl Filter<Extract = (WarpResponse,), Error = Rejection> + Clone
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    let connections = warp::any().map(move || connections.clone());
+
+    let reserve = warp::put()
+        .and(connections.clone())
+        .map(|connections: SseConnections| {
+            warp::reply::with_status(connections.reserve(), StatusCode::CREATED).into_response()
+        });
+
+    let connect = warp::get().and(sse_token()).and(connections.clone()).map(
+        |token: String, connections: SseConnections| match connections.connect(&token) {
+            Ok(stream) => event_stream(stream),
+            Err(err) => error_response(err),
+        },
+    );
+
+    let execute = warp::post()
+        .and(sse_token())
+        .and(graphql(schema))
+        .and(connections.clone())
+        .map(
+            |token: String,
+             (schema, request): (
+                Schema<Query, Mutation, Subscription>,
+                async_graphql::Request,
+            ),
+             connections: SseConnections| {
+                match connections.execute(&schema, &token, request) {
+                    Ok(()) => StatusCode::ACCEPTED.into_response(),
+                    Err(err) => error_response(err),
+                }
+            },
+        );
+
+    let stop = warp::delete()
+        .and(sse_token())
+        .and(warp::query::<HashMap<String, String>>())
+        .and(connections)
+        .map(
+            |token: String, query: HashMap<String, String>, connections: SseConnections| {
+                let res = match query.get("operationId") {
+                    Some(operation_id) => connections.stop(&token, operation_id),
+                    None => Err(SseError::MissingOperationId),
+                };
+                match res {
+                    Ok(()) => StatusCode::OK.into_response(),
+                    Err(err) => error_response(err),
+                }
+            },
+        );
+
+    reserve
+        .or(connect)
+        .unify()
+        .or(execute)
+        .unify()
+        .or(stop)
+        .unify()
+}
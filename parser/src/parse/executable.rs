@@ -431,4 +431,19 @@ mod tests {
         assert!(parse_query(query_ok).is_ok());
         assert!(parse_query(query_overflow).is_ok());
     }
+
+    #[test]
+    fn test_get_operation() {
+        let doc = parse_query("{ a }").unwrap();
+        assert!(doc.operations.get(None).is_some());
+        assert!(doc.operations.get(Some("A")).is_none());
+
+        let doc = parse_query("query A { a } query B { b }").unwrap();
+        assert!(doc.operations.get(None).is_none());
+        assert!(doc.operations.get(Some("B")).is_some());
+        assert!(doc.operations.get(Some("C")).is_none());
+
+        let mut doc = parse_query("query A { a }").unwrap();
+        assert!(doc.operations.get_mut(None).is_some());
+    }
 }
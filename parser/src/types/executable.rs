@@ -33,6 +33,39 @@ impl DocumentOperations {
             Self::Multiple(ops) => OperationsIterInner::Multiple(ops.iter()),
         })
     }
+
+    /// Get the operation that is executed for the operation name.
+    ///
+    /// Without a name this is the only operation of the document, with a name it is the named
+    /// operation of a document containing many operations.
+    #[must_use]
+    pub fn get(&self, name: Option<&str>) -> Option<&Positioned<OperationDefinition>> {
+        match self {
+            Self::Single(op) if name.is_none() => Some(op),
+            Self::Single(_) => None,
+            Self::Multiple(ops) => match name {
+                Some(name) => ops.get(name),
+                None if ops.len() == 1 => ops.values().next(),
+                None => None,
+            },
+        }
+    }
+
+    /// Get the operation that is executed for the operation name mutably.
+    ///
+    /// See [`get`](#method.get).
+    #[must_use]
+    pub fn get_mut(&mut self, name: Option<&str>) -> Option<&mut Positioned<OperationDefinition>> {
+        match self {
+            Self::Single(op) if name.is_none() => Some(op),
+            Self::Single(_) => None,
+            Self::Multiple(ops) => match name {
+                Some(name) => ops.get_mut(name),
+                None if ops.len() == 1 => ops.values_mut().next(),
+                None => None,
+            },
+        }
+    }
 }
 
 // TODO: This is not implemented as I would like to later implement IntoIterator for
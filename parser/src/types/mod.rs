@@ -10,12 +10,14 @@ use crate::pos::Positioned;
 use serde::de::{Deserializer, Error as _, Unexpected};
 use serde::ser::{Error as _, Serializer};
 use serde::{Deserialize, Serialize};
+use std::any::Any;
 use std::borrow::Borrow;
 use std::collections::{hash_map, BTreeMap, HashMap};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::{self, Display, Formatter, Write};
 use std::fs::File;
 use std::ops::Deref;
+use std::sync::Arc;
 
 pub use executable::*;
 pub use serde_json::Number;
@@ -397,7 +399,34 @@ pub struct UploadValue {
     /// The content type of the file.
     pub content_type: Option<String>,
     /// The file data.
-    pub content: File,
+    ///
+    /// This was a `File` before streaming uploads, the file is now in `UploadContent::File`.
+    pub content: UploadContent,
+}
+
+/// The data of a file upload.
+///
+/// More ways to receive a file may be added, the other variants must be handled by a match.
+#[non_exhaustive]
+pub enum UploadContent {
+    /// The file was received before the request is executed, and spooled to this file.
+    File(File),
+    /// The file is streamed while the request is executed, the value is defined by the server.
+    Stream(Arc<dyn Any + Send + Sync>),
+}
+
+impl UploadContent {
+    /// Attempt to clone the upload content, a stream is shared by its clones.
+    ///
+    /// # Errors
+    ///
+    /// Fails if cloning the inner `File` fails.
+    pub fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            UploadContent::File(file) => Ok(UploadContent::File(file.try_clone()?)),
+            UploadContent::Stream(stream) => Ok(UploadContent::Stream(stream.clone())),
+        }
+    }
 }
 
 impl UploadValue {
@@ -31,6 +31,24 @@ pub trait Type {
 
 /// Represents a GraphQL input value
 pub trait InputValueType: Type + Sized {
+    /// Type name in input positions.
+    ///
+    /// It is the same as `Type::type_name` unless the type is also used as an output type under
+    /// another name, see the `input_name` attribute of `InputObject`.
+    fn input_type_name() -> Cow<'static, str> {
+        Self::type_name()
+    }
+
+    /// Qualified typename in input positions.
+    fn qualified_input_type_name() -> String {
+        format!("{}!", Self::input_type_name())
+    }
+
+    /// Create type information for input positions in the registry and return qualified typename.
+    fn create_input_type_info(registry: &mut registry::Registry) -> String {
+        Self::create_type_info(registry)
+    }
+
     /// Parse from `Value`. None represents undefined.
     fn parse(value: Option<Value>) -> InputValueResult<Self>;
 
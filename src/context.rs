@@ -1,5 +1,6 @@
 use crate::base::Type;
-use crate::extensions::Extensions;
+use crate::extensions::{Extensions, Middleware};
+use crate::incremental::{Incremental, InitialValue};
 use crate::parser::types::{
     Directive, Field, FragmentDefinition, Name, OperationDefinition, SelectionSet,
     Value as InputValue,
@@ -7,15 +8,16 @@ use crate::parser::types::{
 use crate::schema::SchemaEnv;
 use crate::{FieldResult, InputValueType, Lookahead, Pos, Positioned, QueryError, Result, Value};
 use fnv::FnvHashMap;
+use futures::channel::mpsc;
 use serde::ser::{SerializeSeq, Serializer};
 use serde::{Deserialize, Serialize};
 use std::any::{Any, TypeId};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::ops::Deref;
 use std::sync::atomic::AtomicUsize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Variables of a query.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -246,15 +248,28 @@ pub struct ContextBase<'a, T> {
     pub schema_env: &'a SchemaEnv,
     #[doc(hidden)]
     pub query_env: &'a QueryEnv,
+    /// Whether `@defer` and `@stream` apply, they do not within a deferred fragment or a streamed
+    /// item.
+    pub(crate) incremental: bool,
+    /// Receives the value of the container or the list of this context if it is resolved before
+    /// its deferred fragments and streamed items.
+    pub(crate) initial_value: Option<Arc<InitialValue>>,
 }
 
 #[doc(hidden)]
 pub struct QueryEnvInner {
-    pub extensions: spin::Mutex<Extensions>,
+    pub extensions: Arc<Extensions>,
+    pub middlewares: Arc<[Box<dyn Middleware>]>,
+    /// The indexes of the middlewares that use the resolve hook.
+    pub resolve_middlewares: Vec<usize>,
     pub variables: Variables,
     pub operation: Positioned<OperationDefinition>,
     pub fragments: HashMap<Name, Positioned<FragmentDefinition>>,
     pub ctx_data: Arc<Data>,
+    /// The types and the `Type:id` keys resolved by a live query.
+    pub live_dependencies: Option<Mutex<HashSet<String>>>,
+    /// Receives the deferred fragments and the streamed items of `Schema::execute_incremental`.
+    pub incremental: Option<mpsc::UnboundedSender<Incremental>>,
 }
 
 #[doc(hidden)]
@@ -291,6 +306,8 @@ impl QueryEnv {
             item,
             schema_env,
             query_env: self,
+            incremental: false,
+            initial_value: None,
         }
     }
 }
@@ -323,6 +340,8 @@ impl<'a, T> ContextBase<'a, T> {
             inc_resolve_id: self.inc_resolve_id,
             schema_env: self.schema_env,
             query_env: self.query_env,
+            incremental: self.incremental,
+            initial_value: None,
         }
     }
 
@@ -338,6 +357,8 @@ impl<'a, T> ContextBase<'a, T> {
             inc_resolve_id: &self.inc_resolve_id,
             schema_env: self.schema_env,
             query_env: self.query_env,
+            incremental: self.incremental,
+            initial_value: self.initial_value.clone(),
         }
     }
 
@@ -373,6 +394,14 @@ impl<'a, T> ContextBase<'a, T> {
             .and_then(|d| d.downcast_ref::<D>())
     }
 
+    /// Executes again the live queries that resolved an object of the type, for the key `Type`, or
+    /// the object with the id, for the key `Type:id`.
+    ///
+    /// Call it after the change is applied, so that the live queries resolve the new values.
+    pub fn invalidate_live_queries(&self, key: &str) {
+        self.schema_env.live_queries.invalidate(key);
+    }
+
     fn var_value(&self, name: &str, pos: Pos) -> Result<Value> {
         self.query_env
             .operation
@@ -396,7 +425,7 @@ impl<'a, T> ContextBase<'a, T> {
             })
     }
 
-    fn resolve_input_value(&self, value: Positioned<InputValue>) -> Result<Value> {
+    pub(crate) fn resolve_input_value(&self, value: Positioned<InputValue>) -> Result<Value> {
         let pos = value.pos;
         value
             .node
@@ -460,6 +489,8 @@ impl<'a> ContextBase<'a, &'a Positioned<SelectionSet>> {
             inc_resolve_id: self.inc_resolve_id,
             schema_env: self.schema_env,
             query_env: self.query_env,
+            incremental: self.incremental,
+            initial_value: None,
         }
     }
 }
@@ -470,6 +501,24 @@ impl<'a> ContextBase<'a, &'a Positioned<Field>> {
         &self,
         name: &str,
         default: Option<fn() -> T>,
+    ) -> Result<T> {
+        self.get_param_value(name, default, false)
+    }
+
+    #[doc(hidden)]
+    pub fn secret_param_value<T: InputValueType>(
+        &self,
+        name: &str,
+        default: Option<fn() -> T>,
+    ) -> Result<T> {
+        self.get_param_value(name, default, true)
+    }
+
+    fn get_param_value<T: InputValueType>(
+        &self,
+        name: &str,
+        default: Option<fn() -> T>,
+        secret: bool,
     ) -> Result<T> {
         let value = self.item.node.get_argument(name).cloned();
         if value.is_none() {
@@ -481,7 +530,10 @@ impl<'a> ContextBase<'a, &'a Positioned<Field>> {
             Some(value) => (value.pos, Some(self.resolve_input_value(value)?)),
             None => (Pos::default(), None),
         };
-        InputValueType::parse(value).map_err(|e| e.into_error(pos, T::qualified_type_name()))
+        InputValueType::parse(value).map_err(|e| {
+            let e = if secret { e.redact() } else { e };
+            e.into_error(pos, T::qualified_input_type_name())
+        })
     }
 
     /// Creates a uniform interface to inspect the forthcoming selections.
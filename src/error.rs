@@ -3,6 +3,9 @@ use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
 use std::ops::Deref;
 use thiserror::Error;
 
+/// The text that replaces the value of a secret input value in logs and error messages.
+pub(crate) const SECRET_PLACEHOLDER: &str = "******";
+
 /// An error in the format of an input value.
 #[derive(Debug)]
 pub enum InputValueError {
@@ -20,6 +23,17 @@ impl<T: Display> From<T> for InputValueError {
 }
 
 impl InputValueError {
+    /// Hide the value that caused this error, used for input values marked as `secret`.
+    #[must_use]
+    pub fn redact(self) -> Self {
+        match self {
+            InputValueError::ExpectedType(_) => {
+                InputValueError::ExpectedType(Value::String(SECRET_PLACEHOLDER.to_string()))
+            }
+            err => err,
+        }
+    }
+
     /// Convert this error to a regular `Error` type.
     pub fn into_error(self, pos: Pos, expected_type: String) -> Error {
         match self {
@@ -359,6 +373,15 @@ pub enum ParseRequestError {
     /// The request is a batch request, but the server does not support batch requests.
     #[error("Batch requests are not supported")]
     UnsupportedBatch,
+
+    /// The content type of an uploaded file is not allowed.
+    #[error("The content type of the file \"{var_path}\" is not allowed")]
+    ContentTypeNotAllowed {
+        /// The variable path of the file in the `map` field.
+        var_path: String,
+        /// The content type of the file.
+        content_type: Option<String>,
+    },
 }
 
 #[cfg(feature = "multipart")]
@@ -471,3 +494,184 @@ impl Display for RuleErrors {
         Ok(())
     }
 }
+
+/// An error in the types of a schema, returned by [`Schema::check`](struct.Schema.html#method.check).
+///
+/// Positions in the schema are written as schema coordinates, such as `Query.field(arg:)`.
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum SchemaError {
+    /// The name of a type, field, argument or enum value is not a valid GraphQL name.
+    #[error("\"{name}\" of \"{coordinate}\" is not a valid name.")]
+    InvalidName {
+        /// The coordinate of the invalid name.
+        coordinate: String,
+
+        /// The invalid name.
+        name: String,
+    },
+
+    /// A type is referenced but not defined.
+    #[error("\"{coordinate}\" references the undefined type \"{ty}\".")]
+    UnknownType {
+        /// The coordinate of the reference.
+        coordinate: String,
+
+        /// The undefined type.
+        ty: String,
+    },
+
+    /// An input object is used as the type of a field.
+    #[error("\"{coordinate}\" must be an output type, but \"{ty}\" is an input type.")]
+    NotOutputType {
+        /// The coordinate of the field.
+        coordinate: String,
+
+        /// The input type.
+        ty: String,
+    },
+
+    /// An object, interface or union is used as the type of an argument or input field.
+    #[error("\"{coordinate}\" must be an input type, but \"{ty}\" is an output type.")]
+    NotInputType {
+        /// The coordinate of the argument or input field.
+        coordinate: String,
+
+        /// The output type.
+        ty: String,
+    },
+
+    /// A required argument or input field is deprecated.
+    #[error("\"{coordinate}\" is required, so it cannot be deprecated.")]
+    DeprecatedRequiredInputValue {
+        /// The coordinate of the argument or input field.
+        coordinate: String,
+    },
+
+    /// A member of a union is not an object type.
+    #[error("Member \"{member}\" of union \"{union}\" is not an object type.")]
+    UnionMemberNotObject {
+        /// The union type.
+        union: String,
+
+        /// The member that is not an object.
+        member: String,
+    },
+
+    /// A type implements something that is not an interface.
+    #[error("Type \"{ty}\" implements \"{interface}\", which is not an interface.")]
+    NotInterface {
+        /// The implementing type.
+        ty: String,
+
+        /// The type that is not an interface.
+        interface: String,
+    },
+
+    /// An interface implements itself, directly or through other interfaces.
+    #[error("Interface \"{interface}\" cannot implement itself.")]
+    SelfImplementation {
+        /// The interface.
+        interface: String,
+    },
+
+    /// A type implements an interface, but not the interfaces that interface implements.
+    #[error(
+        "Type \"{ty}\" implements \"{interface}\", so it must also implement \"{transitive}\"."
+    )]
+    MissingTransitiveInterface {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface implemented by the type.
+        interface: String,
+
+        /// The interface implemented by `interface` but not by the type.
+        transitive: String,
+    },
+
+    /// A type does not define a field of an interface it implements.
+    #[error("Field \"{interface}.{field}\" is expected but \"{ty}\" does not provide it.")]
+    MissingInterfaceField {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface.
+        interface: String,
+
+        /// The missing field.
+        field: String,
+    },
+
+    /// The type of a field is not compatible with the type of the interface field.
+    #[error("\"{ty}.{field}\" has type \"{actual}\", which is not a subtype of \"{expect}\" required by \"{interface}.{field}\".")]
+    InterfaceFieldTypeMismatch {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface.
+        interface: String,
+
+        /// The field.
+        field: String,
+
+        /// The type of the interface field.
+        expect: String,
+
+        /// The type of the implementing field.
+        actual: String,
+    },
+
+    /// A field does not define an argument of the interface field it implements.
+    #[error("Argument \"{interface}.{field}({argument}:)\" is expected but \"{ty}.{field}\" does not provide it.")]
+    MissingInterfaceArgument {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface.
+        interface: String,
+
+        /// The field.
+        field: String,
+
+        /// The missing argument.
+        argument: String,
+    },
+
+    /// The type of an argument is different from the type of the interface argument.
+    #[error("\"{ty}.{field}({argument}:)\" has type \"{actual}\", but \"{interface}.{field}({argument}:)\" has type \"{expect}\".")]
+    InterfaceArgumentTypeMismatch {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface.
+        interface: String,
+
+        /// The field.
+        field: String,
+
+        /// The argument.
+        argument: String,
+
+        /// The type of the interface argument.
+        expect: String,
+
+        /// The type of the implementing argument.
+        actual: String,
+    },
+
+    /// An argument that the interface field does not define is required.
+    #[error("\"{ty}.{field}({argument}:)\" is not defined by \"{interface}.{field}\", so it must not be required.")]
+    RequiredExtraArgument {
+        /// The implementing type.
+        ty: String,
+
+        /// The interface.
+        interface: String,
+
+        /// The field.
+        field: String,
+
+        /// The required argument.
+        argument: String,
+    },
+}
@@ -0,0 +1,67 @@
+use crate::extensions::{
+    ErrorLogger, ExtensionContext, Extensions, Middleware, NextExecute, NextParse, NextResolve,
+    NextValidate, ResolveInfo,
+};
+use crate::parser::types::ExecutableDocument;
+use crate::{Response, Result, ValidationResult, Variables};
+use std::sync::Arc;
+
+/// Calls the callbacks of the `Extension`s of a request around the phases of the middleware chain.
+///
+/// It is the innermost middleware, so the callbacks observe the phases as modified by the other
+/// middlewares. `prepare_request`, `prepare_document` and the subscription callbacks have no
+/// matching phase and are still called by the schema.
+pub(crate) struct ExtensionAdapter(pub(crate) Arc<Extensions>);
+
+#[async_trait::async_trait]
+impl Middleware for ExtensionAdapter {
+    fn resolve_hooks(&self) -> bool {
+        !self.0.resolve_extensions.is_empty()
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        self.0.parse_start(ctx, query, variables);
+        let document = next
+            .run(ctx, query, variables)
+            .await
+            .log_error(ctx, &self.0)?;
+        self.0.parse_end(ctx, &document);
+        Ok(document)
+    }
+
+    async fn validate(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidate<'_>,
+    ) -> Result<ValidationResult> {
+        self.0.validation_start(ctx);
+        let result = next.run(ctx).await.log_error(ctx, &self.0)?;
+        self.0.validation_end(ctx);
+        Ok(result)
+    }
+
+    async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+        self.0.execution_start(ctx);
+        let resp = next.run(ctx).await;
+        self.0.execution_end(ctx);
+        resp.extensions(self.0.result(ctx))
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        let states = self.0.resolve_start(ctx, info);
+        let value = next.run(ctx, info).await.log_error(ctx, &self.0)?;
+        self.0.resolve_end(ctx, info, states);
+        Ok(value)
+    }
+}
@@ -0,0 +1,354 @@
+use crate::context::QueryPathSegment;
+use crate::extensions::{Extension, ExtensionContext, ExtensionFactory, ResolveInfo, ResolveState};
+use crate::{Error, Request, Result, Variables};
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use std::any::TypeId;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Request data that enables `ApolloFederationTracing` for this request.
+///
+/// Insert it with `Request::data` when the `apollo-federation-include-trace` header of the request
+/// is `ftv1`.
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "apollo_tracing")))]
+pub struct IncludeFederatedTrace;
+
+/// Apollo federated tracing extension
+///
+/// Federation routers ask the subgraphs for inline traces with the
+/// `apollo-federation-include-trace: ftv1` header, and expect the base64 encoded protobuf `Trace`
+/// of the query in the `ftv1` field of the response extensions.
+///
+/// The trace is only recorded for the requests that contain `IncludeFederatedTrace` data, or a
+/// `ftv1` key in `Request::extensions`.
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "apollo_tracing")))]
+pub struct ApolloFederationTracing;
+
+impl ExtensionFactory for ApolloFederationTracing {
+    fn create(&self) -> Box<dyn Extension> {
+        Box::new(ApolloFederationTracingExtension {
+            enabled: AtomicBool::new(false),
+            inner: Mutex::new(Inner {
+                start_time: Utc::now(),
+                end_time: Utc::now(),
+                root: Default::default(),
+            }),
+        })
+    }
+}
+
+#[derive(Hash, Eq, PartialEq)]
+enum NodeId {
+    ResponseName(String),
+    Index(u32),
+}
+
+#[derive(Default)]
+struct Node {
+    ty: String,
+    parent_type: String,
+    start_time: u64,
+    end_time: u64,
+    errors: Vec<NodeError>,
+    children: IndexMap<NodeId, Node>,
+}
+
+struct NodeError {
+    message: String,
+    location: Option<(u32, u32)>,
+    time: u64,
+}
+
+impl Node {
+    fn child<I: IntoIterator<Item = NodeId>>(&mut self, path: I) -> &mut Node {
+        path.into_iter().fold(self, |node, id| {
+            node.children.entry(id).or_insert_with(Default::default)
+        })
+    }
+}
+
+struct Inner {
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    root: Node,
+}
+
+impl Inner {
+    fn offset(&self, time: DateTime<Utc>) -> u64 {
+        (time - self.start_time)
+            .num_nanoseconds()
+            .unwrap_or_default()
+            .max(0) as u64
+    }
+}
+
+struct ApolloFederationTracingExtension {
+    enabled: AtomicBool,
+    inner: Mutex<Inner>,
+}
+
+fn path_node_ids(info: &ResolveInfo<'_>) -> Vec<NodeId> {
+    let mut path = Vec::new();
+    info.path_node.for_each(|segment| {
+        path.push(match segment {
+            QueryPathSegment::Index(idx) => NodeId::Index(*idx as u32),
+            QueryPathSegment::Name(name) => NodeId::ResponseName(name.to_string()),
+        })
+    });
+    path
+}
+
+#[async_trait::async_trait]
+impl Extension for ApolloFederationTracingExtension {
+    fn name(&self) -> Option<&'static str> {
+        Some("ftv1")
+    }
+
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    async fn prepare_request(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        request: Request,
+    ) -> Result<Request> {
+        let enabled = request.extensions.contains_key("ftv1")
+            || request
+                .data
+                .contains_key(&TypeId::of::<IncludeFederatedTrace>());
+        self.enabled.store(enabled, Ordering::Relaxed);
+        Ok(request)
+    }
+
+    fn parse_start(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        _query_source: &str,
+        _variables: &Variables,
+    ) {
+        self.inner.lock().unwrap().start_time = Utc::now();
+    }
+
+    fn execution_end(&self, _ctx: &ExtensionContext<'_>) {
+        self.inner.lock().unwrap().end_time = Utc::now();
+    }
+
+    fn resolve_start(&self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) -> ResolveState {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let start_time = inner.offset(Utc::now());
+        let node = inner.root.child(path_node_ids(info));
+        if let QueryPathSegment::Name(_) = info.path_node.segment {
+            node.ty = info.return_type.to_string();
+            node.parent_type = info.parent_type.to_string();
+        }
+        node.start_time = start_time;
+        None
+    }
+
+    fn resolve_end(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        _state: ResolveState,
+    ) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let end_time = inner.offset(Utc::now());
+        inner.root.child(path_node_ids(info)).end_time = end_time;
+    }
+
+    fn error(&self, _ctx: &ExtensionContext<'_>, err: &Error) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let (message, location, path) = match err {
+            Error::Query { pos, path, err } => (
+                err.to_string(),
+                Some((pos.line as u32, pos.column as u32)),
+                path.as_ref()
+                    .and_then(|path| path.as_array())
+                    .map(|path| {
+                        path.iter()
+                            .filter_map(|segment| match segment {
+                                serde_json::Value::String(name) => {
+                                    Some(NodeId::ResponseName(name.clone()))
+                                }
+                                serde_json::Value::Number(idx) => {
+                                    idx.as_u64().map(|idx| NodeId::Index(idx as u32))
+                                }
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default(),
+            ),
+            _ => (err.to_string(), None, Vec::new()),
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        let time = inner.offset(Utc::now());
+        let error = NodeError {
+            message,
+            location,
+            time,
+        };
+
+        let node = inner.root.child(path);
+        if node.end_time == 0 {
+            node.end_time = time;
+        }
+        // The error is reported again by each of the fields it propagates through.
+        if !node
+            .errors
+            .iter()
+            .any(|e| e.message == error.message && e.location == error.location)
+        {
+            node.errors.push(error);
+        }
+    }
+
+    fn result(&self, _ctx: &ExtensionContext<'_>) -> Option<serde_json::Value> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let inner = self.inner.lock().unwrap();
+        let mut trace = Encoder::default();
+        trace.message(3, |e| encode_timestamp(e, &inner.end_time));
+        trace.message(4, |e| encode_timestamp(e, &inner.start_time));
+        trace.uint64(
+            11,
+            (inner.end_time - inner.start_time)
+                .num_nanoseconds()
+                .unwrap_or_default()
+                .max(0) as u64,
+        );
+        trace.message(14, |e| encode_node(e, None, &inner.root));
+        Some(base64::encode(&trace.0).into())
+    }
+}
+
+fn encode_timestamp(e: &mut Encoder, time: &DateTime<Utc>) {
+    e.uint64(1, time.timestamp() as u64);
+    e.uint64(2, time.timestamp_subsec_nanos() as u64);
+}
+
+fn encode_node(e: &mut Encoder, id: Option<&NodeId>, node: &Node) {
+    match id {
+        Some(NodeId::ResponseName(name)) => e.string(1, name),
+        // The index is a member of a oneof, so it is encoded even if it is zero.
+        Some(NodeId::Index(idx)) => {
+            e.key(2, WIRE_TYPE_VARINT);
+            e.varint(*idx as u64);
+        }
+        None => {}
+    }
+    e.string(3, &node.ty);
+    e.uint64(8, node.start_time);
+    e.uint64(9, node.end_time);
+    for error in &node.errors {
+        e.message(11, |e| {
+            e.string(1, &error.message);
+            if let Some((line, column)) = error.location {
+                e.message(2, |e| {
+                    e.uint64(1, line as u64);
+                    e.uint64(2, column as u64);
+                });
+            }
+            e.uint64(3, error.time);
+        });
+    }
+    for (id, child) in &node.children {
+        e.message(12, |e| encode_node(e, Some(id), child));
+    }
+    e.string(13, &node.parent_type);
+}
+
+const WIRE_TYPE_VARINT: u64 = 0;
+const WIRE_TYPE_LENGTH_DELIMITED: u64 = 2;
+
+/// A minimal protobuf encoder, fields with default values are skipped as in proto3.
+#[derive(Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn key(&mut self, field: u64, wire_type: u64) {
+        self.varint(field << 3 | wire_type);
+    }
+
+    fn uint64(&mut self, field: u64, value: u64) {
+        if value != 0 {
+            self.key(field, WIRE_TYPE_VARINT);
+            self.varint(value);
+        }
+    }
+
+    fn bytes(&mut self, field: u64, value: &[u8]) {
+        self.key(field, WIRE_TYPE_LENGTH_DELIMITED);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+    }
+
+    fn string(&mut self, field: u64, value: &str) {
+        if !value.is_empty() {
+            self.bytes(field, value.as_bytes());
+        }
+    }
+
+    fn message<F: FnOnce(&mut Encoder)>(&mut self, field: u64, f: F) {
+        let mut message = Encoder::default();
+        f(&mut message);
+        self.bytes(field, &message.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_node() {
+        let mut root = Node::default();
+        let field = root.child(vec![NodeId::ResponseName("a".to_string())]);
+        field.ty = "Int!".to_string();
+        field.start_time = 1;
+        field.end_time = 300;
+        root.child(vec![
+            NodeId::ResponseName("b".to_string()),
+            NodeId::Index(0),
+        ]);
+
+        let mut e = Encoder::default();
+        encode_node(&mut e, None, &root);
+        assert_eq!(
+            e.0,
+            vec![
+                0x62, 0x0e, // child "a"
+                0x0a, 0x01, b'a', // response_name
+                0x1a, 0x04, b'I', b'n', b't', b'!', // type
+                0x40, 0x01, // start_time
+                0x48, 0xac, 0x02, // end_time
+                0x62, 0x07, // child "b"
+                0x0a, 0x01, b'b', // response_name
+                0x62, 0x02, // child 0
+                0x10, 0x00, // index
+            ]
+        );
+    }
+}
@@ -75,7 +75,7 @@ struct ApolloPersistedQueriesExtension<T> {
 #[async_trait::async_trait]
 impl<T: CacheStorage> Extension for ApolloPersistedQueriesExtension<T> {
     async fn prepare_request(
-        &mut self,
+        &self,
         _ctx: &ExtensionContext<'_>,
         mut request: Request,
     ) -> Result<Request> {
@@ -1,10 +1,10 @@
-use crate::extensions::{Extension, ExtensionContext, ExtensionFactory, ResolveInfo};
+use crate::extensions::{Extension, ExtensionContext, ExtensionFactory, ResolveInfo, ResolveState};
 use crate::Variables;
 use chrono::{DateTime, Utc};
 use serde::ser::SerializeMap;
 use serde::{Serialize, Serializer};
-use std::collections::BTreeMap;
 use std::ops::Deref;
+use std::sync::Mutex;
 
 struct PendingResolve {
     path: serde_json::Value,
@@ -57,75 +57,91 @@ pub struct ApolloTracing;
 impl ExtensionFactory for ApolloTracing {
     fn create(&self) -> Box<dyn Extension> {
         Box::new(ApolloTracingExtension {
-            start_time: Utc::now(),
-            end_time: Utc::now(),
-            pending_resolves: Default::default(),
-            resolves: Default::default(),
+            inner: Mutex::new(Inner {
+                start_time: Utc::now(),
+                end_time: Utc::now(),
+                resolves: Default::default(),
+            }),
         })
     }
 }
 
-struct ApolloTracingExtension {
+struct Inner {
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
-    pending_resolves: BTreeMap<usize, PendingResolve>,
     resolves: Vec<ResolveStat>,
 }
 
+struct ApolloTracingExtension {
+    inner: Mutex<Inner>,
+}
+
 impl Extension for ApolloTracingExtension {
     fn name(&self) -> Option<&'static str> {
         Some("tracing")
     }
 
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
     fn parse_start(
-        &mut self,
+        &self,
         _ctx: &ExtensionContext<'_>,
         _query_source: &str,
         _variables: &Variables,
     ) {
-        self.start_time = Utc::now();
+        self.inner.lock().unwrap().start_time = Utc::now();
     }
 
-    fn execution_end(&mut self, _ctx: &ExtensionContext<'_>) {
-        self.end_time = Utc::now();
+    fn execution_end(&self, _ctx: &ExtensionContext<'_>) {
+        self.inner.lock().unwrap().end_time = Utc::now();
     }
 
-    fn resolve_start(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        self.pending_resolves.insert(
-            info.resolve_id.current,
-            PendingResolve {
-                path: serde_json::to_value(info.path_node).unwrap(),
-                field_name: info.path_node.field_name().to_string(),
-                parent_type: info.parent_type.to_string(),
-                return_type: info.return_type.to_string(),
-                start_time: Utc::now(),
-            },
-        );
+    fn resolve_start(&self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) -> ResolveState {
+        Some(Box::new(PendingResolve {
+            path: serde_json::to_value(info.path_node).unwrap(),
+            field_name: info.path_node.field_name().to_string(),
+            parent_type: info.parent_type.to_string(),
+            return_type: info.return_type.to_string(),
+            start_time: Utc::now(),
+        }))
     }
 
-    fn resolve_end(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        if let Some(pending_resolve) = self.pending_resolves.remove(&info.resolve_id.current) {
-            let start_offset = (pending_resolve.start_time - self.start_time)
+    fn resolve_end(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        _info: &ResolveInfo<'_>,
+        state: ResolveState,
+    ) {
+        if let Some(pending_resolve) =
+            state.and_then(|state| state.downcast::<PendingResolve>().ok())
+        {
+            let end_time = Utc::now();
+            let mut inner = self.inner.lock().unwrap();
+            let start_offset = (pending_resolve.start_time - inner.start_time)
                 .num_nanoseconds()
                 .unwrap();
-            self.resolves.push(ResolveStat {
-                pending_resolve,
+            inner.resolves.push(ResolveStat {
+                pending_resolve: *pending_resolve,
                 start_offset,
-                end_time: Utc::now(),
+                end_time,
             });
         }
     }
 
-    fn result(&mut self, _ctx: &ExtensionContext<'_>) -> Option<serde_json::Value> {
-        self.resolves
+    fn result(&self, _ctx: &ExtensionContext<'_>) -> Option<serde_json::Value> {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .resolves
             .sort_by(|a, b| a.start_offset.cmp(&b.start_offset));
         Some(serde_json::json!({
             "version": 1,
-            "startTime": self.start_time.to_rfc3339(),
-            "endTime": self.end_time.to_rfc3339(),
-            "duration": (self.end_time - self.start_time).num_nanoseconds(),
+            "startTime": inner.start_time.to_rfc3339(),
+            "endTime": inner.end_time.to_rfc3339(),
+            "duration": (inner.end_time - inner.start_time).num_nanoseconds(),
             "execution": {
-                "resolvers": self.resolves
+                "resolvers": inner.resolves
             }
         }))
     }
@@ -1,103 +1,342 @@
-use crate::extensions::{Extension, ExtensionContext, ExtensionFactory, ResolveInfo};
-use crate::parser::types::{ExecutableDocument, OperationType, Selection};
-use crate::{Error, Variables};
+use crate::error::SECRET_PLACEHOLDER;
+use crate::extensions::{
+    ExtensionContext, Middleware, MiddlewareFactory, NextParse, NextRequest, NextResolve,
+    ResolveInfo,
+};
+use crate::parser::types::{DocumentOperations, ExecutableDocument, OperationType, Selection};
+use crate::{Error, Request, Response, Result, Value, Variables};
 use itertools::Itertools;
-use log::{error, info, trace};
+use log::{error, info, log_enabled, trace, Level};
+use regex::Regex;
 use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A field of the records written by the `Logger` extension.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "log")))]
+pub enum LoggerField {
+    /// The name of the operation.
+    OperationName,
+    /// The query, with the `secret` values redacted.
+    Query,
+    /// The variables, with the `secret` values redacted.
+    Variables,
+    /// The duration of the request.
+    Duration,
+}
 
 /// Logger extension
+///
+/// It is a middleware, add it with `SchemaBuilder::middleware`. A record is written with
+/// `log::info!` when a request ends, and with `log::error!` for each error, with the path of the
+/// field for the errors of resolvers. The introspection queries are only logged if they fail.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::extensions::{Logger, LoggerField};
+/// use std::time::Duration;
+///
+/// #[derive(SimpleObject)]
+/// struct Query {
+///     value: i32,
+/// }
+///
+/// let schema = Schema::build(Query { value: 10 }, EmptyMutation, EmptySubscription)
+///     .middleware(
+///         Logger::default()
+///             .fields(vec![LoggerField::OperationName, LoggerField::Duration])
+///             .redact_variables("(?i)password|token")
+///             .slow_threshold(Duration::from_millis(500))
+///             .json(),
+///     )
+///     .finish();
+/// ```
+#[derive(Clone)]
 #[cfg_attr(feature = "nightly", doc(cfg(feature = "log")))]
-pub struct Logger;
-
-impl ExtensionFactory for Logger {
-    fn create(&self) -> Box<dyn Extension> {
-        Box::new(LoggerExtension {
-            enabled: true,
-            query: String::new(),
-            variables: Default::default(),
+pub struct Logger {
+    fields: Vec<LoggerField>,
+    redact_variables: Option<Regex>,
+    slow_threshold: Option<Duration>,
+    json: bool,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self {
+            fields: vec![
+                LoggerField::OperationName,
+                LoggerField::Query,
+                LoggerField::Variables,
+                LoggerField::Duration,
+            ],
+            redact_variables: None,
+            slow_threshold: None,
+            json: false,
+        }
+    }
+}
+
+impl Logger {
+    /// Set the fields of the records, all the fields are written by default.
+    pub fn fields(self, fields: impl IntoIterator<Item = LoggerField>) -> Self {
+        Self {
+            fields: fields.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Redact the variables, and the fields of the input objects in the variables, whose name
+    /// matches the regular expression.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is not a valid regular expression.
+    pub fn redact_variables(self, pattern: &str) -> Self {
+        Self {
+            redact_variables: Some(Regex::new(pattern).expect("invalid variable name pattern")),
+            ..self
+        }
+    }
+
+    /// Only log the successful requests that take longer than the threshold.
+    pub fn slow_threshold(self, threshold: Duration) -> Self {
+        Self {
+            slow_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Write the records as JSON objects.
+    pub fn json(self) -> Self {
+        Self { json: true, ..self }
+    }
+}
+
+impl MiddlewareFactory for Logger {
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(LoggerMiddleware {
+            logger: self.clone(),
+            record: Default::default(),
         })
     }
 }
 
-struct LoggerExtension {
-    enabled: bool,
+#[derive(Default)]
+struct Record {
+    operation_name: Option<String>,
     query: String,
     variables: Variables,
+    is_introspection: bool,
 }
 
-impl Extension for LoggerExtension {
-    fn parse_start(
-        &mut self,
-        _ctx: &ExtensionContext<'_>,
-        query_source: &str,
-        variables: &Variables,
-    ) {
-        self.query = query_source.replace(char::is_whitespace, "");
-        self.variables = variables.clone();
+struct LoggerMiddleware {
+    logger: Logger,
+    record: Mutex<Record>,
+}
+
+fn redact_value(pattern: &Regex, value: &mut Value) {
+    match value {
+        Value::List(items) => {
+            for item in items {
+                redact_value(pattern, item);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, value) in fields.iter_mut() {
+                if pattern.is_match(name) {
+                    *value = Value::String(SECRET_PLACEHOLDER.to_string());
+                } else {
+                    redact_value(pattern, value);
+                }
+            }
+        }
+        _ => {}
     }
+}
 
-    fn parse_end(&mut self, _ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {
-        let is_schema = document
-            .operations
+fn is_introspection(document: &ExecutableDocument) -> bool {
+    document
+        .operations
+        .iter()
+        .filter(|(_, operation)| operation.node.ty == OperationType::Query)
+        .any(|(_, operation)| {
+            operation.node.selection_set.node.items.iter().any(|selection| {
+                matches!(&selection.node, Selection::Field(field) if field.node.name.node == "__schema")
+            })
+        })
+}
+
+fn path_segments(path: &serde_json::Value) -> Vec<Cow<'_, str>> {
+    match path {
+        serde_json::Value::Array(values) => values
             .iter()
-            .filter(|(_, operation)| operation.node.ty == OperationType::Query)
-            .any(|(_, operation)| operation.node.selection_set.node.items.iter().any(|selection| matches!(&selection.node, Selection::Field(field) if field.node.name.node == "__schema")));
+            .filter_map(|value| match value {
+                serde_json::Value::String(s) => Some(Cow::Borrowed(s.as_str())),
+                serde_json::Value::Number(n) => Some(Cow::Owned(n.to_string())),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
 
-        if is_schema {
-            self.enabled = false;
-            return;
+impl LoggerMiddleware {
+    fn redact_variables(&self, variables: &mut Variables) {
+        if let Some(pattern) = &self.logger.redact_variables {
+            for (name, value) in variables.0.iter_mut() {
+                if pattern.is_match(name) {
+                    *value = Value::String(SECRET_PLACEHOLDER.to_string());
+                } else {
+                    redact_value(pattern, value);
+                }
+            }
         }
+    }
 
-        info!(target: "async-graphql", "[Query] query: \"{}\", variables: {}", &self.query, self.variables);
+    fn text_fields(&self, record: &Record, duration: Duration) -> String {
+        self.logger
+            .fields
+            .iter()
+            .filter_map(|field| match field {
+                LoggerField::OperationName => record
+                    .operation_name
+                    .as_ref()
+                    .map(|name| format!("operation: \"{}\"", name)),
+                LoggerField::Query => Some(format!("query: \"{}\"", record.query)),
+                LoggerField::Variables => Some(format!("variables: {}", record.variables)),
+                LoggerField::Duration => Some(format!("duration: {:?}", duration)),
+            })
+            .join(", ")
     }
 
-    fn resolve_start(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        if !self.enabled {
-            return;
+    fn json_fields(
+        &self,
+        record: &Record,
+        duration: Duration,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut map = serde_json::Map::new();
+        for field in &self.logger.fields {
+            match field {
+                LoggerField::OperationName => {
+                    if let Some(name) = &record.operation_name {
+                        map.insert("operationName".to_string(), name.clone().into());
+                    }
+                }
+                LoggerField::Query => {
+                    map.insert("query".to_string(), record.query.clone().into());
+                }
+                LoggerField::Variables => {
+                    map.insert(
+                        "variables".to_string(),
+                        serde_json::to_value(&record.variables).unwrap_or_default(),
+                    );
+                }
+                LoggerField::Duration => {
+                    map.insert(
+                        "durationMs".to_string(),
+                        (duration.as_secs_f64() * 1000.0).into(),
+                    );
+                }
+            }
         }
-        trace!(target: "async-graphql", "[ResolveStart] path: \"{}\"", info.path_node);
+        map
     }
 
-    fn resolve_end(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        if !self.enabled {
-            return;
+    fn log_query(&self, record: &Record, duration: Duration) {
+        if self.logger.json {
+            let mut map = self.json_fields(record, duration);
+            map.insert("type".to_string(), "query".into());
+            info!(target: "async-graphql", "{}", serde_json::Value::Object(map));
+        } else {
+            info!(target: "async-graphql", "[Query] {}", self.text_fields(record, duration));
         }
-        trace!(target: "async-graphql", "[ResolveEnd] path: \"{}\"", info.path_node);
     }
 
-    fn error(&mut self, _ctx: &ExtensionContext<'_>, err: &Error) {
+    fn log_error(&self, record: &Record, duration: Duration, err: &Error) {
+        if self.logger.json {
+            let mut records = Vec::new();
+            match err {
+                Error::Parse(err) => {
+                    let mut map = self.json_fields(record, duration);
+                    map.insert("type".to_string(), "parse_error".into());
+                    map.insert("message".to_string(), err.to_string().into());
+                    if let Some(pos) = err.positions().next() {
+                        map.insert(
+                            "locations".to_string(),
+                            serde_json::json!([{ "line": pos.line, "column": pos.column }]),
+                        );
+                    }
+                    records.push(map);
+                }
+                Error::Query { pos, path, err } => {
+                    let mut map = self.json_fields(record, duration);
+                    map.insert("type".to_string(), "query_error".into());
+                    map.insert("message".to_string(), err.to_string().into());
+                    map.insert(
+                        "locations".to_string(),
+                        serde_json::json!([{ "line": pos.line, "column": pos.column }]),
+                    );
+                    if let Some(path) = path {
+                        map.insert("path".to_string(), path.clone());
+                    }
+                    records.push(map);
+                }
+                Error::Rule { errors } => {
+                    for error in errors.iter() {
+                        let mut map = self.json_fields(record, duration);
+                        map.insert("type".to_string(), "validation_error".into());
+                        map.insert("message".to_string(), error.message.clone().into());
+                        map.insert(
+                            "locations".to_string(),
+                            error
+                                .locations
+                                .iter()
+                                .map(|pos| serde_json::json!({ "line": pos.line, "column": pos.column }))
+                                .collect(),
+                        );
+                        records.push(map);
+                    }
+                }
+                Error::Other(err) => {
+                    let mut map = self.json_fields(record, duration);
+                    map.insert("type".to_string(), "other_error".into());
+                    map.insert("message".to_string(), err.clone().into());
+                    records.push(map);
+                }
+            }
+            for map in records {
+                error!(target: "async-graphql", "{}", serde_json::Value::Object(map));
+            }
+            return;
+        }
+
+        let mut fields = self.text_fields(record, duration);
+        if !fields.is_empty() {
+            fields.push_str(", ");
+        }
         match err {
             Error::Parse(err) => {
                 error!(
-                    target: "async-graphql", "[ParseError] {}query: \"{}\", variables: {}, {}",
+                    target: "async-graphql", "[ParseError] {}{}{}",
                     if let Some(pos) = err.positions().next() {
                         // TODO: Make this more efficient
                         format!("pos: [{}:{}], ", pos.line, pos.column)
                     } else {
                         String::new()
                     },
-                    self.query,
-                    self.variables,
+                    fields,
                     err
                 )
             }
             Error::Query { pos, path, err } => {
                 if let Some(path) = path {
-                    let path = if let serde_json::Value::Array(values) = path {
-                        values
-                            .iter()
-                            .filter_map(|value| match value {
-                                serde_json::Value::String(s) => Some(Cow::Borrowed(s.as_str())),
-                                serde_json::Value::Number(n) => Some(Cow::Owned(n.to_string())),
-                                _ => None,
-                            })
-                            .join(".")
-                    } else {
-                        String::new()
-                    };
-                    error!(target: "async-graphql", "[QueryError] path: \"{}\", pos: [{}:{}], query: \"{}\", variables: {}, {}", path, pos.line, pos.column, self.query, self.variables, err)
+                    let path = path_segments(path).iter().join(".");
+                    error!(target: "async-graphql", "[QueryError] path: \"{}\", pos: [{}:{}], {}{}", path, pos.line, pos.column, fields, err)
                 } else {
-                    error!(target: "async-graphql", "[QueryError] pos: [{}:{}], query: \"{}\", variables: {}, {}", pos.line, pos.column, self.query, self.variables, err)
+                    error!(target: "async-graphql", "[QueryError] pos: [{}:{}], {}{}", pos.line, pos.column, fields, err)
                 }
             }
             Error::Rule { errors } => {
@@ -107,15 +346,90 @@ impl Extension for LoggerExtension {
                         .iter()
                         .map(|pos| format!("{}:{}", pos.line, pos.column))
                         .join(", ");
-                    error!(target: "async-graphql", "[ValidationError] pos: [{}], query: \"{}\", variables: {}, {}", locations, self.query, self.variables, error.message)
+                    error!(target: "async-graphql", "[ValidationError] pos: [{}], {}{}", locations, fields, error.message)
                 }
             }
-            Error::Other(err) => error!(
-                target: "async-graphql", "[OtherError] query: \"{}\", variables: {}, {}",
-                self.query,
-                self.variables,
-                err
-            ),
+            Error::Other(err) => {
+                error!(target: "async-graphql", "[OtherError] {}{}", fields, err)
+            }
         }
     }
 }
+
+#[async_trait::async_trait]
+impl Middleware for LoggerMiddleware {
+    fn resolve_hooks(&self) -> bool {
+        log_enabled!(target: "async-graphql", Level::Trace)
+    }
+
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        self.record.lock().unwrap().operation_name = request.operation_name.clone();
+        let start_time = Instant::now();
+        let resp = next.run(ctx, request).await;
+        let duration = start_time.elapsed();
+
+        let record = self.record.lock().unwrap();
+        match &resp.error {
+            Some(err) => self.log_error(&record, duration, err),
+            None if record.is_introspection => {}
+            None => {
+                if self
+                    .logger
+                    .slow_threshold
+                    .map_or(true, |threshold| duration >= threshold)
+                {
+                    self.log_query(&record, duration);
+                }
+            }
+        }
+        resp
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        let res = next.run(ctx, query, variables).await;
+        let mut record = self.record.lock().unwrap();
+        match &res {
+            Ok(document) => {
+                record.is_introspection = is_introspection(document);
+                record.query = ctx.stringify_execute_doc(document);
+                record.variables = ctx.redact_variables(document, variables);
+                if let (None, DocumentOperations::Multiple(operations)) =
+                    (&record.operation_name, &document.operations)
+                {
+                    if operations.len() == 1 {
+                        record.operation_name = operations.keys().next().map(ToString::to_string);
+                    }
+                }
+            }
+            Err(_) => {
+                record.query = query.replace(char::is_whitespace, "");
+                record.variables = variables.clone();
+            }
+        }
+        self.redact_variables(&mut record.variables);
+        res
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        trace!(target: "async-graphql", "[ResolveStart] path: \"{}\"", info.path_node);
+        let res = next.run(ctx, info).await;
+        trace!(target: "async-graphql", "[ResolveEnd] path: \"{}\"", info.path_node);
+        res
+    }
+}
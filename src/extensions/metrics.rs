@@ -0,0 +1,552 @@
+use crate::extensions::{
+    ExtensionContext, Middleware, MiddlewareFactory, NextParse, NextRequest, NextResolve,
+    ResolveInfo,
+};
+use crate::parser::types::{DocumentOperations, ExecutableDocument, Selection};
+use crate::{QueryPathSegment, Request, Response, Result, Variables};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The default histogram buckets, in seconds.
+const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// The label of the unnamed operations recorded after the limit is reached, and of the operation
+/// names that are not in the document.
+const OTHER_UNNAMED_OPERATIONS: &str = "{...}";
+
+/// Metrics extension
+///
+/// It is a middleware, add it with `SchemaBuilder::middleware` and keep a clone to render the
+/// metrics in the [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/)
+/// with [`render`](#method.render), for example in a `/metrics` handler of the web framework.
+///
+/// The following metrics are recorded:
+///
+/// - `graphql_operation_requests_total`, `graphql_operation_errors_total` and
+///   `graphql_operation_duration_seconds`, with the `operation` label.
+/// - `graphql_root_field_requests_total` and `graphql_root_field_errors_total`, with the `field`
+///   label.
+/// - `graphql_field_resolves_total`, `graphql_field_errors_total` and
+///   `graphql_field_duration_seconds`, with the `field` label.
+///
+/// The `field` label is `parent_type.field_name`. The `operation` label is the operation name,
+/// the unnamed operations are labelled by their root fields, such as `{user,posts}`. As any query
+/// can be sent without a name, the number of labels of the unnamed operations is limited by
+/// [`max_unnamed_operations`](#method.max_unnamed_operations), the others are recorded as `{...}`.
+/// An operation name is only used if the document has the operation, the requests naming another
+/// operation are also recorded as `{...}`.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::extensions::Metrics;
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let metrics = Metrics::new();
+///     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+///         .middleware(metrics.clone())
+///         .finish();
+///     schema.execute("query A { value }").await;
+///     assert!(metrics
+///         .render()
+///         .contains(r#"graphql_operation_requests_total{operation="A"} 1"#));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct Metrics {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    buckets: Vec<f64>,
+    max_unnamed_operations: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    operations: BTreeMap<String, OperationStats>,
+    unnamed_operations: usize,
+    root_fields: BTreeMap<String, RootFieldStats>,
+    fields: BTreeMap<String, FieldStats>,
+}
+
+struct Histogram {
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &[f64]) -> Self {
+        Self {
+            counts: vec![0; buckets.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, buckets: &[f64], duration: Duration) {
+        let value = duration.as_secs_f64();
+        if let Some(idx) = buckets.iter().position(|bucket| value <= *bucket) {
+            self.counts[idx] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn merge(&mut self, other: &Histogram) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+}
+
+struct OperationStats {
+    requests: u64,
+    errors: u64,
+    duration: Histogram,
+}
+
+#[derive(Default)]
+struct RootFieldStats {
+    requests: u64,
+    errors: u64,
+}
+
+struct FieldStats {
+    resolves: u64,
+    errors: u64,
+    duration: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Create a metrics extension with the default buckets, and at most 100 labels for the unnamed
+    /// operations.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                buckets: DEFAULT_BUCKETS.to_vec(),
+                max_unnamed_operations: 100,
+                state: Default::default(),
+            }),
+        }
+    }
+
+    fn options(&mut self) -> &mut Inner {
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) if inner.state.lock().unwrap().operations.is_empty() => inner,
+            _ => panic!("The options of the metrics extension must be set before it is used"),
+        }
+    }
+
+    /// Set the upper bounds of the buckets of the duration histograms, in seconds.
+    ///
+    /// The values that are not finite are ignored, the `+Inf` bucket is always rendered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension has been cloned or has recorded metrics.
+    pub fn buckets(mut self, buckets: impl IntoIterator<Item = f64>) -> Self {
+        let mut buckets = buckets
+            .into_iter()
+            .filter(|bucket| bucket.is_finite())
+            .collect::<Vec<_>>();
+        buckets.sort_by(f64::total_cmp);
+        buckets.dedup();
+        self.options().buckets = buckets;
+        self
+    }
+
+    /// Set the maximum number of labels of the unnamed operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the extension has been cloned or has recorded metrics.
+    pub fn max_unnamed_operations(mut self, max_unnamed_operations: usize) -> Self {
+        self.options().max_unnamed_operations = max_unnamed_operations;
+        self
+    }
+
+    /// Render the metrics in the Prometheus text format.
+    ///
+    /// The content type of the response is `text/plain; version=0.0.4`.
+    pub fn render(&self) -> String {
+        let buckets = &self.inner.buckets;
+        let state = self.inner.state.lock().unwrap();
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "graphql_operation_requests_total",
+            "counter",
+            "The number of GraphQL requests.",
+        );
+        for (operation, stats) in &state.operations {
+            write_sample(
+                &mut out,
+                "graphql_operation_requests_total",
+                ("operation", operation),
+                None,
+                stats.requests,
+            );
+        }
+        write_header(
+            &mut out,
+            "graphql_operation_errors_total",
+            "counter",
+            "The number of GraphQL requests that returned an error.",
+        );
+        for (operation, stats) in &state.operations {
+            write_sample(
+                &mut out,
+                "graphql_operation_errors_total",
+                ("operation", operation),
+                None,
+                stats.errors,
+            );
+        }
+        write_header(
+            &mut out,
+            "graphql_operation_duration_seconds",
+            "histogram",
+            "The duration of the GraphQL requests.",
+        );
+        for (operation, stats) in &state.operations {
+            write_histogram(
+                &mut out,
+                "graphql_operation_duration_seconds",
+                ("operation", operation),
+                buckets,
+                &stats.duration,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "graphql_root_field_requests_total",
+            "counter",
+            "The number of requests of the root fields.",
+        );
+        for (field, stats) in &state.root_fields {
+            write_sample(
+                &mut out,
+                "graphql_root_field_requests_total",
+                ("field", field),
+                None,
+                stats.requests,
+            );
+        }
+        write_header(
+            &mut out,
+            "graphql_root_field_errors_total",
+            "counter",
+            "The number of requests of the root fields that returned an error.",
+        );
+        for (field, stats) in &state.root_fields {
+            write_sample(
+                &mut out,
+                "graphql_root_field_errors_total",
+                ("field", field),
+                None,
+                stats.errors,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "graphql_field_resolves_total",
+            "counter",
+            "The number of resolved fields.",
+        );
+        for (field, stats) in &state.fields {
+            write_sample(
+                &mut out,
+                "graphql_field_resolves_total",
+                ("field", field),
+                None,
+                stats.resolves,
+            );
+        }
+        write_header(
+            &mut out,
+            "graphql_field_errors_total",
+            "counter",
+            "The number of resolved fields that returned an error.",
+        );
+        for (field, stats) in &state.fields {
+            write_sample(
+                &mut out,
+                "graphql_field_errors_total",
+                ("field", field),
+                None,
+                stats.errors,
+            );
+        }
+        write_header(
+            &mut out,
+            "graphql_field_duration_seconds",
+            "histogram",
+            "The duration of the field resolvers.",
+        );
+        for (field, stats) in &state.fields {
+            write_histogram(
+                &mut out,
+                "graphql_field_duration_seconds",
+                ("field", field),
+                buckets,
+                &stats.duration,
+            );
+        }
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, ty: &str, help: &str) {
+    writeln!(out, "# HELP {} {}", name, help).unwrap();
+    writeln!(out, "# TYPE {} {}", name, ty).unwrap();
+}
+
+fn write_label_value(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    label: (&str, &str),
+    le: Option<&str>,
+    value: impl std::fmt::Display,
+) {
+    write!(out, "{}{{{}=\"", name, label.0).unwrap();
+    write_label_value(out, label.1);
+    out.push('"');
+    if let Some(le) = le {
+        write!(out, ",le=\"{}\"", le).unwrap();
+    }
+    writeln!(out, "}} {}", value).unwrap();
+}
+
+fn write_histogram(
+    out: &mut String,
+    name: &str,
+    label: (&str, &str),
+    buckets: &[f64],
+    histogram: &Histogram,
+) {
+    let bucket_name = format!("{}_bucket", name);
+    let mut count = 0;
+    for (bucket, bucket_count) in buckets.iter().zip(&histogram.counts) {
+        count += bucket_count;
+        write_sample(out, &bucket_name, label, Some(&bucket.to_string()), count);
+    }
+    write_sample(out, &bucket_name, label, Some("+Inf"), histogram.count);
+    write_sample(out, &format!("{}_sum", name), label, None, histogram.sum);
+    write_sample(
+        out,
+        &format!("{}_count", name),
+        label,
+        None,
+        histogram.count,
+    );
+}
+
+impl MiddlewareFactory for Metrics {
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(MetricsMiddleware {
+            inner: self.inner.clone(),
+            request: Default::default(),
+        })
+    }
+}
+
+/// The metrics of a request, merged into the shared state when the request ends, so the fields
+/// only lock the state of their request.
+#[derive(Default)]
+struct RequestMetrics {
+    /// The operation name of the request, it may not be in the document.
+    requested_operation_name: Option<String>,
+    operation_name: Option<String>,
+    root_field_names: Vec<String>,
+    root_fields: HashMap<String, RootFieldStats>,
+    fields: HashMap<String, FieldStats>,
+}
+
+struct MetricsMiddleware {
+    inner: Arc<Inner>,
+    request: Mutex<RequestMetrics>,
+}
+
+impl MetricsMiddleware {
+    fn record(&self, duration: Duration, is_err: bool) {
+        let request = std::mem::take(&mut *self.request.lock().unwrap());
+        let buckets = &self.inner.buckets;
+        let mut state = self.inner.state.lock().unwrap();
+
+        let operation = match (request.operation_name, request.requested_operation_name) {
+            (Some(name), _) => name,
+            (None, Some(_)) => OTHER_UNNAMED_OPERATIONS.to_string(),
+            (None, None) => {
+                let label = format!("{{{}}}", request.root_field_names.join(","));
+                if state.operations.contains_key(&label) {
+                    label
+                } else if state.unnamed_operations < self.inner.max_unnamed_operations {
+                    state.unnamed_operations += 1;
+                    label
+                } else {
+                    OTHER_UNNAMED_OPERATIONS.to_string()
+                }
+            }
+        };
+        let stats = state
+            .operations
+            .entry(operation)
+            .or_insert_with(|| OperationStats {
+                requests: 0,
+                errors: 0,
+                duration: Histogram::new(buckets),
+            });
+        stats.requests += 1;
+        if is_err {
+            stats.errors += 1;
+        }
+        stats.duration.observe(buckets, duration);
+
+        for (field, stats) in request.root_fields {
+            let entry = state.root_fields.entry(field).or_default();
+            entry.requests += stats.requests;
+            entry.errors += stats.errors;
+        }
+        for (field, stats) in request.fields {
+            let entry = state.fields.entry(field).or_insert_with(|| FieldStats {
+                resolves: 0,
+                errors: 0,
+                duration: Histogram::new(buckets),
+            });
+            entry.resolves += stats.resolves;
+            entry.errors += stats.errors;
+            entry.duration.merge(&stats.duration);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for MetricsMiddleware {
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        self.request.lock().unwrap().requested_operation_name = request.operation_name.clone();
+        let start_time = Instant::now();
+        let resp = next.run(ctx, request).await;
+        self.record(start_time.elapsed(), resp.is_err());
+        resp
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        let request = &mut *self.request.lock().unwrap();
+        match (&request.requested_operation_name, &document.operations) {
+            (Some(name), operations) => {
+                // Any name can be sent, it is only a label if the document has the operation.
+                if operations.get(Some(name)).is_some() {
+                    request.operation_name = Some(name.clone());
+                }
+            }
+            (None, DocumentOperations::Single(operation)) => {
+                for selection in &operation.node.selection_set.node.items {
+                    if let Selection::Field(field) = &selection.node {
+                        let name = field.node.name.node.to_string();
+                        if !request.root_field_names.contains(&name) {
+                            request.root_field_names.push(name);
+                        }
+                    }
+                }
+            }
+            (None, DocumentOperations::Multiple(operations)) if operations.len() == 1 => {
+                request.operation_name = operations.keys().next().map(ToString::to_string);
+            }
+            (None, DocumentOperations::Multiple(_)) => {}
+        }
+        Ok(document)
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        if let QueryPathSegment::Index(_) = info.path_node.segment {
+            return next.run(ctx, info).await;
+        }
+
+        let start_time = Instant::now();
+        let res = next.run(ctx, info).await;
+        let duration = start_time.elapsed();
+
+        let field = format!("{}.{}", info.parent_type, info.name);
+        let mut request = self.request.lock().unwrap();
+        if info.path_node.parent.is_none() {
+            let stats = request.root_fields.entry(field.clone()).or_default();
+            stats.requests += 1;
+            if res.is_err() {
+                stats.errors += 1;
+            }
+        }
+        let buckets = &self.inner.buckets;
+        let stats = request.fields.entry(field).or_insert_with(|| FieldStats {
+            resolves: 0,
+            errors: 0,
+            duration: Histogram::new(buckets),
+        });
+        stats.resolves += 1;
+        if res.is_err() {
+            stats.errors += 1;
+        }
+        stats.duration.observe(buckets, duration);
+        res
+    }
+}
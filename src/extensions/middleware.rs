@@ -0,0 +1,333 @@
+use crate::extensions::{ExtensionContext, ResolveInfo};
+use crate::parser::types::ExecutableDocument;
+use crate::{QueryEnv, Request, Response, Result, ValidationResult, Variables};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use std::future::Future;
+
+pub(crate) type BoxMiddleware = Box<dyn Middleware>;
+
+type ParseFn<'a> = &'a (dyn Fn(&ExtensionContext<'_>, &str, &Variables) -> Result<ExecutableDocument>
+         + Send
+         + Sync);
+
+/// Represents a middleware-style GraphQL extension.
+///
+/// Each method wraps one phase of the execution and receives a `next` continuation that runs the
+/// rest of the phase. A middleware can run async code before and after calling `next`, modify its
+/// result, or return early without calling it at all.
+///
+/// The `Extension`s of a schema are run by an adapter that is the innermost middleware of the
+/// chain, it calls their callbacks around each phase. So the callbacks observe the phases as
+/// modified by the middlewares, and a middleware that returns early without calling `next` skips
+/// them.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::extensions::*;
+///
+/// struct ReadOnly;
+///
+/// #[async_trait::async_trait]
+/// impl Middleware for ReadOnly {
+///     async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+///         let resp = next.run(ctx).await;
+///         resp.extensions(Some(serde_json::json!({ "readOnly": true })))
+///     }
+/// }
+///
+/// impl MiddlewareFactory for ReadOnly {
+///     fn create(&self) -> Box<dyn Middleware> {
+///         Box::new(ReadOnly)
+///     }
+/// }
+///
+/// struct Query;
+///
+/// #[Object]
+/// impl Query {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+///         .middleware(ReadOnly)
+///         .finish();
+///     let resp = schema.execute("{ value }").await;
+///     assert_eq!(resp.extensions, Some(serde_json::json!({ "readOnly": true })));
+/// });
+/// ```
+#[async_trait::async_trait]
+#[allow(unused_variables)]
+pub trait Middleware: Sync + Send + 'static {
+    /// Returns `true` if this middleware implements `resolve`.
+    ///
+    /// The resolve phase runs for every field and list item, so it skips the middlewares that
+    /// return `false`.
+    fn resolve_hooks(&self) -> bool {
+        false
+    }
+
+    /// Wraps the execution of a query or mutation request.
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        next.run(ctx, request).await
+    }
+
+    /// Wraps the stream of responses of a subscription request.
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        next.run(ctx, stream)
+    }
+
+    /// Wraps the parsing of the query source.
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        next.run(ctx, query, variables).await
+    }
+
+    /// Wraps the validation of the query.
+    async fn validate(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidate<'_>,
+    ) -> Result<ValidationResult> {
+        next.run(ctx).await
+    }
+
+    /// Wraps the execution of the selected operation.
+    async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+        next.run(ctx).await
+    }
+
+    /// Wraps the resolution of a field or a list item, if `resolve_hooks` returns `true`.
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        next.run(ctx, info).await
+    }
+}
+
+/// Middleware factory
+///
+/// Used to create a middleware instance for each request.
+pub trait MiddlewareFactory: Send + Sync + 'static {
+    /// Create a middleware instance.
+    fn create(&self) -> Box<dyn Middleware>;
+}
+
+/// The remaining middlewares of the request phase.
+pub struct NextRequest<'a> {
+    pub(crate) chain: &'a [BoxMiddleware],
+    pub(crate) request_fn: Box<dyn FnOnce(Request) -> BoxFuture<'a, Response> + Send + 'a>,
+}
+
+impl NextRequest<'_> {
+    /// Call the next middleware, or execute the request.
+    pub async fn run(self, ctx: &ExtensionContext<'_>, request: Request) -> Response {
+        match self.chain.split_first() {
+            Some((first, chain)) => {
+                first
+                    .request(
+                        ctx,
+                        request,
+                        NextRequest {
+                            chain,
+                            request_fn: self.request_fn,
+                        },
+                    )
+                    .await
+            }
+            None => (self.request_fn)(request).await,
+        }
+    }
+}
+
+/// The remaining middlewares of the subscribe phase.
+pub struct NextSubscribe<'a> {
+    pub(crate) chain: &'a [BoxMiddleware],
+}
+
+impl NextSubscribe<'_> {
+    /// Call the next middleware, or return the stream.
+    pub fn run<'s>(
+        self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+    ) -> BoxStream<'s, Response> {
+        match self.chain.split_first() {
+            Some((first, chain)) => first.subscribe(ctx, stream, NextSubscribe { chain }),
+            None => stream,
+        }
+    }
+}
+
+/// The remaining middlewares of the parse phase.
+pub struct NextParse<'a> {
+    pub(crate) chain: &'a [BoxMiddleware],
+    pub(crate) parse_fn: ParseFn<'a>,
+}
+
+impl NextParse<'_> {
+    /// Call the next middleware, or parse the query source.
+    pub async fn run(
+        self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+    ) -> Result<ExecutableDocument> {
+        match self.chain.split_first() {
+            Some((first, chain)) => {
+                first
+                    .parse(
+                        ctx,
+                        query,
+                        variables,
+                        NextParse {
+                            chain,
+                            parse_fn: self.parse_fn,
+                        },
+                    )
+                    .await
+            }
+            None => (self.parse_fn)(ctx, query, variables),
+        }
+    }
+}
+
+/// The remaining middlewares of the validation phase.
+pub struct NextValidate<'a> {
+    pub(crate) chain: &'a [BoxMiddleware],
+    pub(crate) validate_fut: BoxFuture<'a, Result<ValidationResult>>,
+}
+
+impl NextValidate<'_> {
+    /// Call the next middleware, or validate the query.
+    pub async fn run(self, ctx: &ExtensionContext<'_>) -> Result<ValidationResult> {
+        match self.chain.split_first() {
+            Some((first, chain)) => {
+                first
+                    .validate(
+                        ctx,
+                        NextValidate {
+                            chain,
+                            validate_fut: self.validate_fut,
+                        },
+                    )
+                    .await
+            }
+            None => self.validate_fut.await,
+        }
+    }
+}
+
+/// The remaining middlewares of the execution phase.
+pub struct NextExecute<'a> {
+    pub(crate) chain: &'a [BoxMiddleware],
+    pub(crate) execute_fut: BoxFuture<'a, Response>,
+}
+
+impl NextExecute<'_> {
+    /// Call the next middleware, or execute the operation.
+    pub async fn run(self, ctx: &ExtensionContext<'_>) -> Response {
+        match self.chain.split_first() {
+            Some((first, chain)) => {
+                first
+                    .execute(
+                        ctx,
+                        NextExecute {
+                            chain,
+                            execute_fut: self.execute_fut,
+                        },
+                    )
+                    .await
+            }
+            None => self.execute_fut.await,
+        }
+    }
+}
+
+/// The remaining middlewares of the resolve phase.
+pub struct NextResolve<'a> {
+    pub(crate) middlewares: &'a [BoxMiddleware],
+    /// The indexes of the remaining middlewares that use the resolve hook.
+    pub(crate) chain: &'a [usize],
+    pub(crate) resolve_fut: BoxFuture<'a, Result<serde_json::Value>>,
+}
+
+impl NextResolve<'_> {
+    /// Call the next middleware, or resolve the value.
+    pub async fn run(
+        self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+    ) -> Result<serde_json::Value> {
+        match self.chain.split_first() {
+            Some((first, chain)) => {
+                self.middlewares[*first]
+                    .resolve(
+                        ctx,
+                        info,
+                        NextResolve {
+                            middlewares: self.middlewares,
+                            chain,
+                            resolve_fut: self.resolve_fut,
+                        },
+                    )
+                    .await
+            }
+            None => self.resolve_fut.await,
+        }
+    }
+}
+
+/// Returns the indexes of the middlewares that use the resolve hook.
+pub(crate) fn resolve_middlewares(middlewares: &[BoxMiddleware]) -> Vec<usize> {
+    middlewares
+        .iter()
+        .enumerate()
+        .filter(|(_, middleware)| middleware.resolve_hooks())
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Resolve a value through the middlewares that use the resolve hook, without boxing the future if
+/// there are none.
+pub(crate) async fn resolve_with_middlewares<'a>(
+    env: &'a QueryEnv,
+    ctx: &ExtensionContext<'_>,
+    info: &ResolveInfo<'_>,
+    resolve_fut: impl Future<Output = Result<serde_json::Value>> + Send + 'a,
+) -> Result<serde_json::Value> {
+    if env.resolve_middlewares.is_empty() {
+        resolve_fut.await
+    } else {
+        NextResolve {
+            middlewares: &env.middlewares,
+            chain: &env.resolve_middlewares,
+            resolve_fut: Box::pin(resolve_fut),
+        }
+        .run(ctx, info)
+        .await
+    }
+}
@@ -1,32 +1,52 @@
 //! Extensions for schema
 
+mod adapter;
+#[cfg(feature = "apollo_tracing")]
+mod apollo_federation_tracing;
 #[cfg(feature = "apollo_persisted_queries")]
 pub mod apollo_persisted_queries;
 #[cfg(feature = "apollo_tracing")]
 mod apollo_tracing;
 #[cfg(feature = "log")]
 mod logger;
+mod metrics;
+mod middleware;
+#[cfg(feature = "opentelemetry")]
+mod opentelemetry;
+mod redact;
+#[cfg(feature = "response_cache")]
+pub mod response_cache;
 #[cfg(feature = "tracing")]
 mod tracing;
 
 use crate::context::{QueryPathNode, ResolveId};
 use crate::{Data, FieldResult, Request, Result, Variables};
 
+#[cfg(feature = "apollo_tracing")]
+pub use self::apollo_federation_tracing::{ApolloFederationTracing, IncludeFederatedTrace};
 #[cfg(feature = "apollo_tracing")]
 pub use self::apollo_tracing::ApolloTracing;
 #[cfg(feature = "log")]
-pub use self::logger::Logger;
+pub use self::logger::{Logger, LoggerField};
+pub use self::metrics::Metrics;
+pub use self::middleware::{
+    Middleware, MiddlewareFactory, NextExecute, NextParse, NextRequest, NextResolve, NextSubscribe,
+    NextValidate,
+};
+#[cfg(feature = "opentelemetry")]
+pub use self::opentelemetry::OpenTelemetry;
 #[cfg(feature = "tracing")]
 pub use self::tracing::Tracing;
 use crate::parser::types::ExecutableDocument;
+use crate::registry::Registry;
 use crate::Error;
 use serde_json::Value;
 use std::any::{Any, TypeId};
 
-pub(crate) type BoxExtension = Box<dyn Extension>;
+pub(crate) use self::adapter::ExtensionAdapter;
+pub(crate) use self::middleware::{resolve_middlewares, resolve_with_middlewares, BoxMiddleware};
 
-#[doc(hidden)]
-pub struct Extensions(pub(crate) Vec<BoxExtension>);
+pub(crate) type BoxExtension = Box<dyn Extension>;
 
 /// Context for extension
 pub struct ExtensionContext<'a> {
@@ -35,6 +55,9 @@ pub struct ExtensionContext<'a> {
 
     #[doc(hidden)]
     pub query_data: &'a Data,
+
+    #[doc(hidden)]
+    pub registry: &'a Registry,
 }
 
 impl<'a> ExtensionContext<'a> {
@@ -70,6 +93,9 @@ impl<'a> ExtensionContext<'a> {
 }
 
 /// Parameters for `Extension::resolve_field_start`
+///
+/// It is non-exhaustive, so fields can be added to it without breaking the extensions.
+#[non_exhaustive]
 pub struct ResolveInfo<'a> {
     /// Because resolver is concurrent, `Extension::resolve_field_start` and `Extension::resolve_field_end` are
     /// not strictly ordered, so each pair is identified by an id.
@@ -81,11 +107,45 @@ pub struct ResolveInfo<'a> {
     /// Parent type
     pub parent_type: &'a str,
 
+    /// Field name, for the items of a list it is the name of the list field.
+    ///
+    /// Unlike the last segment of `path_node`, it is never an alias.
+    pub name: &'a str,
+
     /// Current return type, is qualified name.
     pub return_type: &'a str,
 }
 
+impl<'a> ResolveInfo<'a> {
+    #[doc(hidden)]
+    pub fn new(
+        resolve_id: ResolveId,
+        path_node: &'a QueryPathNode<'a>,
+        parent_type: &'a str,
+        name: &'a str,
+        return_type: &'a str,
+    ) -> Self {
+        Self {
+            resolve_id,
+            path_node,
+            parent_type,
+            name,
+            return_type,
+        }
+    }
+}
+
+/// The state of an extension for a field, returned by `Extension::resolve_start` and passed to
+/// `Extension::resolve_end`.
+pub type ResolveState = Option<Box<dyn Any + Send>>;
+
 /// Represents a GraphQL extension
+///
+/// An extension instance is created for each request. Fields are resolved concurrently, so the
+/// methods take `&self` and an extension keeps its state with interior mutability.
+///
+/// The callbacks are a simpler form of [`Middleware`](trait.Middleware.html), the extensions are
+/// called by an adapter that is the innermost middleware of the schema.
 #[async_trait::async_trait]
 #[allow(unused_variables)]
 pub trait Extension: Sync + Send + 'static {
@@ -94,9 +154,17 @@ pub trait Extension: Sync + Send + 'static {
         None
     }
 
+    /// Returns `true` if this extension uses `resolve_start` and `resolve_end`.
+    ///
+    /// They are called for every field, so they are skipped entirely for the extensions that
+    /// return `false`.
+    fn resolve_hooks(&self) -> bool {
+        false
+    }
+
     /// Called at the prepare request
     async fn prepare_request(
-        &mut self,
+        &self,
         ctx: &ExtensionContext<'_>,
         request: Request,
     ) -> Result<Request> {
@@ -104,136 +172,238 @@ pub trait Extension: Sync + Send + 'static {
     }
 
     /// Called at the begin of the parse.
-    fn parse_start(
-        &mut self,
-        ctx: &ExtensionContext<'_>,
-        query_source: &str,
-        variables: &Variables,
-    ) {
-    }
+    fn parse_start(&self, ctx: &ExtensionContext<'_>, query_source: &str, variables: &Variables) {}
 
     /// Called at the end of the parse.
-    fn parse_end(&mut self, ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {}
+    fn parse_end(&self, ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {}
+
+    /// Called after the parse and before the validation, the document and the variables can be
+    /// modified.
+    ///
+    /// Use `document.operations.get_mut(operation_name)` to get the operation that will be
+    /// executed. Returning an error ends the request with it.
+    // The hooks return the public `Error`, boxing it would break the API.
+    #[allow(clippy::result_large_err)]
+    fn prepare_document(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        document: &mut ExecutableDocument,
+        operation_name: Option<&str>,
+        variables: &mut Variables,
+    ) -> Result<()> {
+        Ok(())
+    }
 
     /// Called at the begin of the validation.
-    fn validation_start(&mut self, ctx: &ExtensionContext<'_>) {}
+    fn validation_start(&self, ctx: &ExtensionContext<'_>) {}
 
     /// Called at the end of the validation.
-    fn validation_end(&mut self, ctx: &ExtensionContext<'_>) {}
+    fn validation_end(&self, ctx: &ExtensionContext<'_>) {}
 
     /// Called at the begin of the execution.
-    fn execution_start(&mut self, ctx: &ExtensionContext<'_>) {}
+    fn execution_start(&self, ctx: &ExtensionContext<'_>) {}
 
     /// Called at the end of the execution.
-    fn execution_end(&mut self, ctx: &ExtensionContext<'_>) {}
+    fn execution_end(&self, ctx: &ExtensionContext<'_>) {}
+
+    /// Called at the begin of the resolve field, if `resolve_hooks` returns `true`.
+    ///
+    /// The returned state is passed to `resolve_end` of the same field.
+    fn resolve_start(&self, ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) -> ResolveState {
+        None
+    }
+
+    /// Called at the end of the resolve field, if `resolve_hooks` returns `true`.
+    ///
+    /// It is not called if the field returns an error.
+    fn resolve_end(&self, ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>, state: ResolveState) {
+    }
 
-    /// Called at the begin of the resolve field.
-    fn resolve_start(&mut self, ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {}
+    /// Called when the streams of a subscription have been created.
+    fn subscription_start(&self, ctx: &ExtensionContext<'_>) {}
 
-    /// Called at the end of the resolve field.
-    fn resolve_end(&mut self, ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {}
+    /// Called for every event of a subscription, before `result`.
+    fn subscription_event(&self, ctx: &ExtensionContext<'_>, data: &serde_json::Value) {}
+
+    /// Called when a subscription ends, after its last event or an error, or when its stream is
+    /// dropped.
+    ///
+    /// It is called only if `subscription_start` was called.
+    fn subscription_end(&self, ctx: &ExtensionContext<'_>) {}
 
     /// Called when an error occurs.
-    fn error(&mut self, ctx: &ExtensionContext<'_>, err: &Error) {}
+    fn error(&self, ctx: &ExtensionContext<'_>, err: &Error) {}
 
     /// Get the results
-    fn result(&mut self, ctx: &ExtensionContext<'_>) -> Option<serde_json::Value> {
+    ///
+    /// For subscriptions it is called for every event.
+    fn result(&self, ctx: &ExtensionContext<'_>) -> Option<serde_json::Value> {
         None
     }
 }
 
 pub(crate) trait ErrorLogger {
-    fn log_error(self, ctx: &ExtensionContext<'_>, extensions: &spin::Mutex<Extensions>) -> Self;
+    fn log_error(self, ctx: &ExtensionContext<'_>, extensions: &Extensions) -> Self;
 }
 
 impl<T> ErrorLogger for Result<T> {
-    fn log_error(self, ctx: &ExtensionContext<'_>, extensions: &spin::Mutex<Extensions>) -> Self {
+    fn log_error(self, ctx: &ExtensionContext<'_>, extensions: &Extensions) -> Self {
         if let Err(err) = &self {
-            extensions.lock().error(ctx, err);
+            extensions.error(ctx, err);
         }
         self
     }
 }
 
-#[async_trait::async_trait]
-impl Extension for Extensions {
-    async fn prepare_request(
-        &mut self,
+#[doc(hidden)]
+pub struct Extensions {
+    extensions: Vec<BoxExtension>,
+    /// The extensions that use the resolve hooks.
+    resolve_extensions: Vec<usize>,
+}
+
+impl Extensions {
+    pub(crate) fn new(extensions: Vec<BoxExtension>) -> Self {
+        let resolve_extensions = extensions
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.resolve_hooks())
+            .map(|(idx, _)| idx)
+            .collect();
+        Self {
+            extensions,
+            resolve_extensions,
+        }
+    }
+
+    pub(crate) async fn prepare_request(
+        &self,
         ctx: &ExtensionContext<'_>,
         request: Request,
     ) -> Result<Request> {
         let mut request = request;
-        for e in self.0.iter_mut() {
+        for e in &self.extensions {
             request = e.prepare_request(ctx, request).await?;
         }
         Ok(request)
     }
 
-    fn parse_start(
-        &mut self,
+    pub(crate) fn parse_start(
+        &self,
         ctx: &ExtensionContext<'_>,
         query_source: &str,
         variables: &Variables,
     ) {
-        self.0
-            .iter_mut()
-            .for_each(|e| e.parse_start(ctx, query_source, variables));
+        for e in &self.extensions {
+            e.parse_start(ctx, query_source, variables);
+        }
     }
 
-    fn parse_end(&mut self, ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {
-        self.0.iter_mut().for_each(|e| e.parse_end(ctx, document));
+    pub(crate) fn parse_end(&self, ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {
+        for e in &self.extensions {
+            e.parse_end(ctx, document);
+        }
     }
 
-    fn validation_start(&mut self, ctx: &ExtensionContext<'_>) {
-        self.0.iter_mut().for_each(|e| e.validation_start(ctx));
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn prepare_document(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        document: &mut ExecutableDocument,
+        operation_name: Option<&str>,
+        variables: &mut Variables,
+    ) -> Result<()> {
+        for e in &self.extensions {
+            e.prepare_document(ctx, document, operation_name, variables)?;
+        }
+        Ok(())
     }
 
-    fn validation_end(&mut self, ctx: &ExtensionContext<'_>) {
-        self.0.iter_mut().for_each(|e| e.validation_end(ctx));
+    pub(crate) fn validation_start(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.validation_start(ctx);
+        }
     }
 
-    fn execution_start(&mut self, ctx: &ExtensionContext<'_>) {
-        self.0.iter_mut().for_each(|e| e.execution_start(ctx));
+    pub(crate) fn validation_end(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.validation_end(ctx);
+        }
     }
 
-    fn execution_end(&mut self, ctx: &ExtensionContext<'_>) {
-        self.0.iter_mut().for_each(|e| e.execution_end(ctx));
+    pub fn execution_start(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.execution_start(ctx);
+        }
     }
 
-    fn resolve_start(&mut self, ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        self.0.iter_mut().for_each(|e| e.resolve_start(ctx, info));
+    pub fn execution_end(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.execution_end(ctx);
+        }
     }
 
-    fn resolve_end(&mut self, ctx: &ExtensionContext<'_>, resolve_id: &ResolveInfo<'_>) {
-        self.0
-            .iter_mut()
-            .for_each(|e| e.resolve_end(ctx, resolve_id));
+    /// Returns the states of the extensions that use the resolve hooks, it doesn't allocate if
+    /// there are none.
+    pub fn resolve_start(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+    ) -> Vec<ResolveState> {
+        self.resolve_extensions
+            .iter()
+            .map(|idx| self.extensions[*idx].resolve_start(ctx, info))
+            .collect()
     }
 
-    fn error(&mut self, ctx: &ExtensionContext<'_>, err: &Error) {
-        self.0.iter_mut().for_each(|e| e.error(ctx, err));
+    pub fn resolve_end(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        states: Vec<ResolveState>,
+    ) {
+        for (idx, state) in self.resolve_extensions.iter().zip(states) {
+            self.extensions[*idx].resolve_end(ctx, info, state);
+        }
     }
 
-    fn result(&mut self, ctx: &ExtensionContext<'_>) -> Option<Value> {
-        if !self.0.is_empty() {
-            let value = self
-                .0
-                .iter_mut()
-                .filter_map(|e| {
-                    if let Some(name) = e.name() {
-                        e.result(ctx).map(|res| (name.to_string(), res))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<serde_json::Map<_, _>>();
-            if value.is_empty() {
-                None
-            } else {
-                Some(value.into())
-            }
-        } else {
+    pub(crate) fn subscription_start(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.subscription_start(ctx);
+        }
+    }
+
+    pub(crate) fn subscription_event(&self, ctx: &ExtensionContext<'_>, data: &Value) {
+        for e in &self.extensions {
+            e.subscription_event(ctx, data);
+        }
+    }
+
+    pub(crate) fn subscription_end(&self, ctx: &ExtensionContext<'_>) {
+        for e in &self.extensions {
+            e.subscription_end(ctx);
+        }
+    }
+
+    pub(crate) fn error(&self, ctx: &ExtensionContext<'_>, err: &Error) {
+        for e in &self.extensions {
+            e.error(ctx, err);
+        }
+    }
+
+    pub(crate) fn result(&self, ctx: &ExtensionContext<'_>) -> Option<Value> {
+        let value = self
+            .extensions
+            .iter()
+            .filter_map(|e| {
+                e.name()
+                    .and_then(|name| e.result(ctx).map(|res| (name.to_string(), res)))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        if value.is_empty() {
             None
+        } else {
+            Some(value.into())
         }
     }
 }
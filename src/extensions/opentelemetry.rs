@@ -0,0 +1,334 @@
+use crate::extensions::{
+    ExtensionContext, Middleware, MiddlewareFactory, NextExecute, NextParse, NextRequest,
+    NextResolve, NextSubscribe, NextValidate, ResolveInfo,
+};
+use crate::parser::types::{DocumentOperations, ExecutableDocument, OperationType};
+use crate::{Error, Request, Response, Result, ValidationResult, Variables};
+use futures::stream::{BoxStream, StreamExt};
+use opentelemetry::metrics::{Counter, Histogram, Meter, Unit};
+use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context as OpenTelemetryContext, KeyValue};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const KEY_OPERATION_NAME: &str = "graphql.operation.name";
+const KEY_OPERATION_TYPE: &str = "graphql.operation.type";
+const KEY_DOCUMENT: &str = "graphql.document";
+const KEY_FIELD_PATH: &str = "graphql.field.path";
+const KEY_FIELD_PARENT_TYPE: &str = "graphql.field.parent_type";
+const KEY_FIELD_RETURN_TYPE: &str = "graphql.field.return_type";
+
+/// OpenTelemetry extension
+///
+/// It is a middleware, add it with `SchemaBuilder::middleware`. Each request is recorded as a
+/// server span with the attributes of the
+/// [GraphQL semantic conventions](https://opentelemetry.io/docs/specs/semconv/graphql/graphql-spans/),
+/// and the parsing, validation, execution and every resolved field are recorded as its children.
+/// Errors are added to the spans as `exception` events.
+///
+/// A subscription is recorded as a server span that lasts until its stream ends or is dropped,
+/// with the parsing and validation as its children, and a `subscription event` child span for
+/// every response it sends.
+///
+/// With [`with_meter`](#method.with_meter), the duration of the requests is recorded in the
+/// `graphql.request.duration` histogram (in seconds) and the number of errors in the
+/// `graphql.request.errors` counter, both with the operation type and name attributes. They are
+/// not recorded for subscriptions.
+///
+/// # References
+///
+/// <https://crates.io/crates/opentelemetry>
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "opentelemetry")))]
+pub struct OpenTelemetry<T> {
+    tracer: Arc<T>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+struct Metrics {
+    request_duration: Histogram<f64>,
+    request_errors: Counter<u64>,
+}
+
+impl<T> OpenTelemetry<T> {
+    /// Create an OpenTelemetry extension with the tracer.
+    pub fn new(tracer: T) -> Self {
+        Self {
+            tracer: Arc::new(tracer),
+            metrics: None,
+        }
+    }
+
+    /// Record the request metrics with the meter.
+    pub fn with_meter(self, meter: &Meter) -> Self {
+        Self {
+            metrics: Some(Arc::new(Metrics {
+                request_duration: meter
+                    .f64_histogram("graphql.request.duration")
+                    .with_description("The duration of the GraphQL requests.")
+                    .with_unit(Unit::new("s"))
+                    .init(),
+                request_errors: meter
+                    .u64_counter("graphql.request.errors")
+                    .with_description("The number of errors returned by the GraphQL requests.")
+                    .init(),
+            })),
+            ..self
+        }
+    }
+}
+
+impl<T> MiddlewareFactory for OpenTelemetry<T>
+where
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(OpenTelemetryMiddleware {
+            tracer: self.tracer.clone(),
+            metrics: self.metrics.clone(),
+            operation: Default::default(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Operation {
+    ty: Option<&'static str>,
+    name: Option<String>,
+}
+
+impl Operation {
+    fn attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = Vec::new();
+        if let Some(ty) = self.ty {
+            attributes.push(KeyValue::new(KEY_OPERATION_TYPE, ty));
+        }
+        if let Some(name) = &self.name {
+            attributes.push(KeyValue::new(KEY_OPERATION_NAME, name.clone()));
+        }
+        attributes
+    }
+}
+
+struct OpenTelemetryMiddleware<T> {
+    tracer: Arc<T>,
+    metrics: Option<Arc<Metrics>>,
+    operation: Mutex<Operation>,
+}
+
+impl<T> OpenTelemetryMiddleware<T>
+where
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    fn operation_context(&self) -> OpenTelemetryContext {
+        let span = self
+            .tracer
+            .span_builder("GraphQL Operation")
+            .with_kind(SpanKind::Server)
+            .start_with_context(&*self.tracer, &OpenTelemetryContext::current());
+        OpenTelemetryContext::current_with_span(span)
+    }
+
+    fn child_context(&self, name: &'static str, attributes: Vec<KeyValue>) -> OpenTelemetryContext {
+        let span = self
+            .tracer
+            .span_builder(name)
+            .with_attributes(attributes)
+            .start_with_context(&*self.tracer, &OpenTelemetryContext::current());
+        OpenTelemetryContext::current_with_span(span)
+    }
+}
+
+fn operation_type_name(ty: OperationType) -> &'static str {
+    match ty {
+        OperationType::Query => "query",
+        OperationType::Mutation => "mutation",
+        OperationType::Subscription => "subscription",
+    }
+}
+
+fn error_count(err: &Error) -> u64 {
+    match err {
+        Error::Rule { errors } => errors.len() as u64,
+        _ => 1,
+    }
+}
+
+/// Ends the span of a subscription when its stream is dropped.
+struct EndSpanGuard(OpenTelemetryContext);
+
+impl Drop for EndSpanGuard {
+    fn drop(&mut self) {
+        self.0.span().end();
+    }
+}
+
+fn record_error(cx: &OpenTelemetryContext, err: &Error) {
+    let span = cx.span();
+    span.add_event(
+        "exception",
+        vec![KeyValue::new("exception.message", err.to_string())],
+    );
+    span.set_status(Status::error(err.to_string()));
+}
+
+#[async_trait::async_trait]
+impl<T> Middleware for OpenTelemetryMiddleware<T>
+where
+    T: Tracer + Send + Sync + 'static,
+    T::Span: Send + Sync + 'static,
+{
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        self.operation.lock().unwrap().name = request.operation_name.clone();
+
+        let cx = self.operation_context();
+        let start_time = Instant::now();
+        let resp = next.run(ctx, request).with_context(cx.clone()).await;
+        let duration = start_time.elapsed();
+
+        if let Some(err) = &resp.error {
+            record_error(&cx, err);
+        }
+        let attributes = self.operation.lock().unwrap().attributes();
+        cx.span().end();
+
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .request_duration
+                .record(duration.as_secs_f64(), &attributes);
+            if let Some(err) = &resp.error {
+                metrics.request_errors.add(error_count(err), &attributes);
+            }
+        }
+        resp
+    }
+
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        // The operation name is unknown, it is set by `parse` from the document.
+        let cx = self.operation_context();
+        let tracer = self.tracer.clone();
+        let guard = EndSpanGuard(cx.clone());
+        next.run(ctx, stream)
+            .map(move |resp| {
+                let cx = &guard.0;
+                let span = tracer
+                    .span_builder("subscription event")
+                    .start_with_context(&*tracer, cx);
+                let event_cx = cx.with_span(span);
+                if let Some(err) = &resp.error {
+                    record_error(&event_cx, err);
+                    record_error(cx, err);
+                }
+                event_cx.span().end();
+                resp
+            })
+            .with_context(cx)
+            .boxed()
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        let request_cx = OpenTelemetryContext::current();
+        let cx = self.child_context("parse", Vec::new());
+        let res = next
+            .run(ctx, query, variables)
+            .with_context(cx.clone())
+            .await;
+
+        match &res {
+            Ok(document) => {
+                let mut operation = self.operation.lock().unwrap();
+                if let Some(op) = document.operations.get(operation.name.as_deref()) {
+                    operation.ty = Some(operation_type_name(op.node.ty));
+                }
+                if let (None, DocumentOperations::Multiple(ops)) =
+                    (&operation.name, &document.operations)
+                {
+                    if ops.len() == 1 {
+                        operation.name = ops.keys().next().map(|name| name.to_string());
+                    }
+                }
+
+                let request_span = request_cx.span();
+                request_span.set_attributes(operation.attributes());
+                request_span.set_attribute(KeyValue::new(
+                    KEY_DOCUMENT,
+                    ctx.stringify_execute_doc(document),
+                ));
+                // The span name is "{operation type} {operation name}" as in the conventions.
+                if let Some(ty) = operation.ty {
+                    request_span.update_name(match &operation.name {
+                        Some(name) => format!("{} {}", ty, name),
+                        None => ty.to_string(),
+                    });
+                }
+            }
+            Err(err) => record_error(&cx, err),
+        }
+        cx.span().end();
+        res
+    }
+
+    async fn validate(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidate<'_>,
+    ) -> Result<ValidationResult> {
+        let cx = self.child_context("validation", Vec::new());
+        let res = next.run(ctx).with_context(cx.clone()).await;
+        if let Err(err) = &res {
+            record_error(&cx, err);
+        }
+        cx.span().end();
+        res
+    }
+
+    async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+        let cx = self.child_context("execute", Vec::new());
+        let resp = next.run(ctx).with_context(cx.clone()).await;
+        cx.span().end();
+        resp
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        let cx = self.child_context(
+            "field",
+            vec![
+                KeyValue::new(KEY_FIELD_PATH, info.path_node.to_string()),
+                KeyValue::new(KEY_FIELD_PARENT_TYPE, info.parent_type.to_string()),
+                KeyValue::new(KEY_FIELD_RETURN_TYPE, info.return_type.to_string()),
+            ],
+        );
+        let res = next.run(ctx, info).with_context(cx.clone()).await;
+        if let Err(err) = &res {
+            record_error(&cx, err);
+        }
+        cx.span().end();
+        res
+    }
+}
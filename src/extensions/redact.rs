@@ -0,0 +1,347 @@
+use crate::error::SECRET_PLACEHOLDER;
+use crate::extensions::ExtensionContext;
+use crate::parser::types::{
+    Directive, ExecutableDocument, Name, OperationDefinition, OperationType, Selection,
+    SelectionSet, Value as InputValue,
+};
+use crate::registry::{MetaType, MetaTypeName, Registry};
+use crate::{Positioned, Value, Variables};
+use std::fmt::{self, Write};
+
+impl<'a> ExtensionContext<'a> {
+    /// Convert the document to a single line string.
+    ///
+    /// The values of arguments and input fields marked as `secret` are replaced with `"******"`,
+    /// so the result is safe to write to logs.
+    pub fn stringify_execute_doc(&self, doc: &ExecutableDocument) -> String {
+        let mut stringifier = Stringifier::new(self.registry);
+        let mut output = String::new();
+        stringifier.write_document(&mut output, doc).ok();
+        output
+    }
+
+    /// Returns a copy of the variables where every value that is used as a `secret` argument or
+    /// input field in the document is replaced with `"******"`.
+    pub fn redact_variables(&self, doc: &ExecutableDocument, variables: &Variables) -> Variables {
+        let mut stringifier = Stringifier::new(self.registry);
+        stringifier.write_document(&mut String::new(), doc).ok();
+
+        let mut variables = variables.clone();
+        for (name, ty, secret) in stringifier.variable_uses {
+            if let Some(value) = variables.0.get_mut(name) {
+                if secret {
+                    *value = secret_value();
+                } else {
+                    redact_value(self.registry, value, ty);
+                }
+            }
+        }
+        variables
+    }
+}
+
+fn secret_value() -> Value {
+    Value::String(SECRET_PLACEHOLDER.to_string())
+}
+
+fn list_item_type(ty: &str) -> Option<&str> {
+    match MetaTypeName::create(ty) {
+        MetaTypeName::NonNull(ty) => list_item_type(ty),
+        MetaTypeName::List(ty) => Some(ty),
+        MetaTypeName::Named(_) => None,
+    }
+}
+
+fn redact_value(registry: &Registry, value: &mut Value, ty: &str) {
+    match value {
+        Value::List(items) => {
+            if let Some(item_ty) = list_item_type(ty) {
+                for item in items {
+                    redact_value(registry, item, item_ty);
+                }
+            }
+        }
+        Value::Object(map) => {
+            if let Some(MetaType::InputObject { input_fields, .. }) =
+                registry.concrete_type_by_name(ty)
+            {
+                for (name, value) in map.iter_mut() {
+                    if let Some(field) = input_fields.get(name.as_str()) {
+                        if field.is_secret {
+                            *value = secret_value();
+                        } else {
+                            redact_value(registry, value, &field.ty);
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+struct Stringifier<'a> {
+    registry: &'a Registry,
+
+    /// Every use of a variable in the document, with the input type of its position and whether
+    /// that position is secret.
+    variable_uses: Vec<(&'a Name, &'a str, bool)>,
+}
+
+impl<'a> Stringifier<'a> {
+    fn new(registry: &'a Registry) -> Self {
+        Self {
+            registry,
+            variable_uses: Vec::new(),
+        }
+    }
+
+    fn write_document(&mut self, f: &mut String, doc: &'a ExecutableDocument) -> fmt::Result {
+        let mut operations = doc.operations.iter().collect::<Vec<_>>();
+        operations.sort_unstable_by_key(|(name, _)| *name);
+        let mut fragments = doc.fragments.iter().collect::<Vec<_>>();
+        fragments.sort_unstable_by_key(|(name, _)| *name);
+
+        // Selection sets are written first so that all variable uses are known when the default
+        // values of the variable definitions are written.
+        let mut operation_bodies = Vec::new();
+        for (_, operation) in &operations {
+            let mut body = String::new();
+            let root_type = match operation.node.ty {
+                OperationType::Query => Some(&self.registry.query_type),
+                OperationType::Mutation => self.registry.mutation_type.as_ref(),
+                OperationType::Subscription => self.registry.subscription_type.as_ref(),
+            }
+            .and_then(|name| self.registry.types.get(name));
+            self.write_directives(&mut body, &operation.node.directives)?;
+            self.write_selection_set(&mut body, root_type, &operation.node.selection_set)?;
+            operation_bodies.push(body);
+        }
+
+        let mut fragment_bodies = Vec::new();
+        for (_, fragment) in &fragments {
+            let mut body = String::new();
+            let ty = self
+                .registry
+                .types
+                .get(fragment.node.type_condition.node.on.node.as_str());
+            self.write_directives(&mut body, &fragment.node.directives)?;
+            self.write_selection_set(&mut body, ty, &fragment.node.selection_set)?;
+            fragment_bodies.push(body);
+        }
+
+        let mut first = true;
+        for ((name, operation), body) in operations.into_iter().zip(operation_bodies) {
+            if !first {
+                f.write_char(' ')?;
+            }
+            first = false;
+            self.write_operation_header(f, name, &operation.node)?;
+            f.write_str(&body)?;
+        }
+        for ((name, fragment), body) in fragments.into_iter().zip(fragment_bodies) {
+            if !first {
+                f.write_char(' ')?;
+            }
+            first = false;
+            write!(
+                f,
+                "fragment {} on {}",
+                name, fragment.node.type_condition.node.on.node
+            )?;
+            f.write_str(&body)?;
+        }
+        Ok(())
+    }
+
+    fn write_operation_header(
+        &self,
+        f: &mut String,
+        name: Option<&Name>,
+        operation: &OperationDefinition,
+    ) -> fmt::Result {
+        write!(f, "{}", operation.ty)?;
+        if let Some(name) = name {
+            write!(f, " {}", name)?;
+        }
+        if !operation.variable_definitions.is_empty() {
+            f.write_char('(')?;
+            for (idx, definition) in operation.variable_definitions.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(", ")?;
+                }
+                let definition = &definition.node;
+                write!(f, "${}: {}", definition.name.node, definition.var_type.node)?;
+                if let Some(default_value) = &definition.default_value {
+                    let mut value = default_value.node.clone();
+                    for (_, ty, secret) in self
+                        .variable_uses
+                        .iter()
+                        .filter(|(name, _, _)| **name == definition.name.node)
+                    {
+                        if *secret {
+                            value = secret_value();
+                        } else {
+                            redact_value(self.registry, &mut value, ty);
+                        }
+                    }
+                    write!(f, " = {}", value)?;
+                }
+            }
+            f.write_char(')')?;
+        }
+        Ok(())
+    }
+
+    fn write_directives(
+        &mut self,
+        f: &mut String,
+        directives: &'a [Positioned<Directive>],
+    ) -> fmt::Result {
+        for directive in directives {
+            write!(f, " @{}", directive.node.name.node)?;
+            let meta_directive = self
+                .registry
+                .directives
+                .get(directive.node.name.node.as_str());
+            if !directive.node.arguments.is_empty() {
+                f.write_char('(')?;
+                for (idx, (name, value)) in directive.node.arguments.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: ", name.node)?;
+                    let arg = meta_directive.and_then(|d| d.args.get(name.node.as_str()));
+                    self.write_value(
+                        f,
+                        &value.node,
+                        arg.map(|arg| arg.ty.as_str()),
+                        arg.map(|arg| arg.is_secret).unwrap_or_default(),
+                    )?;
+                }
+                f.write_char(')')?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_selection_set(
+        &mut self,
+        f: &mut String,
+        ty: Option<&'a MetaType>,
+        selection_set: &'a Positioned<SelectionSet>,
+    ) -> fmt::Result {
+        if selection_set.node.items.is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(" {")?;
+        for selection in &selection_set.node.items {
+            f.write_char(' ')?;
+            match &selection.node {
+                Selection::Field(field) => {
+                    let field = &field.node;
+                    if let Some(alias) = &field.alias {
+                        write!(f, "{}: ", alias.node)?;
+                    }
+                    write!(f, "{}", field.name.node)?;
+
+                    let meta_field = ty.and_then(|ty| ty.field_by_name(&field.name.node));
+                    if !field.arguments.is_empty() {
+                        f.write_char('(')?;
+                        for (idx, (name, value)) in field.arguments.iter().enumerate() {
+                            if idx > 0 {
+                                f.write_str(", ")?;
+                            }
+                            write!(f, "{}: ", name.node)?;
+                            let arg =
+                                meta_field.and_then(|field| field.args.get(name.node.as_str()));
+                            self.write_value(
+                                f,
+                                &value.node,
+                                arg.map(|arg| arg.ty.as_str()),
+                                arg.map(|arg| arg.is_secret).unwrap_or_default(),
+                            )?;
+                        }
+                        f.write_char(')')?;
+                    }
+
+                    self.write_directives(f, &field.directives)?;
+                    let field_ty =
+                        meta_field.and_then(|field| self.registry.concrete_type_by_name(&field.ty));
+                    self.write_selection_set(f, field_ty, &field.selection_set)?;
+                }
+                Selection::FragmentSpread(fragment_spread) => {
+                    write!(f, "...{}", fragment_spread.node.fragment_name.node)?;
+                    self.write_directives(f, &fragment_spread.node.directives)?;
+                }
+                Selection::InlineFragment(inline_fragment) => {
+                    f.write_str("...")?;
+                    let ty = match &inline_fragment.node.type_condition {
+                        Some(type_condition) => {
+                            write!(f, " on {}", type_condition.node.on.node)?;
+                            self.registry
+                                .types
+                                .get(type_condition.node.on.node.as_str())
+                        }
+                        None => ty,
+                    };
+                    self.write_directives(f, &inline_fragment.node.directives)?;
+                    self.write_selection_set(f, ty, &inline_fragment.node.selection_set)?;
+                }
+            }
+        }
+        f.write_str(" }")
+    }
+
+    fn write_value(
+        &mut self,
+        f: &mut String,
+        value: &'a InputValue,
+        ty: Option<&'a str>,
+        secret: bool,
+    ) -> fmt::Result {
+        match value {
+            InputValue::Variable(name) => {
+                if let Some(ty) = ty {
+                    self.variable_uses.push((name, ty, secret));
+                }
+                write!(f, "${}", name)
+            }
+            _ if secret => write!(f, "{}", secret_value()),
+            InputValue::List(items) => {
+                let item_ty = ty.and_then(list_item_type);
+                f.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    self.write_value(f, item, item_ty, false)?;
+                }
+                f.write_char(']')
+            }
+            InputValue::Object(map) => {
+                let input_fields = match ty.and_then(|ty| self.registry.concrete_type_by_name(ty)) {
+                    Some(MetaType::InputObject { input_fields, .. }) => Some(input_fields),
+                    _ => None,
+                };
+                f.write_char('{')?;
+                for (idx, (name, value)) in map.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: ", name)?;
+                    let field = input_fields.and_then(|fields| fields.get(name.as_str()));
+                    self.write_value(
+                        f,
+                        value,
+                        field.map(|field| field.ty.as_str()),
+                        field.map(|field| field.is_secret).unwrap_or_default(),
+                    )?;
+                }
+                f.write_char('}')
+            }
+            _ => write!(f, "{}", value),
+        }
+    }
+}
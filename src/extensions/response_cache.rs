@@ -0,0 +1,347 @@
+//! Response cache extension.
+
+use crate::extensions::{ExtensionContext, Middleware, MiddlewareFactory, NextParse, NextRequest};
+use crate::parser::types::{ExecutableDocument, OperationType, Selection, SelectionSet};
+use crate::registry::{MetaTypeName, Registry};
+use crate::{CacheControl, Request, Response, Result, Variables};
+use futures::lock::Mutex as AsyncMutex;
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Request data that identifies the session of the user.
+///
+/// The responses whose `CacheControl` is private are only cached for the requests that contain
+/// this data, and only returned to the requests with the same session key.
+///
+/// It is the only request data used by the cache key, the responses of the resolvers that read
+/// other request data must be private, or they are returned to requests with different data.
+pub struct SessionKey(pub String);
+
+/// A cached response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// The data of the response.
+    pub data: serde_json::Value,
+
+    /// Whether the response is public.
+    pub public: bool,
+
+    /// The max age of the response in seconds.
+    pub max_age: usize,
+
+    /// The time after which the response must not be returned.
+    pub expires_at: SystemTime,
+
+    /// The names of the types that were selected to build the response.
+    pub types: Vec<String>,
+}
+
+impl CachedResponse {
+    /// Returns `true` if the response has expired.
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at
+    }
+}
+
+/// Cache storage for responses.
+#[async_trait::async_trait]
+pub trait CacheStorage: Send + Sync + Clone + 'static {
+    /// Load the response by `key`.
+    ///
+    /// The extension ignores the expired responses, so the storage may keep them.
+    async fn get(&self, key: String) -> Option<CachedResponse>;
+
+    /// Save the response by `key`.
+    async fn set(&self, key: String, response: CachedResponse);
+
+    /// Remove all the responses that selected the type.
+    async fn invalidate(&self, type_name: String);
+}
+
+/// Memory-based LRU cache.
+#[derive(Clone)]
+pub struct LruCacheStorage(Arc<AsyncMutex<lru::LruCache<String, CachedResponse>>>);
+
+impl LruCacheStorage {
+    /// Creates a new LRU Cache that holds at most `cap` responses.
+    pub fn new(cap: usize) -> Self {
+        Self(Arc::new(AsyncMutex::new(lru::LruCache::new(cap))))
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for LruCacheStorage {
+    async fn get(&self, key: String) -> Option<CachedResponse> {
+        let mut cache = self.0.lock().await;
+        match cache.get(&key) {
+            Some(response) if response.is_expired() => {
+                cache.pop(&key);
+                None
+            }
+            Some(response) => Some(response.clone()),
+            None => None,
+        }
+    }
+
+    async fn set(&self, key: String, response: CachedResponse) {
+        let mut cache = self.0.lock().await;
+        cache.put(key, response);
+    }
+
+    async fn invalidate(&self, type_name: String) {
+        let mut cache = self.0.lock().await;
+        let keys = cache
+            .iter()
+            .filter(|(_, response)| response.types.contains(&type_name))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in keys {
+            cache.pop(&key);
+        }
+    }
+}
+
+/// Response cache extension.
+///
+/// It is a middleware, add it with `SchemaBuilder::middleware`. The successful responses of the
+/// queries whose `CacheControl` has a `max_age` are stored in the cache storage, keyed by the
+/// query source, the operation name and the variables, and returned without executing the
+/// query until they expire.
+///
+/// The private responses are only cached for the requests that contain a [`SessionKey`], and
+/// keyed by it.
+///
+/// Only the data and the cache control of a response are cached. The extensions of a response,
+/// such as the tracing results, describe its execution, so a cached response has none.
+///
+/// Keep a clone of the extension to remove the responses that selected a type with
+/// [`invalidate`](#method.invalidate), for example after a mutation.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::extensions::response_cache::{LruCacheStorage, ResponseCache};
+///
+/// struct Query;
+///
+/// #[Object(cache_control(max_age = 60))]
+/// impl Query {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let cache = ResponseCache::new(LruCacheStorage::new(1024));
+///     let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+///         .middleware(cache.clone())
+///         .finish();
+///     schema.execute("{ value }").await;
+///     cache.invalidate("Query").await;
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ResponseCache<T> {
+    storage: T,
+}
+
+impl<T: CacheStorage> ResponseCache<T> {
+    /// Creates a response cache extension.
+    pub fn new(storage: T) -> Self {
+        Self { storage }
+    }
+
+    /// Remove all the cached responses that selected the type.
+    pub async fn invalidate(&self, type_name: &str) {
+        self.storage.invalidate(type_name.to_string()).await;
+    }
+}
+
+impl<T: CacheStorage> MiddlewareFactory for ResponseCache<T> {
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(ResponseCacheMiddleware {
+            storage: self.storage.clone(),
+            operation: Default::default(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Operation {
+    name: Option<String>,
+    is_query: bool,
+    types: Vec<String>,
+}
+
+struct ResponseCacheMiddleware<T> {
+    storage: T,
+    operation: Mutex<Operation>,
+}
+
+fn cache_key(session: Option<&str>, request: &Request) -> String {
+    serde_json::json!([
+        session,
+        request.operation_name,
+        request.variables,
+        request.query
+    ])
+    .to_string()
+}
+
+struct TypeCollector<'a> {
+    registry: &'a Registry,
+    document: &'a ExecutableDocument,
+    types: HashSet<String>,
+    fragments: HashSet<&'a str>,
+}
+
+impl<'a> TypeCollector<'a> {
+    fn add_type(&mut self, type_name: &str) {
+        if let Some(ty) = self.registry.types.get(type_name) {
+            if let Some(possible_types) = ty.possible_types() {
+                self.types.extend(possible_types.iter().cloned());
+            }
+        }
+        self.types.insert(type_name.to_string());
+    }
+
+    fn visit_selection_set(&mut self, type_name: &str, selection_set: &'a SelectionSet) {
+        for selection in &selection_set.items {
+            match &selection.node {
+                Selection::Field(field) => {
+                    let ty = self
+                        .registry
+                        .types
+                        .get(type_name)
+                        .and_then(|ty| ty.field_by_name(&field.node.name.node))
+                        .map(|field| MetaTypeName::concrete_typename(&field.ty).to_string());
+                    if let Some(ty) = ty {
+                        self.add_type(&ty);
+                        self.visit_selection_set(&ty, &field.node.selection_set.node);
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.as_str();
+                    if !self.fragments.insert(name) {
+                        continue;
+                    }
+                    if let Some(fragment) = self.document.fragments.get(name) {
+                        let ty = fragment.node.type_condition.node.on.node.as_str();
+                        self.add_type(ty);
+                        self.visit_selection_set(ty, &fragment.node.selection_set.node);
+                    }
+                }
+                Selection::InlineFragment(fragment) => {
+                    let ty = match &fragment.node.type_condition {
+                        Some(type_condition) => {
+                            let ty = type_condition.node.on.node.as_str();
+                            self.add_type(ty);
+                            ty
+                        }
+                        None => type_name,
+                    };
+                    self.visit_selection_set(ty, &fragment.node.selection_set.node);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: CacheStorage> Middleware for ResponseCacheMiddleware<T> {
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        let session = request
+            .data
+            .get(&TypeId::of::<SessionKey>())
+            .and_then(|data| data.downcast_ref::<SessionKey>())
+            .map(|SessionKey(key)| key.clone());
+
+        let public_key = cache_key(None, &request);
+        let private_key = session
+            .as_deref()
+            .map(|session| cache_key(Some(session), &request));
+        for key in std::iter::once(&public_key).chain(&private_key) {
+            if let Some(cached) = self.storage.get(key.clone()).await {
+                if !cached.is_expired() {
+                    return Response::new(cached.data).cache_control(CacheControl {
+                        public: cached.public,
+                        max_age: cached.max_age,
+                    });
+                }
+            }
+        }
+
+        self.operation.lock().unwrap().name = request.operation_name.clone();
+        let resp = next.run(ctx, request).await;
+        if resp.is_err() || resp.cache_control.max_age == 0 {
+            return resp;
+        }
+        let key = match (resp.cache_control.public, private_key) {
+            (true, _) => public_key,
+            (false, Some(private_key)) => private_key,
+            (false, None) => return resp,
+        };
+
+        let types = {
+            let mut operation = self.operation.lock().unwrap();
+            if !operation.is_query {
+                return resp;
+            }
+            std::mem::take(&mut operation.types)
+        };
+        self.storage
+            .set(
+                key,
+                CachedResponse {
+                    data: resp.data.clone(),
+                    public: resp.cache_control.public,
+                    max_age: resp.cache_control.max_age,
+                    expires_at: SystemTime::now()
+                        + Duration::from_secs(resp.cache_control.max_age as u64),
+                    types,
+                },
+            )
+            .await;
+        resp
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        let mut operation = self.operation.lock().unwrap();
+        let selection_set = match document.operations.get(operation.name.as_deref()) {
+            Some(op) if op.node.ty == OperationType::Query => &op.node.selection_set.node,
+            _ => return Ok(document),
+        };
+
+        let mut collector = TypeCollector {
+            registry: ctx.registry,
+            document: &document,
+            types: HashSet::new(),
+            fragments: HashSet::new(),
+        };
+        let query_type = &ctx.registry.query_type;
+        collector.add_type(query_type);
+        collector.visit_selection_set(query_type, selection_set);
+        let mut types = collector.types.into_iter().collect::<Vec<_>>();
+        types.sort();
+
+        operation.is_query = true;
+        operation.types = types;
+        Ok(document)
+    }
+}
@@ -1,11 +1,17 @@
-use crate::extensions::{Extension, ExtensionContext, ExtensionFactory, ResolveInfo};
-use crate::{Error, Variables};
-use async_graphql_parser::types::ExecutableDocument;
-use std::collections::BTreeMap;
-use tracing::{span, Level, Span};
+use crate::extensions::{
+    ExtensionContext, Middleware, MiddlewareFactory, NextExecute, NextParse, NextRequest,
+    NextResolve, NextValidate, ResolveInfo,
+};
+use crate::parser::types::ExecutableDocument;
+use crate::{Request, Response, Result, ValidationResult, Variables};
+use tracing::{span, Instrument, Level, Span};
 
 /// Tracing extension
 ///
+/// It is a middleware, add it with `SchemaBuilder::middleware`. The spans of the fields are
+/// children of the spans of their parents, as each field is resolved inside the span of its
+/// parent.
+///
 /// # References
 ///
 /// <https://crates.io/crates/tracing>
@@ -13,154 +19,95 @@ use tracing::{span, Level, Span};
 #[cfg_attr(feature = "nightly", doc(cfg(feature = "tracing")))]
 pub struct Tracing;
 
-impl ExtensionFactory for Tracing {
-    fn create(&self) -> Box<dyn Extension> {
-        Box::new(TracingExtension::default())
+impl MiddlewareFactory for Tracing {
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(TracingExtension)
     }
 }
 
-#[derive(Default)]
-struct TracingExtension {
-    root: Option<Span>,
-    parse: Option<Span>,
-    validation: Option<Span>,
-    execute: Option<Span>,
-    fields: BTreeMap<usize, Span>,
+struct TracingExtension;
+
+fn record_error<T>(res: &Result<T>) {
+    if let Err(err) = res {
+        tracing::error!(target: "async_graphql::graphql", error = %err.to_string());
+    }
 }
 
-impl Extension for TracingExtension {
-    fn parse_start(
-        &mut self,
-        _ctx: &ExtensionContext<'_>,
-        query_source: &str,
-        _variables: &Variables,
-    ) {
+#[async_trait::async_trait]
+impl Middleware for TracingExtension {
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        // The source is recorded after parsing, so that secret input values can be redacted.
         let root_span = span!(
             target: "async_graphql::graphql",
             parent: None,
             Level::INFO,
             "query",
-            source = %query_source
+            source = tracing::field::Empty
         );
-
-        let parse_span = span!(
-            target: "async_graphql::graphql",
-            parent: &root_span,
-            Level::INFO,
-            "parse"
-        );
-
-        root_span.with_subscriber(|(id, d)| d.enter(id));
-        self.root.replace(root_span);
-
-        parse_span.with_subscriber(|(id, d)| d.enter(id));
-        self.parse.replace(parse_span);
+        next.run(ctx, request).instrument(root_span).await
     }
 
-    fn parse_end(&mut self, _ctx: &ExtensionContext<'_>, _document: &ExecutableDocument) {
-        self.parse
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-    }
-
-    fn validation_start(&mut self, _ctx: &ExtensionContext<'_>) {
-        if let Some(parent) = &self.root {
-            let validation_span = span!(
-                target: "async_graphql::graphql",
-                parent: parent,
-                Level::INFO,
-                "validation"
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        let parse_span = span!(target: "async_graphql::graphql", Level::INFO, "parse");
+        let res = next.run(ctx, query, variables).instrument(parse_span).await;
+        record_error(&res);
+        if let Ok(document) = &res {
+            Span::current().record(
+                "source",
+                tracing::field::display(ctx.stringify_execute_doc(document)),
             );
-            validation_span.with_subscriber(|(id, d)| d.enter(id));
-            self.validation.replace(validation_span);
         }
+        res
     }
 
-    fn validation_end(&mut self, _ctx: &ExtensionContext<'_>) {
-        self.validation
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-    }
-
-    fn execution_start(&mut self, _ctx: &ExtensionContext<'_>) {
-        let execute_span = if let Some(parent) = &self.root {
-            span!(
-                target: "async_graphql::graphql",
-                parent: parent,
-                Level::INFO,
-                "execute"
-            )
-        } else {
-            // For every step of the subscription stream.
-            span!(
-                target: "async_graphql::graphql",
-                parent: None,
-                Level::INFO,
-                "execute"
-            )
-        };
-
-        execute_span.with_subscriber(|(id, d)| d.enter(id));
-        self.execute.replace(execute_span);
+    async fn validate(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidate<'_>,
+    ) -> Result<ValidationResult> {
+        let validation_span = span!(target: "async_graphql::graphql", Level::INFO, "validation");
+        let res = next.run(ctx).instrument(validation_span).await;
+        record_error(&res);
+        res
     }
 
-    fn execution_end(&mut self, _ctx: &ExtensionContext<'_>) {
-        self.execute
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-
-        self.root
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
+    async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+        let execute_span = span!(target: "async_graphql::graphql", Level::INFO, "execute");
+        next.run(ctx).instrument(execute_span).await
     }
 
-    fn resolve_start(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        let parent_span = match info.resolve_id.parent {
-            Some(parent_id) if parent_id > 0 => self.fields.get(&parent_id),
-            _ => self.execute.as_ref(),
-        };
-
-        if let Some(parent_span) = parent_span {
-            let span = span!(
-                target: "async_graphql::graphql",
-                parent: parent_span,
-                Level::INFO,
-                "field",
-                id = %info.resolve_id.current,
-                path = %info.path_node,
-                parent_type = %info.parent_type,
-                return_type = %info.return_type,
-            );
-            span.with_subscriber(|(id, d)| d.enter(id));
-            self.fields.insert(info.resolve_id.current, span);
-        }
-    }
-
-    fn resolve_end(&mut self, _ctx: &ExtensionContext<'_>, info: &ResolveInfo<'_>) {
-        if let Some(span) = self.fields.remove(&info.resolve_id.current) {
-            span.with_subscriber(|(id, d)| d.exit(id));
-        }
-    }
-
-    fn error(&mut self, _ctx: &ExtensionContext<'_>, err: &Error) {
-        tracing::error!(target: "async_graphql::graphql", error = %err.to_string());
-
-        for span in self.fields.values() {
-            span.with_subscriber(|(id, d)| d.exit(id));
-        }
-        self.fields.clear();
-
-        self.execute
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-        self.validation
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-        self.parse
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
-        self.root
-            .take()
-            .and_then(|span| span.with_subscriber(|(id, d)| d.exit(id)));
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        let field_span = span!(
+            target: "async_graphql::graphql",
+            Level::INFO,
+            "field",
+            id = %info.resolve_id.current,
+            path = %info.path_node,
+            parent_type = %info.parent_type,
+            return_type = %info.return_type,
+        );
+        let res = next.run(ctx, info).instrument(field_span).await;
+        record_error(&res);
+        res
     }
 }
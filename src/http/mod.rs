@@ -3,13 +3,19 @@
 mod graphiql_source;
 #[cfg(feature = "multipart")]
 mod multipart;
+mod multipart_mixed;
 mod playground_source;
+mod sse;
 mod websocket;
 
 pub use graphiql_source::graphiql_source;
 #[cfg(feature = "multipart")]
-pub use multipart::MultipartOptions;
+pub use multipart::{MultipartOptions, TempFileStorage, UploadStorage, UploadStream};
+pub use multipart_mixed::{
+    accepts_multipart_mixed, multipart_mixed_stream, MULTIPART_MIXED_CONTENT_TYPE,
+};
 pub use playground_source::{playground_source, GraphQLPlaygroundConfig};
+pub use sse::{sse_stream, SseConnections, SseError, SseEvent, SSE_CONTENT_TYPE, SSE_TOKEN_HEADER};
 pub use websocket::WebSocket;
 
 use crate::{BatchRequest, ParseRequestError, Request};
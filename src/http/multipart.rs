@@ -1,16 +1,63 @@
+use crate::parser::types::{UploadContent, UploadValue};
 use crate::{BatchRequest, ParseRequestError};
 use bytes::Bytes;
+use futures::future::BoxFuture;
 use futures::io::AsyncRead;
+use futures::lock::Mutex;
 use futures::stream::Stream;
-use multer::{Constraints, Multipart, SizeLimit};
+use futures::FutureExt;
+use multer::{Constraints, Field, Multipart, SizeLimit};
 use pin_project_lite::pin_project;
-use std::collections::HashMap;
-use std::io::{self, Seek, SeekFrom, Write};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+/// A storage backend for the uploaded files that are spooled before they are read.
+///
+/// All the files are spooled when the streaming mode is disabled. In the streaming mode, a file is
+/// spooled only when a file after it in the request is read first.
+pub trait UploadStorage: Send + Sync {
+    /// Create the file to spool an uploaded file to.
+    ///
+    /// The data of the uploaded file is written to it, then it is read from the start.
+    fn create(&self, filename: &str, content_type: Option<&str>) -> io::Result<File>;
+}
+
+/// Spools the uploaded files to anonymous temporary files, this is the default storage.
+#[derive(Debug, Clone, Default)]
+pub struct TempFileStorage {
+    dir: Option<PathBuf>,
+}
+
+impl TempFileStorage {
+    /// Create the files in the default temporary directory.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create the files in a directory.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+}
+
+impl UploadStorage for TempFileStorage {
+    fn create(&self, _filename: &str, _content_type: Option<&str>) -> io::Result<File> {
+        match &self.dir {
+            Some(dir) => tempfile::tempfile_in(dir),
+            None => tempfile::tempfile(),
+        }
+    }
+}
+
 /// Options for `receive_multipart`.
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone)]
 #[non_exhaustive]
 #[cfg_attr(feature = "nightly", doc(cfg(feature = "multipart")))]
 pub struct MultipartOptions {
@@ -18,6 +65,15 @@ pub struct MultipartOptions {
     pub max_file_size: Option<usize>,
     /// The maximum number of files.
     pub max_num_files: Option<usize>,
+    /// Whether the files are streamed to the resolvers.
+    pub streaming: bool,
+    /// The content types allowed for all the files.
+    pub allowed_content_types: Option<Vec<String>>,
+    /// The content types allowed for the files of variable paths, they replace
+    /// `allowed_content_types`.
+    pub upload_content_types: HashMap<String, Vec<String>>,
+    /// The storage of the spooled files, `TempFileStorage` if it is not set.
+    pub storage: Option<Arc<dyn UploadStorage>>,
 }
 
 impl MultipartOptions {
@@ -36,6 +92,138 @@ impl MultipartOptions {
             ..self
         }
     }
+
+    /// Set whether the files are streamed to the resolvers, the default is `false`.
+    ///
+    /// In the streaming mode, the request is executed once the `operations` and `map` fields are
+    /// received, and the files are read by the resolvers with
+    /// [`Upload::into_stream`](../struct.Upload.html#method.into_stream) while the rest of the
+    /// request is received. The `operations` and `map` fields must be before the files, as in the
+    /// [specification](https://github.com/jaydenseric/graphql-multipart-request-spec).
+    ///
+    /// The files are in the order of the request, a file is spooled to the storage if a file after
+    /// it is read first. A file can only be read once, even if it is mapped to several variables.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use async_graphql::*;
+    /// use async_graphql::http::MultipartOptions;
+    /// use futures::AsyncReadExt;
+    ///
+    /// struct MutationRoot;
+    ///
+    /// #[Object]
+    /// impl MutationRoot {
+    ///     async fn upload(&self, file: Upload) -> FieldResult<u64> {
+    ///         let mut stream = file.into_stream().await?;
+    ///         let mut data = Vec::new();
+    ///         stream.read_to_end(&mut data).await?;
+    ///         Ok(data.len() as u64)
+    ///     }
+    /// }
+    ///
+    /// let opts = MultipartOptions::default()
+    ///     .streaming(true)
+    ///     .upload_content_types("variables.file", vec!["text/*"]);
+    /// ```
+    pub fn streaming(self, streaming: bool) -> Self {
+        MultipartOptions { streaming, ..self }
+    }
+
+    /// Set the content types allowed for all the files, such as `image/png` or `image/*`.
+    ///
+    /// A file without a content type is rejected.
+    pub fn allowed_content_types<I, T>(self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        MultipartOptions {
+            allowed_content_types: Some(content_types.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Set the content types allowed for the files of a variable path of the `map` field, such
+    /// as `variables.avatar`, or `0.variables.avatar` in a batch request.
+    ///
+    /// They replace the content types set by
+    /// [`allowed_content_types`](#method.allowed_content_types) for this variable path.
+    pub fn upload_content_types<I, T>(
+        mut self,
+        var_path: impl Into<String>,
+        content_types: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.upload_content_types.insert(
+            var_path.into(),
+            content_types.into_iter().map(Into::into).collect(),
+        );
+        self
+    }
+
+    /// Set the storage of the spooled files.
+    pub fn storage(self, storage: impl UploadStorage + 'static) -> Self {
+        MultipartOptions {
+            storage: Some(Arc::new(storage)),
+            ..self
+        }
+    }
+
+    fn check_content_type(
+        &self,
+        var_path: &str,
+        content_type: Option<&str>,
+    ) -> Result<(), ParseRequestError> {
+        let allowed = match self
+            .upload_content_types
+            .get(var_path)
+            .or(self.allowed_content_types.as_ref())
+        {
+            Some(allowed) => allowed,
+            None => return Ok(()),
+        };
+        let is_allowed = content_type.is_some_and(|content_type| {
+            allowed
+                .iter()
+                .any(|pattern| content_type_matches(pattern, content_type))
+        });
+        if is_allowed {
+            Ok(())
+        } else {
+            Err(ParseRequestError::ContentTypeNotAllowed {
+                var_path: var_path.to_string(),
+                content_type: content_type.map(ToString::to_string),
+            })
+        }
+    }
+
+    fn create_file(&self, filename: &str, content_type: Option<&str>) -> io::Result<File> {
+        match &self.storage {
+            Some(storage) => storage.create(filename, content_type),
+            None => TempFileStorage::new().create(filename, content_type),
+        }
+    }
+}
+
+/// Returns whether a content type matches a pattern such as `image/png`, `image/*` or `*/*`,
+/// the parameters of the content type are ignored.
+fn content_type_matches(pattern: &str, content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    if pattern == "*/*" {
+        return true;
+    }
+    match pattern.strip_suffix("/*") {
+        Some(ty) => essence
+            .split('/')
+            .next()
+            .is_some_and(|essence_ty| essence_ty.eq_ignore_ascii_case(ty)),
+        None => essence.eq_ignore_ascii_case(pattern),
+    }
 }
 
 pub(super) async fn receive_batch_multipart(
@@ -43,13 +231,13 @@ pub(super) async fn receive_batch_multipart(
     boundary: impl Into<String>,
     opts: MultipartOptions,
 ) -> Result<BatchRequest, ParseRequestError> {
-    let mut multipart = Multipart::new_with_constraints(
+    let multipart = Multipart::new_with_constraints(
         ReaderStream::new(body),
         boundary,
         Constraints::new().size_limit({
             let mut limit = SizeLimit::new();
             if let (Some(max_file_size), Some(max_num_files)) =
-                (opts.max_file_size, opts.max_file_size)
+                (opts.max_file_size, opts.max_num_files)
             {
                 limit = limit.whole_stream((max_file_size * max_num_files) as u64);
             }
@@ -60,33 +248,73 @@ pub(super) async fn receive_batch_multipart(
         }),
     );
 
+    if opts.streaming {
+        receive_streaming(multipart, opts).await
+    } else {
+        receive_spooled(multipart, opts).await
+    }
+}
+
+async fn read_operations(field: Field) -> Result<BatchRequest, ParseRequestError> {
+    let request_str = field.text().await?;
+    serde_json::from_str::<BatchRequest>(&request_str).map_err(ParseRequestError::InvalidRequest)
+}
+
+async fn read_map(field: Field) -> Result<HashMap<String, Vec<String>>, ParseRequestError> {
+    let map_str = field.text().await?;
+    serde_json::from_str::<HashMap<String, Vec<String>>>(&map_str)
+        .map_err(ParseRequestError::InvalidFilesMap)
+}
+
+fn set_upload(
+    request: &mut BatchRequest,
+    var_path: &str,
+    filename: String,
+    content_type: Option<String>,
+    content: UploadContent,
+) {
+    match request {
+        BatchRequest::Single(request) => {
+            request.set_upload_content(var_path, filename, content_type, content);
+        }
+        BatchRequest::Batch(requests) => {
+            let mut s = var_path.splitn(2, '.');
+            let idx = s.next().and_then(|idx| idx.parse::<usize>().ok());
+            let path = s.next();
+
+            if let (Some(idx), Some(path)) = (idx, path) {
+                if let Some(request) = requests.get_mut(idx) {
+                    request.set_upload_content(path, filename, content_type, content);
+                }
+            }
+        }
+    }
+}
+
+async fn receive_spooled(
+    mut multipart: Multipart,
+    opts: MultipartOptions,
+) -> Result<BatchRequest, ParseRequestError> {
     let mut request = None;
     let mut map = None;
     let mut files = Vec::new();
 
     while let Some(mut field) = multipart.next_field().await? {
         match field.name() {
-            Some("operations") => {
-                let request_str = field.text().await?;
-                request = Some(
-                    serde_json::from_str::<BatchRequest>(&request_str)
-                        .map_err(ParseRequestError::InvalidRequest)?,
-                );
-            }
-            Some("map") => {
-                let map_str = field.text().await?;
-                map = Some(
-                    serde_json::from_str::<HashMap<String, Vec<String>>>(&map_str)
-                        .map_err(ParseRequestError::InvalidFilesMap)?,
-                );
-            }
+            Some("operations") => request = Some(read_operations(field).await?),
+            Some("map") => map = Some(read_map(field).await?),
             _ => {
                 if let Some(name) = field.name().map(ToString::to_string) {
                     if let Some(filename) = field.file_name().map(ToString::to_string) {
                         let content_type = field.content_type().map(|mime| mime.to_string());
-                        let mut file = tempfile::tempfile().map_err(ParseRequestError::Io)?;
-                        while let Some(chunk) = field.chunk().await.unwrap() {
-                            file.write(&chunk).map_err(ParseRequestError::Io)?;
+                        if let Some(var_paths) = map.as_ref().and_then(|map| map.get(&name)) {
+                            for var_path in var_paths {
+                                opts.check_content_type(var_path, content_type.as_deref())?;
+                            }
+                        }
+                        let mut file = opts.create_file(&filename, content_type.as_deref())?;
+                        while let Some(chunk) = field.chunk().await? {
+                            file.write_all(&chunk)?;
                         }
                         file.seek(SeekFrom::Start(0))?;
                         files.push((name, filename, content_type, file));
@@ -102,32 +330,14 @@ pub(super) async fn receive_batch_multipart(
     for (name, filename, content_type, file) in files {
         if let Some(var_paths) = map.remove(&name) {
             for var_path in var_paths {
-                match &mut request {
-                    BatchRequest::Single(request) => {
-                        request.set_upload(
-                            &var_path,
-                            filename.clone(),
-                            content_type.clone(),
-                            file.try_clone().unwrap(),
-                        );
-                    }
-                    BatchRequest::Batch(requests) => {
-                        let mut s = var_path.splitn(2, '.');
-                        let idx = s.next().and_then(|idx| idx.parse::<usize>().ok());
-                        let path = s.next();
-
-                        if let (Some(idx), Some(path)) = (idx, path) {
-                            if let Some(request) = requests.get_mut(idx) {
-                                request.set_upload(
-                                    path,
-                                    filename.clone(),
-                                    content_type.clone(),
-                                    file.try_clone().unwrap(),
-                                );
-                            }
-                        }
-                    }
-                }
+                opts.check_content_type(&var_path, content_type.as_deref())?;
+                set_upload(
+                    &mut request,
+                    &var_path,
+                    filename.clone(),
+                    content_type.clone(),
+                    UploadContent::File(file.try_clone()?),
+                );
             }
         }
     }
@@ -139,6 +349,306 @@ pub(super) async fn receive_batch_multipart(
     Ok(request)
 }
 
+async fn receive_streaming(
+    mut multipart: Multipart,
+    opts: MultipartOptions,
+) -> Result<BatchRequest, ParseRequestError> {
+    let mut request = None;
+    let mut map = None;
+
+    while request.is_none() || map.is_none() {
+        let missing_part = if request.is_none() {
+            ParseRequestError::MissingOperatorsPart
+        } else {
+            ParseRequestError::MissingMapPart
+        };
+        let field = match multipart.next_field().await? {
+            Some(field) => field,
+            None => return Err(missing_part),
+        };
+        match field.name() {
+            Some("operations") => request = Some(read_operations(field).await?),
+            Some("map") => map = Some(read_map(field).await?),
+            _ => return Err(missing_part),
+        }
+    }
+
+    let mut request: BatchRequest = request.ok_or(ParseRequestError::MissingOperatorsPart)?;
+    let map = map.ok_or(ParseRequestError::MissingMapPart)?;
+
+    let parts = Arc::new(Mutex::new(StreamingParts {
+        multipart,
+        opts,
+        names: map.keys().cloned().collect(),
+        current: None,
+        spooled: HashMap::new(),
+        opened: HashSet::new(),
+    }));
+    for (name, var_paths) in map {
+        for var_path in var_paths {
+            let upload = StreamingUpload {
+                name: name.clone(),
+                var_path: var_path.clone(),
+                parts: parts.clone(),
+            };
+            set_upload(
+                &mut request,
+                &var_path,
+                String::new(),
+                None,
+                UploadContent::Stream(Arc::new(upload)),
+            );
+        }
+    }
+
+    Ok(request)
+}
+
+fn multipart_io_error(err: multer::Error) -> io::Error {
+    io::Error::other(ParseRequestError::from(err))
+}
+
+#[derive(Clone)]
+struct PartHeaders {
+    filename: String,
+    content_type: Option<String>,
+}
+
+/// The files of a request in the streaming mode, which are received while it is executed.
+struct StreamingParts {
+    multipart: Multipart,
+    opts: MultipartOptions,
+    /// The names of the files in the `map` field.
+    names: HashSet<String>,
+    /// The file that is being received.
+    current: Option<(String, PartHeaders, Field)>,
+    /// The files that are received before they are read.
+    spooled: HashMap<String, (PartHeaders, File)>,
+    opened: HashSet<String>,
+}
+
+impl StreamingParts {
+    /// Receive the next file of the `map` field, the other fields are skipped.
+    async fn next_part(&mut self) -> io::Result<()> {
+        loop {
+            let mut field = match self.multipart.next_field().await {
+                Ok(Some(field)) => field,
+                Ok(None) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        ParseRequestError::MissingFiles,
+                    ))
+                }
+                Err(err) => return Err(multipart_io_error(err)),
+            };
+            let part = match (field.name(), field.file_name()) {
+                (Some(name), Some(filename)) if self.names.contains(name) => Some((
+                    name.to_string(),
+                    PartHeaders {
+                        filename: filename.to_string(),
+                        content_type: field.content_type().map(|mime| mime.to_string()),
+                    },
+                )),
+                _ => None,
+            };
+            if let Some((name, headers)) = part {
+                self.current = Some((name, headers, field));
+                return Ok(());
+            }
+            while field.chunk().await.map_err(multipart_io_error)?.is_some() {}
+        }
+    }
+
+    /// Spool the rest of the file that is being received.
+    async fn spool_current(&mut self) -> io::Result<()> {
+        if let Some((name, headers, mut field)) = self.current.take() {
+            let mut file = self
+                .opts
+                .create_file(&headers.filename, headers.content_type.as_deref())?;
+            while let Some(chunk) = field.chunk().await.map_err(multipart_io_error)? {
+                file.write_all(&chunk)?;
+            }
+            file.seek(SeekFrom::Start(0))?;
+            self.spooled.insert(name, (headers, file));
+        }
+        Ok(())
+    }
+}
+
+/// The content of a streamed upload, it is stored in `UploadContent::Stream`.
+#[derive(Clone)]
+struct StreamingUpload {
+    name: String,
+    var_path: String,
+    parts: Arc<Mutex<StreamingParts>>,
+}
+
+enum Chunk {
+    Data(Bytes),
+    /// The rest of the file was spooled.
+    File(File),
+    End,
+}
+
+impl StreamingUpload {
+    /// Wait for the file and check its content type.
+    async fn open(&self) -> io::Result<PartHeaders> {
+        let mut parts = self.parts.lock().await;
+        if !parts.opened.insert(self.name.clone()) {
+            return Err(io::Error::other("The uploaded file has already been read"));
+        }
+
+        let headers = loop {
+            if let Some((headers, _)) = parts.spooled.get(&self.name) {
+                break headers.clone();
+            }
+            match &parts.current {
+                Some((name, headers, _)) if *name == self.name => break headers.clone(),
+                Some(_) => parts.spool_current().await?,
+                None => parts.next_part().await?,
+            }
+        };
+
+        parts
+            .opts
+            .check_content_type(&self.var_path, headers.content_type.as_deref())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(headers)
+    }
+
+    async fn next_chunk(self) -> io::Result<Chunk> {
+        let mut parts = self.parts.lock().await;
+        if let Some((_, file)) = parts.spooled.remove(&self.name) {
+            return Ok(Chunk::File(file));
+        }
+
+        let chunk = match &mut parts.current {
+            Some((name, _, field)) if *name == self.name => {
+                field.chunk().await.map_err(multipart_io_error)?
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The uploaded file is not available",
+                ))
+            }
+        };
+        match chunk {
+            Some(data) => Ok(Chunk::Data(data)),
+            None => {
+                parts.current = None;
+                Ok(Chunk::End)
+            }
+        }
+    }
+}
+
+enum StreamReader {
+    File(File),
+    Part {
+        upload: StreamingUpload,
+        data: Bytes,
+        next: Option<BoxFuture<'static, io::Result<Chunk>>>,
+        end: bool,
+    },
+}
+
+/// An uploaded file that is read as an `AsyncRead`.
+///
+/// It is created by [`Upload::into_stream`](../struct.Upload.html#method.into_stream). A file that
+/// was spooled is read synchronously.
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "multipart")))]
+pub struct UploadStream {
+    filename: String,
+    content_type: Option<String>,
+    reader: StreamReader,
+}
+
+impl UploadStream {
+    pub(crate) async fn open(upload: UploadValue) -> io::Result<Self> {
+        match upload.content {
+            UploadContent::File(file) => Ok(Self {
+                filename: upload.filename,
+                content_type: upload.content_type,
+                reader: StreamReader::File(file),
+            }),
+            UploadContent::Stream(stream) => {
+                let upload = stream
+                    .downcast_ref::<StreamingUpload>()
+                    .ok_or_else(|| io::Error::other("Unknown uploaded file stream"))?
+                    .clone();
+                let headers = upload.open().await?;
+                Ok(Self {
+                    filename: headers.filename,
+                    content_type: headers.content_type,
+                    reader: StreamReader::Part {
+                        upload,
+                        data: Bytes::new(),
+                        next: None,
+                        end: false,
+                    },
+                })
+            }
+            _ => Err(io::Error::other("Unknown uploaded file content")),
+        }
+    }
+
+    /// Filename
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Content type, such as `application/json`, `image/jpg` ...
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+}
+
+impl AsyncRead for UploadStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let spooled = match &mut self.reader {
+                StreamReader::File(file) => return Poll::Ready(file.read(buf)),
+                StreamReader::Part {
+                    upload,
+                    data,
+                    next,
+                    end,
+                } => {
+                    if !data.is_empty() {
+                        let len = data.len().min(buf.len());
+                        buf[..len].copy_from_slice(&data.split_to(len));
+                        return Poll::Ready(Ok(len));
+                    }
+                    if *end {
+                        return Poll::Ready(Ok(0));
+                    }
+
+                    let fut = next.get_or_insert_with(|| upload.clone().next_chunk().boxed());
+                    let chunk = futures::ready!(fut.as_mut().poll(cx));
+                    *next = None;
+                    match chunk? {
+                        Chunk::Data(chunk) => {
+                            *data = chunk;
+                            continue;
+                        }
+                        Chunk::End => {
+                            *end = true;
+                            continue;
+                        }
+                        Chunk::File(file) => file,
+                    }
+                }
+            };
+            self.reader = StreamReader::File(spooled);
+        }
+    }
+}
+
 pin_project! {
     pub(crate) struct ReaderStream<T> {
         buf: [u8; 2048],
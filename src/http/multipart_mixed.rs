@@ -0,0 +1,33 @@
+//! `multipart/mixed` encoding of incremental responses
+
+use crate::IncrementalResponse;
+use futures::{stream, Stream, StreamExt};
+
+/// The content type of a `multipart/mixed` response of
+/// [`Schema::execute_incremental`](../struct.Schema.html#method.execute_incremental).
+pub const MULTIPART_MIXED_CONTENT_TYPE: &str = "multipart/mixed; boundary=\"-\"";
+
+/// Returns `true` if a request with the `Accept` header accepts a `multipart/mixed` response.
+pub fn accepts_multipart_mixed(accept: &str) -> bool {
+    accept.split(',').any(|media_type| {
+        let essence = media_type.split(';').next().unwrap_or_default();
+        essence.trim().eq_ignore_ascii_case("multipart/mixed")
+    })
+}
+
+/// Encode the payloads of an incremental response as the body of a `multipart/mixed` response
+/// with the [`MULTIPART_MIXED_CONTENT_TYPE`](constant.MULTIPART_MIXED_CONTENT_TYPE.html).
+///
+/// Every payload is a JSON part, the closing delimiter is written after the last one.
+pub fn multipart_mixed_stream(
+    payloads: impl Stream<Item = IncrementalResponse> + Send + 'static,
+) -> impl Stream<Item = String> + Send + 'static {
+    payloads
+        .map(|payload| {
+            format!(
+                "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{}",
+                serde_json::to_string(&payload).unwrap()
+            )
+        })
+        .chain(stream::once(async { "\r\n-----\r\n".to_string() }))
+}
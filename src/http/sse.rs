@@ -0,0 +1,366 @@
+//! Server-Sent Events transport for subscription
+
+use crate::{ObjectType, Request, Response, Schema, SubscriptionType};
+use futures::channel::mpsc;
+use futures::future::{AbortHandle, Abortable};
+use futures::stream::{self, BoxStream, SelectAll};
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// The content type of an event stream.
+pub const SSE_CONTENT_TYPE: &str = "text/event-stream";
+
+/// The header that contains the stream token in the single connection mode.
+///
+/// As `EventSource` cannot set headers, the `GET` request that opens the event stream may have the
+/// token in the `token` query parameter instead.
+pub const SSE_TOKEN_HEADER: &str = "x-graphql-event-stream-token";
+
+/// The time a reserved stream token has to be connected by default.
+const DEFAULT_RESERVATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An event of the GraphQL over SSE protocol.
+///
+/// It is formatted as an event of the `text/event-stream` content type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SseEvent {
+    /// The event type, `next` or `complete`.
+    pub event: &'static str,
+
+    /// The data of the event, it never contains a newline.
+    pub data: String,
+}
+
+impl SseEvent {
+    fn next(id: Option<&str>, resp: &Response) -> Self {
+        let data = match id {
+            Some(id) => serde_json::json!({ "id": id, "payload": resp }).to_string(),
+            None => serde_json::to_string(resp).unwrap(),
+        };
+        Self {
+            event: "next",
+            data,
+        }
+    }
+
+    fn complete(id: Option<&str>) -> Self {
+        Self {
+            event: "complete",
+            data: id
+                .map(|id| serde_json::json!({ "id": id }).to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Display for SseEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "event: {}\ndata: {}\n\n", self.event, self.data)
+    }
+}
+
+/// Execute a GraphQL request in the distinct connections mode of the
+/// [GraphQL over SSE protocol](https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md).
+///
+/// Returns the events to write to the body of a `text/event-stream` response: a `next` event for
+/// every response and a `complete` event at the end. Queries and mutations have a single response.
+///
+/// The request can be received from a `GET` request with the query parameters, or from the body of
+/// a `POST` request.
+pub fn sse_stream<Query, Mutation, Subscription>(
+    schema: &Schema<Query, Mutation, Subscription>,
+    request: impl Into<Request>,
+) -> impl Stream<Item = SseEvent> + Send + 'static
+where
+    Query: ObjectType + Send + Sync + 'static,
+    Mutation: ObjectType + Send + Sync + 'static,
+    Subscription: SubscriptionType + Send + Sync + 'static,
+{
+    schema
+        .execute_stream(request.into())
+        .map(|resp| SseEvent::next(None, &resp))
+        .chain(stream::once(async { SseEvent::complete(None) }))
+}
+
+/// An error of the single connection mode of the GraphQL over SSE protocol.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum SseError {
+    /// The stream token has not been reserved or the connection has been closed.
+    #[error("Unknown event stream token")]
+    UnknownToken,
+
+    /// The event stream of the token is already open.
+    #[error("The event stream is already open")]
+    AlreadyConnected,
+
+    /// The request has no `operationId` extension.
+    #[error("Missing operationId extension")]
+    MissingOperationId,
+
+    /// An operation with the same id is running.
+    #[error("Operation with id `{0}` is already running")]
+    DuplicateOperationId(String),
+}
+
+impl SseError {
+    /// Returns the HTTP status code of the response for this error.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            SseError::UnknownToken => 404,
+            SseError::AlreadyConnected | SseError::DuplicateOperationId(_) => 409,
+            SseError::MissingOperationId => 400,
+        }
+    }
+}
+
+type EventStream = BoxStream<'static, SseEvent>;
+
+struct Connection {
+    sender: mpsc::UnboundedSender<EventStream>,
+    receiver: Option<mpsc::UnboundedReceiver<EventStream>>,
+    operations: HashMap<String, AbortHandle>,
+    reserved_at: Instant,
+}
+
+impl Connection {
+    /// Returns `true` if the token has not been connected within the timeout.
+    fn is_expired(&self, timeout: Duration) -> bool {
+        self.receiver.is_some() && self.reserved_at.elapsed() >= timeout
+    }
+}
+
+fn new_token() -> String {
+    let mut bytes = [0; 16];
+    getrandom::getrandom(&mut bytes).expect("failed to generate an event stream token");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The connections of the single connection mode of the
+/// [GraphQL over SSE protocol](https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md).
+///
+/// In this mode the client makes the following requests to the same URL, the last three with the
+/// stream token in the [`SSE_TOKEN_HEADER`](constant.SSE_TOKEN_HEADER.html) header:
+///
+/// - `PUT`: [`reserve`](#method.reserve) a stream token, respond `201 Created` with the token.
+/// - `GET`: [`connect`](#method.connect) and respond with the returned events as a
+///   `text/event-stream`.
+/// - `POST`: [`execute`](#method.execute) the GraphQL request, respond `202 Accepted`.
+/// - `DELETE`: [`stop`](#method.stop) the operation of the `operationId` query parameter,
+///   respond `200 OK`.
+///
+/// The errors are converted to responses with [`SseError::status_code`](enum.SseError.html#method.status_code).
+///
+/// The connection is closed when its event stream is dropped. A token that is not connected
+/// within the [`reservation_timeout`](#method.reservation_timeout) is forgotten with its operations.
+/// The token is not a credential, it must not be used to authenticate the requests.
+#[derive(Clone)]
+pub struct SseConnections {
+    connections: Arc<Mutex<HashMap<String, Connection>>>,
+    reservation_timeout: Duration,
+}
+
+impl Default for SseConnections {
+    fn default() -> Self {
+        Self {
+            connections: Default::default(),
+            reservation_timeout: DEFAULT_RESERVATION_TIMEOUT,
+        }
+    }
+}
+
+impl SseConnections {
+    /// Create an empty set of connections.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time a reserved token has to be connected, 30 seconds by default.
+    pub fn reservation_timeout(self, reservation_timeout: Duration) -> Self {
+        Self {
+            reservation_timeout,
+            ..self
+        }
+    }
+
+    /// Reserve a new event stream and return its token.
+    pub fn reserve(&self) -> String {
+        let token = new_token();
+        let (sender, receiver) = mpsc::unbounded();
+        let mut connections = self.connections.lock().unwrap();
+        // The reservations that are never connected would be kept forever.
+        connections.retain(|_, connection| !connection.is_expired(self.reservation_timeout));
+        connections.insert(
+            token.clone(),
+            Connection {
+                sender,
+                receiver: Some(receiver),
+                operations: HashMap::new(),
+                reserved_at: Instant::now(),
+            },
+        );
+        token
+    }
+
+    /// Returns the connection of a token, an expired reservation is removed.
+    fn connection<'a>(
+        &self,
+        connections: &'a mut HashMap<String, Connection>,
+        token: &str,
+    ) -> Result<&'a mut Connection, SseError> {
+        let expired = connections
+            .get(token)
+            .filter(|connection| connection.is_expired(self.reservation_timeout))
+            .is_some();
+        if expired {
+            connections.remove(token);
+        }
+        connections.get_mut(token).ok_or(SseError::UnknownToken)
+    }
+
+    /// Open the event stream of a reserved token.
+    ///
+    /// The events of the operations are written to this stream, including those executed before
+    /// it was opened.
+    pub fn connect(
+        &self,
+        token: &str,
+    ) -> Result<impl Stream<Item = SseEvent> + Send + 'static, SseError> {
+        let mut connections = self.connections.lock().unwrap();
+        let connection = self.connection(&mut connections, token)?;
+        let receiver = connection
+            .receiver
+            .take()
+            .ok_or(SseError::AlreadyConnected)?;
+        Ok(ConnectionStream {
+            connections: self.clone(),
+            token: token.to_string(),
+            receiver: Some(receiver),
+            operations: SelectAll::new(),
+        })
+    }
+
+    /// Execute a GraphQL request on the event stream of a token.
+    ///
+    /// The request must have an `operationId` extension, it is the `id` of the `next` and
+    /// `complete` events of the operation.
+    pub fn execute<Query, Mutation, Subscription>(
+        &self,
+        schema: &Schema<Query, Mutation, Subscription>,
+        token: &str,
+        request: impl Into<Request>,
+    ) -> Result<(), SseError>
+    where
+        Query: ObjectType + Send + Sync + 'static,
+        Mutation: ObjectType + Send + Sync + 'static,
+        Subscription: SubscriptionType + Send + Sync + 'static,
+    {
+        let request: Request = request.into();
+        let id = request
+            .extensions
+            .get("operationId")
+            .and_then(|id| id.as_str())
+            .ok_or(SseError::MissingOperationId)?
+            .to_string();
+
+        let mut connections = self.connections.lock().unwrap();
+        let connection = self.connection(&mut connections, token)?;
+        if connection.operations.contains_key(&id) {
+            return Err(SseError::DuplicateOperationId(id));
+        }
+
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let events = Abortable::new(
+            schema.execute_stream(request).map({
+                let id = id.clone();
+                move |resp| SseEvent::next(Some(&id), &resp)
+            }),
+            abort_registration,
+        );
+        let complete = stream::once({
+            let connections = self.clone();
+            let token = token.to_string();
+            let id = id.clone();
+            async move { connections.finish(&token, &id) }
+        })
+        .filter_map(futures::future::ready);
+
+        connection.operations.insert(id, abort_handle);
+        connection
+            .sender
+            .unbounded_send(events.chain(complete).boxed())
+            .ok();
+        Ok(())
+    }
+
+    /// Stop an operation of the event stream of a token.
+    ///
+    /// No `complete` event is sent for a stopped operation. Stopping an operation that has
+    /// completed does nothing.
+    pub fn stop(&self, token: &str, operation_id: &str) -> Result<(), SseError> {
+        let mut connections = self.connections.lock().unwrap();
+        let connection = self.connection(&mut connections, token)?;
+        if let Some(abort_handle) = connection.operations.remove(operation_id) {
+            abort_handle.abort();
+        }
+        Ok(())
+    }
+
+    /// Returns the `complete` event of an operation that has not been stopped.
+    fn finish(&self, token: &str, operation_id: &str) -> Option<SseEvent> {
+        let mut connections = self.connections.lock().unwrap();
+        connections
+            .get_mut(token)?
+            .operations
+            .remove(operation_id)?;
+        Some(SseEvent::complete(Some(operation_id)))
+    }
+}
+
+struct ConnectionStream {
+    connections: SseConnections,
+    token: String,
+    receiver: Option<mpsc::UnboundedReceiver<EventStream>>,
+    operations: SelectAll<EventStream>,
+}
+
+impl Stream for ConnectionStream {
+    type Item = SseEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while let Some(receiver) = &mut this.receiver {
+            match receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some(operation)) => this.operations.push(operation),
+                Poll::Ready(None) => this.receiver = None,
+                Poll::Pending => break,
+            }
+        }
+
+        match this.operations.poll_next_unpin(cx) {
+            Poll::Ready(Some(event)) => Poll::Ready(Some(event)),
+            Poll::Ready(None) if this.receiver.is_none() => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for ConnectionStream {
+    fn drop(&mut self) {
+        let connection = self
+            .connections
+            .connections
+            .lock()
+            .unwrap()
+            .remove(&self.token);
+        if let Some(connection) = connection {
+            for abort_handle in connection.operations.values() {
+                abort_handle.abort();
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Incremental delivery of `@defer` fragments and `@stream` lists.
+//!
+//! The operation is executed once. A container resolves its deferred fragments on the same object
+//! and a list resolves its remaining items one by one, their results are sent to the executor. The
+//! value of a container or a list whose fields or initial items are resolved before the rest is
+//! set in its context, so that its parent can use it while the rest is still being resolved. The
+//! initial payload is sent when the value of the root is known.
+
+use crate::context::QueryPathSegment;
+use crate::parser::types::Directive;
+use crate::{ContextSelectionSet, Error, InputValueType, Positioned, QueryError, Response, Result};
+use futures::future::{self, BoxFuture, Either};
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Serialize, Serializer};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+
+/// The data of an incremental result.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncrementalData {
+    /// The fields of a deferred fragment.
+    Data(serde_json::Value),
+
+    /// A remaining item of a streamed list, there is none if it failed.
+    Items(Vec<serde_json::Value>),
+}
+
+/// The result of a deferred fragment or of a remaining item of a streamed list.
+#[derive(Debug)]
+pub struct Incremental {
+    /// The data, it is serialized as `null` if there is an error.
+    pub data: IncrementalData,
+
+    /// The path of the object of the fragment, or of the item.
+    pub path: Vec<serde_json::Value>,
+
+    /// The `label` argument of the directive.
+    pub label: Option<String>,
+
+    /// Error
+    pub error: Option<Error>,
+}
+
+impl Serialize for Incremental {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match (&self.data, &self.error) {
+            (IncrementalData::Data(_), Some(_)) => map.serialize_entry("data", &())?,
+            (IncrementalData::Data(data), None) => map.serialize_entry("data", data)?,
+            (IncrementalData::Items(_), Some(_)) => map.serialize_entry("items", &())?,
+            (IncrementalData::Items(items), None) => map.serialize_entry("items", items)?,
+        }
+        map.serialize_entry("path", &self.path)?;
+        if let Some(label) = &self.label {
+            map.serialize_entry("label", label)?;
+        }
+        if let Some(error) = &self.error {
+            map.serialize_entry("errors", error)?;
+        }
+        map.end()
+    }
+}
+
+/// A payload of [`Schema::execute_incremental`](struct.Schema.html#method.execute_incremental).
+#[derive(Debug)]
+pub struct IncrementalResponse {
+    /// The response without the deferred fragments and the remaining items of the streamed lists,
+    /// only in the first payload.
+    pub initial: Option<Response>,
+
+    /// The results delivered in this payload.
+    pub incremental: Vec<Incremental>,
+
+    /// Whether more payloads follow this one.
+    pub has_next: bool,
+
+    /// The extensions of the response, in the last payload if the initial payload is sent before
+    /// the end of the execution.
+    pub extensions: Option<serde_json::Value>,
+}
+
+impl IncrementalResponse {
+    pub(crate) fn complete(response: Response) -> Self {
+        Self {
+            initial: Some(response),
+            incremental: Vec::new(),
+            has_next: false,
+            extensions: None,
+        }
+    }
+}
+
+impl Serialize for IncrementalResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(initial) = &self.initial {
+            if let serde_json::Value::Object(fields) =
+                serde_json::to_value(initial).map_err(S::Error::custom)?
+            {
+                for (name, value) in &fields {
+                    map.serialize_entry(name, value)?;
+                }
+            }
+        }
+        if !self.incremental.is_empty() {
+            map.serialize_entry("incremental", &self.incremental)?;
+        }
+        if let Some(extensions) = &self.extensions {
+            map.serialize_entry("extensions", extensions)?;
+        }
+        map.serialize_entry("hasNext", &self.has_next)?;
+        map.end()
+    }
+}
+
+/// The value of a container or a list, set if it is resolved before its deferred fragments and
+/// streamed items.
+#[derive(Default)]
+pub(crate) struct InitialValue(Mutex<Option<serde_json::Value>>);
+
+impl InitialValue {
+    pub(crate) fn set(&self, value: serde_json::Value) {
+        *self.0.lock().unwrap() = Some(value);
+    }
+
+    pub(crate) fn take(&self) -> Option<serde_json::Value> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// The arguments of a `@defer` or `@stream` directive that applies.
+pub(crate) struct IncrementalDirective {
+    pub(crate) label: Option<String>,
+    pub(crate) initial_count: usize,
+}
+
+impl IncrementalDirective {
+    /// Returns the arguments of the directive with the name if it applies in the context.
+    ///
+    /// The error is boxed, it is only returned for invalid arguments.
+    pub(crate) fn find(
+        ctx: &ContextSelectionSet<'_>,
+        directives: &[Positioned<Directive>],
+        name: &str,
+    ) -> std::result::Result<Option<Self>, Box<Error>> {
+        if !ctx.incremental {
+            return Ok(None);
+        }
+        let directive = match directives
+            .iter()
+            .find(|directive| directive.node.name.node == name)
+        {
+            Some(directive) => directive,
+            None => return Ok(None),
+        };
+        if !argument(ctx, directive, "if")?.unwrap_or(true) {
+            return Ok(None);
+        }
+
+        let initial_count = argument::<i32>(ctx, directive, "initialCount")?.unwrap_or_default();
+        if initial_count < 0 {
+            return Err(Box::new(
+                QueryError::ParseInputValue {
+                    reason: "The initialCount argument of @stream must be non-negative."
+                        .to_string(),
+                }
+                .into_error(directive.pos),
+            ));
+        }
+        Ok(Some(Self {
+            label: argument(ctx, directive, "label")?,
+            initial_count: initial_count as usize,
+        }))
+    }
+}
+
+fn argument<T: InputValueType>(
+    ctx: &ContextSelectionSet<'_>,
+    directive: &Positioned<Directive>,
+    name: &str,
+) -> std::result::Result<Option<T>, Box<Error>> {
+    match directive.node.get_argument(name) {
+        Some(value) => {
+            let pos = value.pos;
+            let value = ctx.resolve_input_value(value.clone())?;
+            let value =
+                T::parse(Some(value)).map_err(|e| e.into_error(pos, T::qualified_type_name()))?;
+            Ok(Some(value))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Returns the path of the context.
+pub(crate) fn path(ctx: &ContextSelectionSet<'_>) -> Vec<serde_json::Value> {
+    let mut path = Vec::new();
+    if let Some(node) = &ctx.path_node {
+        node.for_each(|segment| {
+            path.push(match segment {
+                QueryPathSegment::Index(idx) => (*idx).into(),
+                QueryPathSegment::Name(name) => (*name).into(),
+            })
+        });
+    }
+    path
+}
+
+/// Sends the result of a deferred fragment or a streamed item to the executor.
+pub(crate) fn send(ctx: &ContextSelectionSet<'_>, result: Incremental) {
+    if let Some(sender) = &ctx.query_env.incremental {
+        // The receiver is dropped if the client went away.
+        sender.unbounded_send(result).ok();
+    }
+}
+
+type BoxResultFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// The future of a field or an item, and the key and the initial value of its context.
+pub(crate) type Resolving<'a, K, T> = (BoxResultFuture<'a, T>, Option<(K, Arc<InitialValue>)>);
+
+/// Resolve the fields of a container or the items of a list concurrently with its deferred
+/// fragments or remaining items.
+///
+/// The output of a field or an item whose initial value is set is built from it with `initial`,
+/// its future is still polled to resolve the rest. If the value is resolved before the rest, it is
+/// set in the context.
+pub(crate) async fn resolve_incremental<'a, K, T>(
+    ctx: &ContextSelectionSet<'_>,
+    futures: Vec<Resolving<'a, K, T>>,
+    initial: impl Fn(K, serde_json::Value) -> T + Send,
+    deferred: Option<BoxFuture<'a, ()>>,
+    value: impl FnOnce(Vec<T>) -> serde_json::Value + Send,
+) -> Result<serde_json::Value>
+where
+    K: Send,
+    T: Send,
+{
+    let join = join_initial(futures, initial);
+    futures::pin_mut!(join);
+    let (values, background, deferred) = match deferred {
+        Some(deferred) => match future::select(join, deferred).await {
+            Either::Left((res, deferred)) => {
+                let (values, background) = res?;
+                (values, background, Some(deferred))
+            }
+            Either::Right(((), join)) => {
+                let (values, background) = join.await?;
+                (values, background, None)
+            }
+        },
+        None => {
+            let (values, background) = join.await?;
+            (values, background, None)
+        }
+    };
+
+    let value = value(values);
+    if !background.is_empty() || deferred.is_some() {
+        if let Some(initial_value) = &ctx.initial_value {
+            initial_value.set(value.clone());
+        }
+        // The outputs are the values already known.
+        future::join(future::join_all(background), async {
+            if let Some(deferred) = deferred {
+                deferred.await;
+            }
+        })
+        .await;
+    }
+    Ok(value)
+}
+
+/// Resolve the futures concurrently until each of them completes or sets its initial value,
+/// returns their outputs and the futures that have not completed.
+async fn join_initial<'a, K, T>(
+    futures: Vec<Resolving<'a, K, T>>,
+    initial: impl Fn(K, serde_json::Value) -> T,
+) -> Result<(Vec<T>, Vec<BoxResultFuture<'a, T>>)> {
+    let mut pending = futures.into_iter().map(Some).collect::<Vec<_>>();
+    let mut outputs = pending.iter().map(|_| None).collect::<Vec<_>>();
+    let mut background = Vec::new();
+    let mut remaining = pending.len();
+
+    future::poll_fn(|cx| {
+        for (resolving, output) in pending.iter_mut().zip(&mut outputs) {
+            let (future, initial_value) = match resolving {
+                Some(resolving) => resolving,
+                None => continue,
+            };
+            let value = match future.as_mut().poll(cx) {
+                Poll::Ready(Ok(value)) => {
+                    *resolving = None;
+                    value
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => match initial_value
+                    .as_ref()
+                    .and_then(|(_, initial_value)| initial_value.take())
+                {
+                    Some(value) => {
+                        let (future, initial_value) = resolving.take().unwrap();
+                        background.push(future);
+                        initial(initial_value.unwrap().0, value)
+                    }
+                    None => continue,
+                },
+            };
+            *output = Some(value);
+            remaining -= 1;
+        }
+        if remaining == 0 {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
+        }
+    })
+    .await?;
+
+    Ok((
+        outputs.into_iter().map(Option::unwrap).collect(),
+        background,
+    ))
+}
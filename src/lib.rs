@@ -54,8 +54,9 @@
 //!
 //! This crate offers the following features, all of which are activated by default:
 //!
-//! - `apollo_tracing`: Enable the [Apollo tracing extension](extensions/struct.ApolloTracing.html).
+//! - `apollo_tracing`: Enable the [Apollo tracing extension](extensions/struct.ApolloTracing.html) and the [Apollo federated tracing extension](extensions/struct.ApolloFederationTracing.html).
 //! - `apollo_persisted_queries`: Enable the [Apollo persisted queries extension](extensions/apollo_persisted_queries/struct.ApolloPersistedQueries.html).
+//! - `response_cache`: Enable the [response cache extension](extensions/response_cache/struct.ResponseCache.html).
 //! - `log`: Enable the [logger extension](extensions/struct.Logger.html).
 //! - `tracing`: Enable the [tracing extension](extensions/struct.Tracing.html).
 //! - `multipart`: Support [sending files over HTTP multipart](http/fn.receive_body.html).
@@ -66,6 +67,10 @@
 //! - `url`: Integrate with the [`url` crate](https://crates.io/crates/url).
 //! - `uuid`: Integrate with the [`uuid` crate](https://crates.io/crates/uuid).
 //!
+//! The following features are not activated by default:
+//!
+//! - `opentelemetry`: Enable the [OpenTelemetry extension](extensions/struct.OpenTelemetry.html).
+//!
 //! ## Integrations
 //!
 //! * Actix-web [async-graphql-actix_web](https://crates.io/crates/async-graphql-actix-web)
@@ -119,6 +124,8 @@
 mod base;
 mod context;
 mod error;
+mod incremental;
+mod live;
 mod look_ahead;
 mod model;
 mod request;
@@ -131,6 +138,7 @@ mod validation;
 pub mod extensions;
 pub mod guard;
 pub mod http;
+pub mod pubsub;
 pub mod resolver_utils;
 pub mod types;
 pub mod validators;
@@ -164,15 +172,16 @@ pub use context::{
 };
 pub use error::{
     Error, ErrorExtensions, FieldError, FieldResult, InputValueError, InputValueResult,
-    ParseRequestError, QueryError, ResultExt, RuleError,
+    ParseRequestError, QueryError, ResultExt, RuleError, SchemaError,
 };
+pub use incremental::{Incremental, IncrementalData, IncrementalResponse};
 pub use look_ahead::Lookahead;
 pub use parser::types::{ConstValue as Value, Number};
 pub use registry::CacheControl;
 pub use request::{BatchRequest, Request};
 pub use response::{BatchResponse, Response};
 pub use schema::{Schema, SchemaBuilder, SchemaEnv};
-pub use validation::ValidationMode;
+pub use validation::{ValidationMode, ValidationResult};
 
 #[doc(no_inline)]
 pub use parser::{Pos, Positioned};
@@ -196,6 +205,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// | name          | Object name               | string   | Y        |
 /// | cache_control | Object cache control      | [`CacheControl`](struct.CacheControl.html) | Y        |
 /// | extends       | Add fields to an entity that's defined in another service | bool | Y |
+/// | shareable     | Federation 2: the object can be resolved by several services (`@shareable`) | bool | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
+/// | interface_object | Federation 2: the object stands for an entity interface of another service (`@interfaceObject`) | bool | Y |
+/// | unresolvable  | Federation 2: key fields of an entity that is referenced but not resolved by this service (`@key(resolvable: false)`) | string | Y |
 ///
 /// # Field parameters
 ///
@@ -209,6 +223,10 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// | external      | Mark a field as owned by another service. This allows service A to use fields from service B while also knowing at runtime the types of that field. | bool | Y |
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
+/// | shareable     | Federation 2: the field can be resolved by several services (`@shareable`) | bool | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
+/// | override_from | Federation 2: name of the service this field is migrated from (`@override`) | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
 /// | post_guard    | Field of post guard       | [`PostGuard`](guard/trait.PostGuard.html) | Y        |
 ///
@@ -222,6 +240,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// | default      | Argument default value                   | literal     | Y        |
 /// | default_with | Expression to generate default value     | code string | Y        |
 /// | validator    | Input value validator                    | [`InputValueValidator`](validators/trait.InputValueValidator.html) | Y        |
+/// | process_with | Function called with `&mut T` after parsing | code path   | Y        |
+/// | secret       | Hide the value in logs and error messages| bool        | Y        |
+/// | deprecation  | Deprecation reason                       | string      | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 /// | key          | Is entity key                            | bool        | Y        |
 ///
 /// # Valid field return types
@@ -312,6 +335,11 @@ pub use async_graphql_derive::Object;
 /// | name          | Object name               | string   | Y        |
 /// | cache_control | Object cache control      | [`CacheControl`](struct.CacheControl.html) | Y        |
 /// | extends       | Add fields to an entity that's defined in another service | bool | Y |
+/// | shareable     | Federation 2: the object can be resolved by several services (`@shareable`) | bool | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
+/// | interface_object | Federation 2: the object stands for an entity interface of another service (`@interfaceObject`) | bool | Y |
+/// | unresolvable  | Federation 2: key fields of an entity that is referenced but not resolved by this service (`@key(resolvable: false)`) | string | Y |
 ///
 /// # Field parameters
 ///
@@ -325,6 +353,10 @@ pub use async_graphql_derive::Object;
 /// | external      | Mark a field as owned by another service. This allows service A to use fields from service B while also knowing at runtime the types of that field. | bool | Y |
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
+/// | shareable     | Federation 2: the field can be resolved by several services (`@shareable`) | bool | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
+/// | override_from | Federation 2: name of the service this field is migrated from (`@override`) | string | Y |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
 /// | post_guard    | Field of post guard       | [`PostGuard`](guard/trait.PostGuard.html) | Y        |
 ///
@@ -358,6 +390,8 @@ pub use async_graphql_derive::SimpleObject;
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Enum name                 | string   | Y        |
 /// | remote      | Derive a remote enum      | string   | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Item parameters
 ///
@@ -365,6 +399,8 @@ pub use async_graphql_derive::SimpleObject;
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Item name                 | string   | Y        |
 /// | deprecation | Item deprecation reason   | string   | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Examples
 ///
@@ -412,6 +448,9 @@ pub use async_graphql_derive::Enum;
 /// | Attribute   | description               | Type     | Optional |
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Object name               | string   | Y        |
+/// | input_name  | Object name in input positions, when the struct also derives `SimpleObject` | string   | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Field parameters
 ///
@@ -422,6 +461,12 @@ pub use async_graphql_derive::Enum;
 /// | default      | Argument default value                   | literal     | Y        |
 /// | default_with | Expression to generate default value     | code string | Y        |
 /// | validator    | Input value validator                    | [`InputValueValidator`](validators/trait.InputValueValidator.html) | Y        |
+/// | process_with | Function called with `&mut T` after parsing | code path   | Y        |
+/// | secret       | Hide the value in logs and error messages| bool        | Y        |
+/// | deprecation  | Deprecation reason, of the output field with `input_name` | string      | Y        |
+/// | input_deprecation | Deprecation reason of the input field, only with `input_name` | string | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 /// | flatten      | Similar to serde (flatten)               | boolean     | Y        |
 ///
 /// # Examples
@@ -456,6 +501,40 @@ pub use async_graphql_derive::Enum;
 ///     assert_eq!(res, serde_json::json!({ "value1": 27, "value2": 90 }));
 /// });
 /// ```
+///
+/// # Input and output types from the same struct
+///
+/// With `input_name`, a struct can derive both `SimpleObject` and `InputObject`. The `SimpleObject`
+/// is registered with `name` and the `InputObject` with `input_name`. Field parameters only apply to
+/// the derive that knows them, `deprecation` only deprecates the output field and
+/// `input_deprecation` the input field.
+///
+/// ```rust
+/// use async_graphql::*;
+///
+/// #[derive(SimpleObject, InputObject)]
+/// #[graphql(input_name = "PointInput")]
+/// struct Point {
+///     x: i32,
+///     #[graphql(default = 1)]
+///     y: i32,
+/// }
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn mirror(&self, point: Point) -> Point {
+///         Point { x: point.y, y: point.x }
+///     }
+/// }
+///
+/// async_std::task::block_on(async move {
+///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+///     let res = schema.execute("{ mirror(point: {x: 5}) { x y } }").await.into_result().unwrap().data;
+///     assert_eq!(res, serde_json::json!({ "mirror": { "x": 1, "y": 5 } }));
+/// });
+/// ```
 pub use async_graphql_derive::InputObject;
 
 /// Define a GraphQL interface
@@ -468,7 +547,11 @@ pub use async_graphql_derive::InputObject;
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Object name               | string   | Y        |
 /// | field      | Fields of this Interface  | [InterfaceField] | N |
+/// | implements  | An interface implemented by this interface, can be repeated | type path | Y |
 /// | extends       | Add fields to an entity that's defined in another service | bool | Y |
+/// | node          | A Relay `Node` interface, an `id: ID` field is added if it is not declared and the members implement [`relay::NodeType`](relay/trait.NodeType.html) | bool | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Field parameters
 ///
@@ -483,6 +566,8 @@ pub use async_graphql_derive::InputObject;
 /// | external      | Mark a field as owned by another service. This allows service A to use fields from service B while also knowing at runtime the types of that field. | bool | Y |
 /// | provides      | Annotate the expected returned fieldset from a field on a base type that is guaranteed to be selectable by the gateway. | string | Y |
 /// | requires      | Annotate the required input fieldset from a base type for a resolver. It is used to develop a query plan where the required fields may not be needed by the client, but the service may need additional information from other services. | string | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Field argument parameters
 ///
@@ -494,6 +579,11 @@ pub use async_graphql_derive::InputObject;
 /// | default      | Use `Default::default` for default value | none        | Y        |
 /// | default      | Argument default value                   | literal     | Y        |
 /// | default_with | Expression to generate default value     | code string | Y        |
+/// | process_with | Function called with `&mut T` after parsing | code path   | Y        |
+/// | secret       | Hide the value in logs and error messages| bool        | Y        |
+/// | deprecation  | Deprecation reason                       | string      | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Define an interface
 ///
@@ -599,6 +689,8 @@ pub use async_graphql_derive::Interface;
 /// | Attribute   | description               | Type     | Optional |
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Object name               | string   | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Item parameters
 ///
@@ -665,10 +757,12 @@ pub use async_graphql_derive::Union;
 ///
 /// *[See also the Book](https://async-graphql.github.io/async-graphql/en/subscription.html).*
 ///
-/// The field function is a synchronization function that performs filtering. When true is returned, the message is pushed to the client.
-/// The second parameter is the type of the field.
-/// Starting with the third parameter is one or more filtering conditions, The filter condition is the parameter of the field.
-/// The filter function should be synchronous.
+/// The `filter` function of a field is called for every event with the context of the subscriber
+/// and a reference to the event, the event is only pushed to the client when it returns `true`. The
+/// filter function should be synchronous.
+///
+/// The `guard` of a field is checked when the subscription starts and again for every event, so
+/// the stream ends with an error as soon as the guard fails.
 ///
 /// # Macro parameters
 ///
@@ -684,6 +778,7 @@ pub use async_graphql_derive::Union;
 /// | deprecation | Field deprecation reason  | string   | Y        |
 /// | guard         | Field of guard            | [`Guard`](guard/trait.Guard.html) | Y        |
 /// | post_guard    | Field of post guard       | [`PostGuard`](guard/trait.PostGuard.html) | Y        |
+/// | filter        | Function called with `(&Context<'_>, &T)` for every event | code path | Y |
 ///
 /// # Field argument parameters
 ///
@@ -695,6 +790,11 @@ pub use async_graphql_derive::Union;
 /// | default      | Argument default value                   | literal     | Y        |
 /// | default_with | Expression to generate default value     | code string | Y        |
 /// | validator    | Input value validator                    | [`InputValueValidator`](validators/trait.InputValueValidator.html) | Y        |
+/// | process_with | Function called with `&mut T` after parsing | code path   | Y        |
+/// | secret       | Hide the value in logs and error messages| bool        | Y        |
+/// | deprecation  | Deprecation reason                       | string      | Y        |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 /// # Examples
 ///
@@ -702,6 +802,10 @@ pub use async_graphql_derive::Union;
 /// use async_graphql::*;
 /// use futures::{Stream, StreamExt};
 ///
+/// fn is_even(_ctx: &Context<'_>, value: &i32) -> bool {
+///     value % 2 == 0
+/// }
+///
 /// struct SubscriptionRoot;
 ///
 /// #[Subscription]
@@ -710,6 +814,11 @@ pub use async_graphql_derive::Union;
 ///         // Returns the number from 0 to `condition`.
 ///         futures::stream::iter(0..condition)
 ///     }
+///
+///     #[graphql(filter = "is_even")]
+///     async fn even_value(&self, condition: i32) -> impl Stream<Item = i32> {
+///         futures::stream::iter(0..condition)
+///     }
 /// }
 /// ```
 pub use async_graphql_derive::Subscription;
@@ -721,6 +830,9 @@ pub use async_graphql_derive::Subscription;
 /// | Attribute   | description               | Type     | Optional |
 /// |-------------|---------------------------|----------|----------|
 /// | name        | Scalar name               | string   | Y        |
+/// | specified_by_url | URL of the specification of the scalar, exported with `@specifiedBy` | string | Y |
+/// | inaccessible  | Federation 2: hide it from the supergraph API (`@inaccessible`) | bool | Y |
+/// | tag           | Federation 2: add a `@tag`, can be repeated | string | Y |
 ///
 pub use async_graphql_derive::Scalar;
 
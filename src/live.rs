@@ -0,0 +1,142 @@
+//! Live queries
+//!
+//! A query operation with the `@live` directive executed with `Schema::execute_stream` records the
+//! types of the objects it resolves, and the `Type:id` keys of those with an `id` field. It is
+//! executed again when one of them is invalidated.
+
+use crate::parser::types::OperationDefinition;
+use crate::Positioned;
+use futures::channel::mpsc;
+use futures::{FutureExt, Stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Returns `true` if the operation has the `@live` directive.
+pub(crate) fn is_live(operation: &Positioned<OperationDefinition>) -> bool {
+    operation
+        .node
+        .directives
+        .iter()
+        .any(|directive| directive.node.name.node == "live")
+}
+
+/// Record the dependencies of a resolved field.
+pub(crate) fn record_dependencies(
+    dependencies: &Mutex<HashSet<String>>,
+    type_name: &str,
+    field_name: &str,
+    value: &serde_json::Value,
+) {
+    let mut dependencies = dependencies.lock().unwrap();
+    if !dependencies.contains(type_name) {
+        dependencies.insert(type_name.to_string());
+    }
+    if field_name == "id" {
+        let id = match value {
+            serde_json::Value::String(id) => id.clone(),
+            serde_json::Value::Number(id) => id.to_string(),
+            _ => return,
+        };
+        dependencies.insert(format!("{}:{}", type_name, id));
+    }
+}
+
+struct Listener {
+    dependencies: HashSet<String>,
+    /// The keys invalidated while the query is executed, its dependencies are not known yet.
+    executing: Option<Vec<String>>,
+    sender: mpsc::UnboundedSender<()>,
+}
+
+#[derive(Default)]
+struct LiveQueriesInner {
+    next_id: u64,
+    listeners: HashMap<u64, Listener>,
+}
+
+/// The live queries of a schema.
+#[derive(Clone, Default)]
+pub(crate) struct LiveQueries(Arc<Mutex<LiveQueriesInner>>);
+
+impl LiveQueries {
+    /// Signal the live queries that depend on the key.
+    pub(crate) fn invalidate(&self, key: &str) {
+        let mut inner = self.0.lock().unwrap();
+        for listener in inner.listeners.values_mut() {
+            if let Some(keys) = &mut listener.executing {
+                keys.push(key.to_string());
+            }
+            if listener.dependencies.contains(key) {
+                listener.sender.unbounded_send(()).ok();
+            }
+        }
+    }
+
+    pub(crate) fn listen(&self) -> LiveQueryListener {
+        let mut inner = self.0.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let (sender, receiver) = mpsc::unbounded();
+        inner.listeners.insert(
+            id,
+            Listener {
+                dependencies: HashSet::new(),
+                executing: None,
+                sender,
+            },
+        );
+        LiveQueryListener {
+            queries: self.clone(),
+            id,
+            receiver,
+        }
+    }
+}
+
+/// The invalidation signals of a live query, it is unregistered when dropped.
+pub(crate) struct LiveQueryListener {
+    queries: LiveQueries,
+    id: u64,
+    receiver: mpsc::UnboundedReceiver<()>,
+}
+
+impl LiveQueryListener {
+    /// Called before executing the query.
+    pub(crate) fn begin(&mut self) {
+        // The signals received until now are handled by this execution.
+        while let Some(Some(())) = self.receiver.next().now_or_never() {}
+        let mut inner = self.queries.0.lock().unwrap();
+        if let Some(listener) = inner.listeners.get_mut(&self.id) {
+            listener.executing = Some(Vec::new());
+        }
+    }
+
+    /// Called with the dependencies of the execution, signals the query again if one of them has
+    /// been invalidated during the execution.
+    pub(crate) fn end(&mut self, dependencies: HashSet<String>) {
+        let mut inner = self.queries.0.lock().unwrap();
+        if let Some(listener) = inner.listeners.get_mut(&self.id) {
+            let invalidated = listener.executing.take().unwrap_or_default();
+            if invalidated.iter().any(|key| dependencies.contains(key)) {
+                listener.sender.unbounded_send(()).ok();
+            }
+            listener.dependencies = dependencies;
+        }
+    }
+}
+
+impl Stream for LiveQueryListener {
+    type Item = ();
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for LiveQueryListener {
+    fn drop(&mut self) {
+        self.queries.0.lock().unwrap().listeners.remove(&self.id);
+    }
+}
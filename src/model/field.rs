@@ -18,10 +18,14 @@ impl<'a> __Field<'a> {
         self.field.description.map(|s| s.to_string())
     }
 
-    async fn args(&self) -> Vec<__InputValue<'a>> {
+    async fn args(
+        &self,
+        #[graphql(default = false)] include_deprecated: bool,
+    ) -> Vec<__InputValue<'a>> {
         self.field
             .args
             .values()
+            .filter(|input_value| include_deprecated || input_value.deprecation.is_none())
             .map(|input_value| __InputValue {
                 registry: self.registry,
                 input_value,
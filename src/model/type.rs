@@ -114,7 +114,9 @@ impl<'a> __Type<'a> {
     }
 
     async fn interfaces(&self) -> Option<Vec<__Type<'a>>> {
-        if let TypeDetail::Named(registry::MetaType::Object { name, .. }) = &self.detail {
+        if let TypeDetail::Named(registry::MetaType::Object { name, .. })
+        | TypeDetail::Named(registry::MetaType::Interface { name, .. }) = &self.detail
+        {
             Some(
                 self.registry
                     .implements
@@ -173,13 +175,17 @@ impl<'a> __Type<'a> {
         }
     }
 
-    async fn input_fields(&self) -> Option<Vec<__InputValue<'a>>> {
+    async fn input_fields(
+        &self,
+        #[graphql(default = false)] include_deprecated: bool,
+    ) -> Option<Vec<__InputValue<'a>>> {
         if let TypeDetail::Named(registry::MetaType::InputObject { input_fields, .. }) =
             &self.detail
         {
             Some(
                 input_fields
                     .values()
+                    .filter(|input_value| include_deprecated || input_value.deprecation.is_none())
                     .map(|input_value| __InputValue {
                         registry: self.registry,
                         input_value,
@@ -200,4 +206,16 @@ impl<'a> __Type<'a> {
             None
         }
     }
+
+    #[graphql(name = "specifiedByURL")]
+    async fn specified_by_url(&self) -> Option<String> {
+        if let TypeDetail::Named(registry::MetaType::Scalar {
+            specified_by_url, ..
+        }) = &self.detail
+        {
+            specified_by_url.map(|url| url.to_string())
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,290 @@
+//! Publish/subscribe support for subscriptions.
+//!
+//! Subscription resolvers return the stream of a [`PubSub`](trait.PubSub.html) implementation,
+//! and mutations publish the messages to it. [`SimpleBroker`](struct.SimpleBroker.html) is the
+//! in-process implementation; implement `PubSub` to use an external message broker.
+
+use crate::FieldResult;
+use futures::stream::BoxStream;
+use futures::task::{Context, Poll, Waker};
+use futures::{Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A publish/subscribe channel for messages of type `T`.
+#[async_trait::async_trait]
+pub trait PubSub<T: Send + 'static>: Send + Sync + 'static {
+    /// Publish a message to all the current subscribers.
+    async fn publish(&self, message: T) -> FieldResult<()>;
+
+    /// Subscribe to the messages published after this call.
+    fn subscribe(&self) -> BoxStream<'static, T>;
+}
+
+/// What a [`SimpleBroker`](struct.SimpleBroker.html) does when the buffer of a subscriber is
+/// full.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LagPolicy {
+    /// Drop the oldest buffered message of the subscriber.
+    DropOldest,
+
+    /// Drop the published message for the subscriber.
+    DropNewest,
+
+    /// End the stream of the subscriber after its buffered messages.
+    Disconnect,
+
+    /// Wait until the subscriber has room for the message before `publish` returns.
+    ///
+    /// A slow subscriber slows down all the publishers.
+    Wait,
+}
+
+struct Slot<T> {
+    queue: VecDeque<T>,
+    closed: bool,
+    receiver: Option<Waker>,
+    senders: Vec<Waker>,
+}
+
+impl<T> Slot<T> {
+    fn wake_receiver(&mut self) {
+        if let Some(waker) = self.receiver.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_senders(&mut self) {
+        for waker in self.senders.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+struct Inner<T> {
+    capacity: usize,
+    policy: LagPolicy,
+    next_id: AtomicUsize,
+    subscribers: Mutex<HashMap<usize, Arc<Mutex<Slot<T>>>>>,
+}
+
+/// An in-process publish/subscribe broker for messages of type `T`.
+///
+/// Every subscriber has a bounded buffer of `capacity` messages, and the
+/// [`LagPolicy`](enum.LagPolicy.html) decides what happens when it is full. The default capacity
+/// is 128 and the default policy is `LagPolicy::DropOldest`.
+///
+/// The broker is cheap to clone, put it in the schema data and get it from the context in the
+/// resolvers.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::pubsub::SimpleBroker;
+/// use futures::Stream;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn value(&self) -> i32 {
+///         10
+///     }
+/// }
+///
+/// struct MutationRoot;
+///
+/// #[Object]
+/// impl MutationRoot {
+///     async fn send(&self, ctx: &Context<'_>, message: String) -> bool {
+///         ctx.data_unchecked::<SimpleBroker<String>>().publish(message).await;
+///         true
+///     }
+/// }
+///
+/// struct SubscriptionRoot;
+///
+/// #[Subscription]
+/// impl SubscriptionRoot {
+///     async fn messages(&self, ctx: &Context<'_>) -> impl Stream<Item = String> {
+///         ctx.data_unchecked::<SimpleBroker<String>>().subscribe()
+///     }
+/// }
+///
+/// let schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+///     .data(SimpleBroker::<String>::new())
+///     .finish();
+/// ```
+pub struct SimpleBroker<T>(Arc<Inner<T>>);
+
+impl<T> Clone for SimpleBroker<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Clone + Send + 'static> Default for SimpleBroker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Send + 'static> SimpleBroker<T> {
+    /// Create a broker.
+    pub fn new() -> Self {
+        Self(Arc::new(Inner {
+            capacity: 128,
+            policy: LagPolicy::DropOldest,
+            next_id: AtomicUsize::new(0),
+            subscribers: Default::default(),
+        }))
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner<T> {
+        Arc::get_mut(&mut self.0).expect("The broker has already been cloned.")
+    }
+
+    /// Set the buffer capacity of each subscriber.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0 or the broker has already been cloned.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "The capacity must be greater than 0.");
+        self.inner_mut().capacity = capacity;
+        self
+    }
+
+    /// Set the policy for the subscribers whose buffer is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the broker has already been cloned.
+    pub fn lag_policy(mut self, policy: LagPolicy) -> Self {
+        self.inner_mut().policy = policy;
+        self
+    }
+
+    /// Returns the number of current subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.0.subscribers.lock().unwrap().len()
+    }
+
+    /// Publish a message to all the current subscribers.
+    pub async fn publish(&self, message: T) {
+        let slots = self
+            .0
+            .subscribers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, slot)| (*id, slot.clone()))
+            .collect::<Vec<_>>();
+        for (id, slot) in slots {
+            self.send(id, &slot, message.clone()).await;
+        }
+    }
+
+    async fn send(&self, id: usize, slot: &Mutex<Slot<T>>, message: T) {
+        let mut message = Some(message);
+        futures::future::poll_fn(|cx| {
+            let mut slot = slot.lock().unwrap();
+            if slot.closed {
+                return Poll::Ready(());
+            }
+            if slot.queue.len() >= self.0.capacity {
+                match self.0.policy {
+                    LagPolicy::DropOldest => {
+                        slot.queue.pop_front();
+                    }
+                    LagPolicy::DropNewest => return Poll::Ready(()),
+                    LagPolicy::Disconnect => {
+                        slot.closed = true;
+                        slot.wake_receiver();
+                        drop(slot);
+                        self.0.subscribers.lock().unwrap().remove(&id);
+                        return Poll::Ready(());
+                    }
+                    LagPolicy::Wait => {
+                        slot.senders.push(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                }
+            }
+            slot.queue.extend(message.take());
+            slot.wake_receiver();
+            Poll::Ready(())
+        })
+        .await
+    }
+
+    /// Subscribe to the messages published after this call.
+    ///
+    /// Note that a subscription resolver only runs when its response stream is first polled. The
+    /// subscriber is removed when the stream is dropped.
+    pub fn subscribe(&self) -> impl Stream<Item = T> + Send + Unpin + 'static {
+        let id = self.0.next_id.fetch_add(1, Ordering::Relaxed);
+        let slot = Arc::new(Mutex::new(Slot {
+            queue: VecDeque::new(),
+            closed: false,
+            receiver: None,
+            senders: Vec::new(),
+        }));
+        self.0.subscribers.lock().unwrap().insert(id, slot.clone());
+        Subscriber {
+            id,
+            slot,
+            broker: self.clone(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Clone + Send + 'static> PubSub<T> for SimpleBroker<T> {
+    async fn publish(&self, message: T) -> FieldResult<()> {
+        SimpleBroker::publish(self, message).await;
+        Ok(())
+    }
+
+    fn subscribe(&self) -> BoxStream<'static, T> {
+        SimpleBroker::subscribe(self).boxed()
+    }
+}
+
+struct Subscriber<T> {
+    id: usize,
+    slot: Arc<Mutex<Slot<T>>>,
+    broker: SimpleBroker<T>,
+}
+
+impl<T> Stream for Subscriber<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.queue.pop_front() {
+            Some(message) => {
+                slot.wake_senders();
+                Poll::Ready(Some(message))
+            }
+            None if slot.closed => Poll::Ready(None),
+            None => {
+                slot.receiver = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        let mut slot = self.slot.lock().unwrap();
+        slot.closed = true;
+        slot.wake_senders();
+        drop(slot);
+        self.broker.0.subscribers.lock().unwrap().remove(&self.id);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::registry::{MetaField, MetaInputValue, MetaType, MetaTypeName, Registry};
+use crate::SchemaError;
+use indexmap::map::IndexMap;
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(ch) if ch == '_' || ch.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric())
+}
+
+impl Registry {
+    /// Validate the types in the registry.
+    ///
+    /// Checks that all names are valid, all referenced types are defined and have the right kind,
+    /// union members are objects, and every type implementing an interface provides its fields with
+    /// compatible types and arguments and also implements the interfaces of that interface.
+    pub fn check(&self) -> Vec<SchemaError> {
+        let mut errors = Vec::new();
+
+        for (name, ty) in &self.types {
+            self.check_name(&mut errors, name, name);
+
+            match ty {
+                MetaType::Object { fields, .. } | MetaType::Interface { fields, .. } => {
+                    self.check_fields(&mut errors, name, fields);
+                }
+                MetaType::Union { possible_types, .. } => {
+                    for member in possible_types {
+                        match self.types.get(member) {
+                            Some(MetaType::Object { .. }) => {}
+                            Some(_) => errors.push(SchemaError::UnionMemberNotObject {
+                                union: name.clone(),
+                                member: member.clone(),
+                            }),
+                            None => errors.push(SchemaError::UnknownType {
+                                coordinate: name.clone(),
+                                ty: member.clone(),
+                            }),
+                        }
+                    }
+                }
+                MetaType::Enum { enum_values, .. } => {
+                    for value in enum_values.keys() {
+                        self.check_name(&mut errors, &format!("{}.{}", name, value), value);
+                    }
+                }
+                MetaType::InputObject { input_fields, .. } => {
+                    for (field_name, field) in input_fields {
+                        self.check_input_value(
+                            &mut errors,
+                            &format!("{}.{}", name, field_name),
+                            field,
+                        );
+                    }
+                }
+                MetaType::Scalar { .. } => {}
+            }
+        }
+
+        for directive in self.directives.values() {
+            for (arg_name, arg) in &directive.args {
+                self.check_input_value(
+                    &mut errors,
+                    &format!("@{}({}:)", directive.name, arg_name),
+                    arg,
+                );
+            }
+        }
+
+        for (ty, interfaces) in &self.implements {
+            for interface in interfaces {
+                self.check_implementation(&mut errors, ty, interface);
+
+                for transitive in self.implements.get(interface).into_iter().flatten() {
+                    if transitive == ty {
+                        errors.push(SchemaError::SelfImplementation {
+                            interface: ty.clone(),
+                        });
+                    } else if !interfaces.contains(transitive) {
+                        errors.push(SchemaError::MissingTransitiveInterface {
+                            ty: ty.clone(),
+                            interface: interface.clone(),
+                            transitive: transitive.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    fn check_name(&self, errors: &mut Vec<SchemaError>, coordinate: &str, name: &str) {
+        if !is_valid_name(name) {
+            errors.push(SchemaError::InvalidName {
+                coordinate: coordinate.to_string(),
+                name: name.to_string(),
+            });
+        }
+    }
+
+    fn check_fields(
+        &self,
+        errors: &mut Vec<SchemaError>,
+        type_name: &str,
+        fields: &IndexMap<String, MetaField>,
+    ) {
+        for (field_name, field) in fields {
+            let coordinate = format!("{}.{}", type_name, field_name);
+            self.check_name(errors, &coordinate, field_name);
+
+            let concrete_type = MetaTypeName::concrete_typename(&field.ty);
+            match self.types.get(concrete_type) {
+                Some(MetaType::InputObject { .. }) => errors.push(SchemaError::NotOutputType {
+                    coordinate: coordinate.clone(),
+                    ty: concrete_type.to_string(),
+                }),
+                Some(_) => {}
+                None => errors.push(SchemaError::UnknownType {
+                    coordinate: coordinate.clone(),
+                    ty: concrete_type.to_string(),
+                }),
+            }
+
+            for (arg_name, arg) in &field.args {
+                self.check_input_value(
+                    errors,
+                    &format!("{}.{}({}:)", type_name, field_name, arg_name),
+                    arg,
+                );
+            }
+        }
+    }
+
+    fn check_input_value(
+        &self,
+        errors: &mut Vec<SchemaError>,
+        coordinate: &str,
+        input_value: &MetaInputValue,
+    ) {
+        self.check_name(errors, coordinate, input_value.name);
+
+        if input_value.deprecation.is_some()
+            && MetaTypeName::create(&input_value.ty).is_non_null()
+            && input_value.default_value.is_none()
+        {
+            errors.push(SchemaError::DeprecatedRequiredInputValue {
+                coordinate: coordinate.to_string(),
+            });
+        }
+
+        let concrete_type = MetaTypeName::concrete_typename(&input_value.ty);
+        match self.types.get(concrete_type) {
+            Some(ty) if !ty.is_input() => errors.push(SchemaError::NotInputType {
+                coordinate: coordinate.to_string(),
+                ty: concrete_type.to_string(),
+            }),
+            Some(_) => {}
+            None => errors.push(SchemaError::UnknownType {
+                coordinate: coordinate.to_string(),
+                ty: concrete_type.to_string(),
+            }),
+        }
+    }
+
+    fn check_implementation(&self, errors: &mut Vec<SchemaError>, ty: &str, interface: &str) {
+        if ty == interface {
+            errors.push(SchemaError::SelfImplementation {
+                interface: interface.to_string(),
+            });
+            return;
+        }
+
+        let interface_fields = match self.types.get(interface) {
+            Some(MetaType::Interface { fields, .. }) => fields,
+            Some(_) => {
+                errors.push(SchemaError::NotInterface {
+                    ty: ty.to_string(),
+                    interface: interface.to_string(),
+                });
+                return;
+            }
+            None => {
+                errors.push(SchemaError::UnknownType {
+                    coordinate: ty.to_string(),
+                    ty: interface.to_string(),
+                });
+                return;
+            }
+        };
+        let fields = match self.types.get(ty).and_then(MetaType::fields) {
+            Some(fields) => fields,
+            None => return,
+        };
+
+        for (field_name, interface_field) in interface_fields {
+            let field = match fields.get(field_name) {
+                Some(field) => field,
+                None => {
+                    errors.push(SchemaError::MissingInterfaceField {
+                        ty: ty.to_string(),
+                        interface: interface.to_string(),
+                        field: field_name.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            if !self.is_valid_implementation_type(&field.ty, &interface_field.ty) {
+                errors.push(SchemaError::InterfaceFieldTypeMismatch {
+                    ty: ty.to_string(),
+                    interface: interface.to_string(),
+                    field: field_name.clone(),
+                    expect: interface_field.ty.clone(),
+                    actual: field.ty.clone(),
+                });
+            }
+
+            for (arg_name, interface_arg) in &interface_field.args {
+                match field.args.get(arg_name) {
+                    Some(arg) if arg.ty != interface_arg.ty => {
+                        errors.push(SchemaError::InterfaceArgumentTypeMismatch {
+                            ty: ty.to_string(),
+                            interface: interface.to_string(),
+                            field: field_name.clone(),
+                            argument: arg_name.to_string(),
+                            expect: interface_arg.ty.clone(),
+                            actual: arg.ty.clone(),
+                        })
+                    }
+                    Some(_) => {}
+                    None => errors.push(SchemaError::MissingInterfaceArgument {
+                        ty: ty.to_string(),
+                        interface: interface.to_string(),
+                        field: field_name.clone(),
+                        argument: arg_name.to_string(),
+                    }),
+                }
+            }
+
+            for (arg_name, arg) in &field.args {
+                if !interface_field.args.contains_key(arg_name)
+                    && MetaTypeName::create(&arg.ty).is_non_null()
+                    && arg.default_value.is_none()
+                {
+                    errors.push(SchemaError::RequiredExtraArgument {
+                        ty: ty.to_string(),
+                        interface: interface.to_string(),
+                        field: field_name.clone(),
+                        argument: arg_name.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if a field of type `ty` can implement an interface field of type `interface_ty`.
+    fn is_valid_implementation_type(&self, ty: &str, interface_ty: &str) -> bool {
+        match (MetaTypeName::create(ty), MetaTypeName::create(interface_ty)) {
+            (MetaTypeName::NonNull(ty), MetaTypeName::NonNull(interface_ty)) => {
+                self.is_valid_implementation_type(ty, interface_ty)
+            }
+            (MetaTypeName::NonNull(ty), _) => self.is_valid_implementation_type(ty, interface_ty),
+            (MetaTypeName::List(ty), MetaTypeName::List(interface_ty)) => {
+                self.is_valid_implementation_type(ty, interface_ty)
+            }
+            (MetaTypeName::Named(ty), MetaTypeName::Named(interface_ty)) => {
+                ty == interface_ty
+                    || self
+                        .types
+                        .get(interface_ty)
+                        .map(|meta_type| meta_type.is_possible_type(ty))
+                        .unwrap_or_default()
+                    || self
+                        .implements
+                        .get(ty)
+                        .map(|interfaces| interfaces.contains(interface_ty))
+                        .unwrap_or_default()
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_valid_name;
+
+    #[test]
+    fn test_is_valid_name() {
+        assert!(is_valid_name("_a1"));
+        assert!(is_valid_name("Query"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("1a"));
+        assert!(!is_valid_name("a-b"));
+    }
+}
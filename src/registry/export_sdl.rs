@@ -1,11 +1,60 @@
 use crate::registry::{MetaField, MetaInputValue, MetaType, Registry};
+use crate::Value;
 use itertools::Itertools;
 use std::fmt::Write;
 
+const FEDERATION2_URL: &str = "https://specs.apollo.dev/federation/v2.3";
+const FEDERATION2_DIRECTIVES: &[&str] = &[
+    "@key",
+    "@requires",
+    "@provides",
+    "@external",
+    "@shareable",
+    "@inaccessible",
+    "@override",
+    "@tag",
+    "@interfaceObject",
+    "@composeDirective",
+];
+
 impl Registry {
     pub fn export_sdl(&self, federation: bool) -> String {
         let mut sdl = String::new();
 
+        if federation && self.enable_federation2 {
+            write!(
+                sdl,
+                "extend schema @link(url: {}, import: [{}])",
+                Value::String(FEDERATION2_URL.to_string()),
+                FEDERATION2_DIRECTIVES
+                    .iter()
+                    .map(|name| Value::String(name.to_string()))
+                    .join(", ")
+            )
+            .ok();
+            for (url, names) in &self.compose_directives {
+                write!(
+                    sdl,
+                    "\n\t@link(url: {}, import: [{}])",
+                    Value::String(url.clone()),
+                    names
+                        .iter()
+                        .map(|name| Value::String(format!("@{}", name)))
+                        .join(", ")
+                )
+                .ok();
+                for name in names {
+                    write!(
+                        sdl,
+                        "\n\t@composeDirective(name: {})",
+                        Value::String(format!("@{}", name))
+                    )
+                    .ok();
+                }
+            }
+            writeln!(sdl).ok();
+        }
+
         for ty in self.types.values() {
             if ty.name().starts_with("__") {
                 continue;
@@ -37,10 +86,13 @@ impl Registry {
     }
 
     fn export_fields<'a, I: Iterator<Item = &'a MetaField>>(
+        &self,
         sdl: &mut String,
         it: I,
         federation: bool,
     ) {
+        let federation2 = federation && self.enable_federation2;
+
         for field in it {
             if field.name.starts_with("__")
                 || (federation && matches!(&*field.name, "_service" | "_entities"))
@@ -48,15 +100,19 @@ impl Registry {
                 continue;
             }
 
-            if field.description.is_some() && !federation {
-                writeln!(
-                    sdl,
-                    "\t\"\"\"\n\t{}\n\t\"\"\"",
-                    field.description.unwrap().replace("\n", "\n\t")
-                )
-                .ok();
+            if !federation {
+                export_description(sdl, "\t", field.description);
             }
-            if !field.args.is_empty() {
+            if field.args.is_empty() {
+                write!(sdl, "\t{}: {}", field.name, field.ty).ok();
+            } else if !federation && field.args.values().any(|arg| arg.description.is_some()) {
+                writeln!(sdl, "\t{}(", field.name).ok();
+                for arg in field.args.values() {
+                    export_description(sdl, "\t\t", arg.description);
+                    writeln!(sdl, "\t\t{}", export_input_value(arg, federation2)).ok();
+                }
+                write!(sdl, "\t): {}", field.ty).ok();
+            } else {
                 write!(
                     sdl,
                     "\t{}({}): {}",
@@ -64,14 +120,13 @@ impl Registry {
                     field
                         .args
                         .values()
-                        .map(|arg| export_input_value(arg))
+                        .map(|arg| export_input_value(arg, federation2))
                         .join(", "),
                     field.ty
                 )
                 .ok();
-            } else {
-                write!(sdl, "\t{}: {}", field.name, field.ty).ok();
             }
+            export_deprecation(sdl, field.deprecation);
 
             if federation {
                 if field.external {
@@ -84,15 +139,31 @@ impl Registry {
                     write!(sdl, " @provides(fields: \"{}\")", provides).ok();
                 }
             }
+            if federation2 {
+                if field.shareable {
+                    write!(sdl, " @shareable").ok();
+                }
+                if let Some(from) = field.override_from {
+                    write!(sdl, " @override(from: {})", Value::String(from.to_string())).ok();
+                }
+                export_inaccessible_and_tags(sdl, field.inaccessible, &field.tags);
+            }
 
             writeln!(sdl).ok();
         }
     }
 
     fn export_type(&self, ty: &MetaType, sdl: &mut String, federation: bool) {
+        let federation2 = federation && self.enable_federation2;
+
         match ty {
             MetaType::Scalar {
-                name, description, ..
+                name,
+                description,
+                specified_by_url,
+                inaccessible,
+                tags,
+                ..
             } => {
                 const SYSTEM_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
                 const FEDERATION_SCALARS: &[&str] = &["Any"];
@@ -101,10 +172,22 @@ impl Registry {
                     export_scalar = false;
                 }
                 if export_scalar {
-                    if description.is_some() && !federation {
-                        writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                    if !federation {
+                        export_description(sdl, "", *description);
+                    }
+                    write!(sdl, "scalar {}", name).ok();
+                    if let Some(url) = specified_by_url {
+                        write!(
+                            sdl,
+                            " @specifiedBy(url: {})",
+                            Value::String(url.to_string())
+                        )
+                        .ok();
                     }
-                    writeln!(sdl, "scalar {}", name).ok();
+                    if federation2 {
+                        export_inaccessible_and_tags(sdl, *inaccessible, tags);
+                    }
+                    writeln!(sdl).ok();
                 }
             }
             MetaType::Object {
@@ -112,6 +195,11 @@ impl Registry {
                 fields,
                 extends,
                 keys,
+                resolvable,
+                shareable,
+                inaccessible,
+                interface_object,
+                tags,
                 description,
                 ..
             } => {
@@ -126,29 +214,42 @@ impl Registry {
                     }
                 }
 
-                if description.is_some() && !federation {
-                    writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                if !federation {
+                    export_description(sdl, "", *description);
                 }
                 if federation && *extends {
                     write!(sdl, "extend ").ok();
                 }
-                write!(sdl, "type {} ", name).ok();
+                write!(sdl, "type {}", name).ok();
                 if let Some(implements) = self.implements.get(name) {
                     if !implements.is_empty() {
-                        write!(sdl, "implements {} ", implements.iter().join(" & ")).ok();
+                        write!(sdl, " implements {}", implements.iter().join(" & ")).ok();
                     }
                 }
 
                 if federation {
                     if let Some(keys) = keys {
                         for key in keys {
-                            write!(sdl, "@key(fields: \"{}\") ", key).ok();
+                            if federation2 && !*resolvable {
+                                write!(sdl, " @key(fields: \"{}\", resolvable: false)", key).ok();
+                            } else {
+                                write!(sdl, " @key(fields: \"{}\")", key).ok();
+                            }
                         }
                     }
                 }
+                if federation2 {
+                    if *shareable {
+                        write!(sdl, " @shareable").ok();
+                    }
+                    if *interface_object {
+                        write!(sdl, " @interfaceObject").ok();
+                    }
+                    export_inaccessible_and_tags(sdl, *inaccessible, tags);
+                }
 
-                writeln!(sdl, "{{").ok();
-                Self::export_fields(sdl, fields.values(), federation);
+                writeln!(sdl, " {{").ok();
+                self.export_fields(sdl, fields.values(), federation);
                 writeln!(sdl, "}}").ok();
             }
             MetaType::Interface {
@@ -156,90 +257,153 @@ impl Registry {
                 fields,
                 extends,
                 keys,
+                inaccessible,
+                tags,
                 description,
                 ..
             } => {
-                if description.is_some() && !federation {
-                    writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                if !federation {
+                    export_description(sdl, "", *description);
                 }
                 if federation && *extends {
                     write!(sdl, "extend ").ok();
                 }
-                write!(sdl, "interface {} ", name).ok();
+                write!(sdl, "interface {}", name).ok();
+                if let Some(implements) = self.implements.get(name) {
+                    if !implements.is_empty() {
+                        write!(sdl, " implements {}", implements.iter().join(" & ")).ok();
+                    }
+                }
                 if federation {
                     if let Some(keys) = keys {
                         for key in keys {
-                            write!(sdl, "@key(fields: \"{}\") ", key).ok();
+                            write!(sdl, " @key(fields: \"{}\")", key).ok();
                         }
                     }
                 }
-                writeln!(sdl, "{{").ok();
-                Self::export_fields(sdl, fields.values(), federation);
+                if federation2 {
+                    export_inaccessible_and_tags(sdl, *inaccessible, tags);
+                }
+                writeln!(sdl, " {{").ok();
+                self.export_fields(sdl, fields.values(), federation);
                 writeln!(sdl, "}}").ok();
             }
             MetaType::Enum {
                 name,
                 enum_values,
+                inaccessible,
+                tags,
                 description,
                 ..
             } => {
-                if description.is_some() && !federation {
-                    writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                if !federation {
+                    export_description(sdl, "", *description);
                 }
-                write!(sdl, "enum {} ", name).ok();
-                writeln!(sdl, "{{").ok();
+                write!(sdl, "enum {}", name).ok();
+                if federation2 {
+                    export_inaccessible_and_tags(sdl, *inaccessible, tags);
+                }
+                writeln!(sdl, " {{").ok();
                 for value in enum_values.values() {
-                    writeln!(sdl, "\t{}", value.name).ok();
+                    if !federation {
+                        export_description(sdl, "\t", value.description);
+                    }
+                    write!(sdl, "\t{}", value.name).ok();
+                    export_deprecation(sdl, value.deprecation);
+                    if federation2 {
+                        export_inaccessible_and_tags(sdl, value.inaccessible, &value.tags);
+                    }
+                    writeln!(sdl).ok();
                 }
                 writeln!(sdl, "}}").ok();
             }
             MetaType::InputObject {
                 name,
                 input_fields,
+                inaccessible,
+                tags,
                 description,
                 ..
             } => {
-                if description.is_some() && !federation {
-                    writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                if !federation {
+                    export_description(sdl, "", *description);
+                }
+                write!(sdl, "input {}", name).ok();
+                if federation2 {
+                    export_inaccessible_and_tags(sdl, *inaccessible, tags);
                 }
-                write!(sdl, "input {} ", name).ok();
-                writeln!(sdl, "{{").ok();
+                writeln!(sdl, " {{").ok();
                 for field in input_fields.values() {
-                    if let Some(description) = field.description {
-                        writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description).ok();
+                    if !federation {
+                        export_description(sdl, "\t", field.description);
                     }
-                    writeln!(sdl, "{}", export_input_value(&field)).ok();
+                    writeln!(sdl, "\t{}", export_input_value(&field, federation2)).ok();
                 }
                 writeln!(sdl, "}}").ok();
             }
             MetaType::Union {
                 name,
                 possible_types,
+                inaccessible,
+                tags,
                 description,
                 ..
             } => {
-                if description.is_some() && !federation {
-                    writeln!(sdl, "\"\"\"\n{}\n\"\"\"", description.unwrap()).ok();
+                if !federation {
+                    export_description(sdl, "", *description);
                 }
-                writeln!(
-                    sdl,
-                    "union {} = {}",
-                    name,
-                    possible_types.iter().join(" | ")
-                )
-                .ok();
+                write!(sdl, "union {}", name).ok();
+                if federation2 {
+                    export_inaccessible_and_tags(sdl, *inaccessible, tags);
+                }
+                writeln!(sdl, " = {}", possible_types.iter().join(" | ")).ok();
             }
         }
     }
 }
 
-fn export_input_value(input_value: &MetaInputValue) -> String {
-    if let Some(default_value) = &input_value.default_value {
-        format!(
-            "{}: {} = {}",
-            input_value.name, input_value.ty, default_value
+fn export_description(sdl: &mut String, indent: &str, description: Option<&str>) {
+    if let Some(description) = description {
+        writeln!(sdl, "{}\"\"\"", indent).ok();
+        for line in description.replace("\"\"\"", "\\\"\"\"").lines() {
+            if line.is_empty() {
+                writeln!(sdl).ok();
+            } else {
+                writeln!(sdl, "{}{}", indent, line).ok();
+            }
+        }
+        writeln!(sdl, "{}\"\"\"", indent).ok();
+    }
+}
+
+fn export_deprecation(sdl: &mut String, deprecation: Option<&str>) {
+    if let Some(reason) = deprecation {
+        write!(
+            sdl,
+            " @deprecated(reason: {})",
+            Value::String(reason.to_string())
         )
-    } else {
-        format!("{}: {}", input_value.name, input_value.ty)
+        .ok();
+    }
+}
+
+fn export_inaccessible_and_tags(sdl: &mut String, inaccessible: bool, tags: &[&str]) {
+    if inaccessible {
+        write!(sdl, " @inaccessible").ok();
+    }
+    for tag in tags {
+        write!(sdl, " @tag(name: {})", Value::String(tag.to_string())).ok();
+    }
+}
+
+fn export_input_value(input_value: &MetaInputValue, federation2: bool) -> String {
+    let mut sdl = format!("{}: {}", input_value.name, input_value.ty);
+    if let Some(default_value) = &input_value.default_value {
+        write!(sdl, " = {}", default_value).ok();
+    }
+    export_deprecation(&mut sdl, input_value.deprecation);
+    if federation2 {
+        export_inaccessible_and_tags(&mut sdl, input_value.inaccessible, &input_value.tags);
     }
+    sdl
 }
@@ -1,4 +1,5 @@
 mod cache_control;
+mod check;
 mod export_sdl;
 
 use crate::parser::types::{BaseType as ParsedBaseType, Type as ParsedType};
@@ -90,6 +91,10 @@ pub struct MetaInputValue {
     pub ty: String,
     pub default_value: Option<String>,
     pub validator: Option<Arc<dyn InputValueValidator>>,
+    pub deprecation: Option<&'static str>,
+    pub is_secret: bool,
+    pub inaccessible: bool,
+    pub tags: Vec<&'static str>,
 }
 
 #[derive(Clone)]
@@ -103,6 +108,10 @@ pub struct MetaField {
     pub external: bool,
     pub requires: Option<&'static str>,
     pub provides: Option<&'static str>,
+    pub shareable: bool,
+    pub inaccessible: bool,
+    pub tags: Vec<&'static str>,
+    pub override_from: Option<&'static str>,
 }
 
 #[derive(Clone)]
@@ -110,6 +119,8 @@ pub struct MetaEnumValue {
     pub name: &'static str,
     pub description: Option<&'static str>,
     pub deprecation: Option<&'static str>,
+    pub inaccessible: bool,
+    pub tags: Vec<&'static str>,
 }
 
 pub enum MetaType {
@@ -117,6 +128,9 @@ pub enum MetaType {
         name: String,
         description: Option<&'static str>,
         is_valid: fn(value: &Value) -> bool,
+        specified_by_url: Option<&'static str>,
+        inaccessible: bool,
+        tags: Vec<&'static str>,
     },
     Object {
         name: String,
@@ -125,6 +139,11 @@ pub enum MetaType {
         cache_control: CacheControl,
         extends: bool,
         keys: Option<Vec<String>>,
+        resolvable: bool,
+        shareable: bool,
+        inaccessible: bool,
+        interface_object: bool,
+        tags: Vec<&'static str>,
     },
     Interface {
         name: String,
@@ -133,21 +152,29 @@ pub enum MetaType {
         possible_types: IndexSet<String>,
         extends: bool,
         keys: Option<Vec<String>>,
+        inaccessible: bool,
+        tags: Vec<&'static str>,
     },
     Union {
         name: String,
         description: Option<&'static str>,
         possible_types: IndexSet<String>,
+        inaccessible: bool,
+        tags: Vec<&'static str>,
     },
     Enum {
         name: String,
         description: Option<&'static str>,
         enum_values: IndexMap<&'static str, MetaEnumValue>,
+        inaccessible: bool,
+        tags: Vec<&'static str>,
     },
     InputObject {
         name: String,
         description: Option<&'static str>,
         input_fields: IndexMap<String, MetaInputValue>,
+        inaccessible: bool,
+        tags: Vec<&'static str>,
     },
 }
 
@@ -259,18 +286,33 @@ pub struct Registry {
     pub query_type: String,
     pub mutation_type: Option<String>,
     pub subscription_type: Option<String>,
+    pub enable_federation2: bool,
+    pub compose_directives: IndexMap<String, Vec<String>>,
 }
 
 impl Registry {
     pub fn create_type<T: crate::Type, F: FnMut(&mut Registry) -> MetaType>(
         &mut self,
-        mut f: F,
+        f: F,
+    ) -> String {
+        self.create_type_with_name(&T::type_name(), f);
+        T::qualified_type_name()
+    }
+
+    /// Like `create_type`, but registers the type under its input type name.
+    pub fn create_input_type<T: crate::InputValueType, F: FnMut(&mut Registry) -> MetaType>(
+        &mut self,
+        f: F,
     ) -> String {
-        let name = T::type_name();
-        if !self.types.contains_key(name.as_ref()) {
+        self.create_type_with_name(&T::input_type_name(), f);
+        T::qualified_input_type_name()
+    }
+
+    fn create_type_with_name<F: FnMut(&mut Registry) -> MetaType>(&mut self, name: &str, mut f: F) {
+        if !self.types.contains_key(name) {
             // Inserting a fake type before calling the function allows recursive types to exist.
             self.types.insert(
-                name.clone().into_owned(),
+                name.to_string(),
                 MetaType::Object {
                     name: "".to_string(),
                     description: None,
@@ -278,12 +320,16 @@ impl Registry {
                     cache_control: Default::default(),
                     extends: false,
                     keys: None,
+                    resolvable: true,
+                    shareable: false,
+                    inaccessible: false,
+                    interface_object: false,
+                    tags: Default::default(),
                 },
             );
             let ty = f(self);
-            *self.types.get_mut(&*name).unwrap() = ty;
+            *self.types.get_mut(name).unwrap() = ty;
         }
-        T::qualified_type_name()
     }
 
     pub fn add_directive(&mut self, directive: MetaDirective) {
@@ -348,6 +394,7 @@ impl Registry {
                 MetaType::Object {
                     name,
                     keys: Some(keys),
+                    resolvable: true,
                     ..
                 } if !keys.is_empty() => Some(name.clone()),
                 MetaType::Interface {
@@ -365,10 +412,86 @@ impl Registry {
                 name: "_Entity".to_string(),
                 description: None,
                 possible_types,
+                inaccessible: false,
+                tags: Default::default(),
             },
         );
     }
 
+    /// Add the Relay `node(id:)` and `nodes(ids:)` fields of the `Node` interface to the query root.
+    ///
+    /// Panics if the query root already has one of these fields.
+    pub(crate) fn create_node_fields(&mut self, node_type: &str) {
+        let id_arg = |name: &'static str, ty: &str| {
+            let mut args = IndexMap::new();
+            args.insert(
+                name,
+                MetaInputValue {
+                    name,
+                    description: None,
+                    ty: ty.to_string(),
+                    default_value: None,
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                },
+            );
+            args
+        };
+
+        let query_root = self.types.get_mut(&self.query_type).unwrap();
+        if let MetaType::Object { fields, .. } = query_root {
+            for name in &["node", "nodes"] {
+                if fields.contains_key(*name) {
+                    panic!(
+                        "The query root \"{}\" already has a \"{}\" field, it can not have the Relay node fields.",
+                        self.query_type, name
+                    );
+                }
+            }
+
+            fields.insert(
+                "node".to_string(),
+                MetaField {
+                    name: "node".to_string(),
+                    description: Some("Fetches an object given its ID."),
+                    args: id_arg("id", "ID!"),
+                    ty: node_type.to_string(),
+                    deprecation: None,
+                    cache_control: Default::default(),
+                    external: false,
+                    requires: None,
+                    provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
+                },
+            );
+
+            fields.insert(
+                "nodes".to_string(),
+                MetaField {
+                    name: "nodes".to_string(),
+                    description: Some("Lookup nodes by a list of IDs."),
+                    args: id_arg("ids", "[ID!]!"),
+                    ty: format!("[{}]!", node_type),
+                    deprecation: None,
+                    cache_control: Default::default(),
+                    external: false,
+                    requires: None,
+                    provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
+                },
+            );
+        }
+    }
+
     pub(crate) fn create_federation_types(&mut self) {
         Any::create_type_info(self);
 
@@ -391,6 +514,10 @@ impl Registry {
                             external: false,
                             requires: None,
                             provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
                         },
                     );
                     fields
@@ -398,6 +525,11 @@ impl Registry {
                 cache_control: Default::default(),
                 extends: false,
                 keys: None,
+                resolvable: true,
+                shareable: false,
+                inaccessible: false,
+                interface_object: false,
+                tags: Default::default(),
             },
         );
 
@@ -417,6 +549,10 @@ impl Registry {
                     external: false,
                     requires: None,
                     provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
                 },
             );
 
@@ -435,6 +571,10 @@ impl Registry {
                                 ty: "[_Any!]!".to_string(),
                                 default_value: None,
                                 validator: None,
+                                deprecation: None,
+                                is_secret: false,
+                                inaccessible: false,
+                                tags: Default::default(),
                             },
                         );
                         args
@@ -445,6 +585,10 @@ impl Registry {
                     external: false,
                     requires: None,
                     provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
                 },
             );
         }
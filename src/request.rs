@@ -1,4 +1,4 @@
-use crate::parser::types::UploadValue;
+use crate::parser::types::{UploadContent, UploadValue};
 use crate::{Data, ParseRequestError, Value, Variables};
 use serde::{Deserialize, Deserializer};
 use std::any::Any;
@@ -85,6 +85,24 @@ impl Request {
         filename: String,
         content_type: Option<String>,
         content: File,
+    ) {
+        self.set_upload_content(
+            var_path,
+            filename,
+            content_type,
+            UploadContent::File(content),
+        );
+    }
+
+    /// Set a variable to an upload value with its content, which can be a stream.
+    ///
+    /// This is the same as [`set_upload`](#method.set_upload).
+    pub fn set_upload_content(
+        &mut self,
+        var_path: &str,
+        filename: String,
+        content_type: Option<String>,
+        content: UploadContent,
     ) {
         let variable = match self.variables.variable_path(var_path) {
             Some(variable) => variable,
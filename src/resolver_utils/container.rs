@@ -1,10 +1,16 @@
-use crate::extensions::{ErrorLogger, Extension, ExtensionContext, ResolveInfo};
+use crate::extensions::{resolve_with_middlewares, ExtensionContext, ResolveInfo};
+use crate::incremental::{
+    path, resolve_incremental, send, Incremental, IncrementalData, IncrementalDirective,
+    InitialValue, Resolving,
+};
+use crate::live::record_dependencies;
 use crate::parser::types::Selection;
 use crate::registry::MetaType;
 use crate::{Context, ContextSelectionSet, Error, OutputValueType, QueryError, Result, Value};
-use futures::TryFutureExt;
+use futures::future::{BoxFuture, FutureExt};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 
 /// A GraphQL container.
 ///
@@ -42,6 +48,19 @@ pub trait ContainerType: OutputValueType {
     async fn find_entity(&self, ctx: &Context<'_>, _params: &Value) -> Result<serde_json::Value> {
         Err(QueryError::EntityNotFound.into_error(ctx.item.pos))
     }
+
+    /// Find the GraphQL entities for representations that all have the same `__typename`.
+    ///
+    /// By default this calls `find_entity` for each representation concurrently. Objects override
+    /// this in case they are the query root and have batch entity lookup functions.
+    async fn find_entities(
+        &self,
+        ctx: &Context<'_>,
+        params: &[Value],
+    ) -> Result<Vec<serde_json::Value>> {
+        futures::future::try_join_all(params.iter().map(|params| self.find_entity(ctx, params)))
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -58,11 +77,32 @@ pub async fn resolve_container<'a, T: ContainerType + Send + Sync>(
     ctx: &ContextSelectionSet<'a>,
     root: &'a T,
 ) -> Result<serde_json::Value> {
-    let mut fields = Fields(Vec::new());
+    let mut fields = Fields::default();
     fields.add_set(ctx, root)?;
-    let futures = fields.0;
 
-    let res = futures::future::try_join_all(futures).await?;
+    if !ctx.incremental {
+        let futures = fields.futures.into_iter().map(|(future, _)| future);
+        return Ok(merge_fields(futures::future::try_join_all(futures).await?));
+    }
+    resolve_incremental(
+        ctx,
+        fields.futures,
+        |name, value| (name, value),
+        if fields.deferred.is_empty() {
+            None
+        } else {
+            Some(
+                futures::future::join_all(fields.deferred)
+                    .map(|_| ())
+                    .boxed(),
+            )
+        },
+        merge_fields,
+    )
+    .await
+}
+
+fn merge_fields(res: Vec<(String, serde_json::Value)>) -> serde_json::Value {
     let mut map = serde_json::Map::new();
     for (name, value) in res {
         if let serde_json::Value::Object(b) = value {
@@ -75,7 +115,7 @@ pub async fn resolve_container<'a, T: ContainerType + Send + Sync>(
             map.insert(name, value);
         }
     }
-    Ok(map.into())
+    map.into()
 }
 
 /// Resolve an container by executing each of the fields serially.
@@ -83,12 +123,11 @@ pub async fn resolve_container_serial<'a, T: ContainerType + Send + Sync>(
     ctx: &ContextSelectionSet<'a>,
     root: &'a T,
 ) -> Result<serde_json::Value> {
-    let mut fields = Fields(Vec::new());
+    let mut fields = Fields::default();
     fields.add_set(ctx, root)?;
-    let futures = fields.0;
 
     let mut map = serde_json::Map::new();
-    for field in futures {
+    for (field, _) in fields.futures {
         let (name, value) = field.await?;
 
         if let serde_json::Value::Object(b) = value {
@@ -108,7 +147,12 @@ type BoxFieldFuture<'a> =
     Pin<Box<dyn Future<Output = Result<(String, serde_json::Value)>> + 'a + Send>>;
 
 /// A set of fields on an container that are being selected.
-pub struct Fields<'a>(Vec<BoxFieldFuture<'a>>);
+#[derive(Default)]
+pub struct Fields<'a> {
+    futures: Vec<Resolving<'a, String, (String, serde_json::Value)>>,
+    /// The deferred fragments, they send their results.
+    deferred: Vec<BoxFuture<'a, ()>>,
+}
 
 impl<'a> Fields<'a> {
     /// Add another set of fields to this set of fields using the given container.
@@ -136,9 +180,10 @@ impl<'a> Fields<'a> {
                             .into_string();
                         let typename = root.introspection_type_name().into_owned();
 
-                        self.0.push(Box::pin(async move {
+                        let future: BoxFieldFuture<'a> = Box::pin(async move {
                             Ok((field_name, serde_json::Value::String(typename)))
-                        }));
+                        });
+                        self.futures.push((future, None));
                         continue;
                     }
 
@@ -152,11 +197,23 @@ impl<'a> Fields<'a> {
                         }
                     }
 
-                    self.0.push(Box::pin({
+                    // The value of a field is used before its deferred fragments and streamed items
+                    // are resolved.
+                    let initial_value = if ctx.incremental {
+                        let field_name = field.node.response_key().node.clone().into_string();
+                        Some((field_name, Arc::new(InitialValue::default())))
+                    } else {
+                        None
+                    };
+                    let future: BoxFieldFuture<'a> = Box::pin({
                         // TODO: investigate removing this
                         let ctx = ctx.clone();
+                        let initial_value = initial_value
+                            .as_ref()
+                            .map(|(_, initial_value)| initial_value.clone());
                         async move {
-                            let ctx_field = ctx.with_field(field);
+                            let mut ctx_field = ctx.with_field(field);
+                            ctx_field.initial_value = initial_value;
                             let field_name = ctx_field
                                 .item
                                 .node
@@ -166,6 +223,7 @@ impl<'a> Fields<'a> {
                                 .into_string();
                             let ctx_extension = ExtensionContext {
                                 schema_data: &ctx.schema_env.data,
+                                registry: &ctx.schema_env.registry,
                                 query_data: &ctx.query_env.ctx_data,
                             };
 
@@ -173,6 +231,7 @@ impl<'a> Fields<'a> {
                                 resolve_id: ctx_field.resolve_id,
                                 path_node: ctx_field.path_node.as_ref().unwrap(),
                                 parent_type: &T::type_name(),
+                                name: field.node.name.node.as_str(),
                                 return_type: match ctx_field
                                     .schema_env
                                     .registry
@@ -200,26 +259,26 @@ impl<'a> Fields<'a> {
                                 },
                             };
 
-                            ctx_field
-                                .query_env
-                                .extensions
-                                .lock()
-                                .resolve_start(&ctx_extension, &resolve_info);
-
-                            let res = root
-                                .resolve_field(&ctx_field)
-                                .map_ok(move |value| (field_name, value))
-                                .await
-                                .log_error(&ctx_extension, &ctx_field.query_env.extensions)?;
+                            let value = resolve_with_middlewares(
+                                ctx_field.query_env,
+                                &ctx_extension,
+                                &resolve_info,
+                                root.resolve_field(&ctx_field),
+                            )
+                            .await?;
 
-                            ctx_field
-                                .query_env
-                                .extensions
-                                .lock()
-                                .resolve_end(&ctx_extension, &resolve_info);
-                            Ok(res)
+                            if let Some(dependencies) = &ctx.query_env.live_dependencies {
+                                record_dependencies(
+                                    dependencies,
+                                    &root.introspection_type_name(),
+                                    field.node.name.node.as_str(),
+                                    &value,
+                                );
+                            }
+                            Ok((field_name, value))
                         }
-                    }));
+                    });
+                    self.futures.push((future, initial_value));
                 }
                 selection => {
                     let (type_condition, selection_set) = match selection {
@@ -252,6 +311,18 @@ impl<'a> Fields<'a> {
                     let type_condition =
                         type_condition.map(|condition| condition.node.on.node.as_str());
 
+                    let defer = IncrementalDirective::find(ctx, selection.directives(), "defer")
+                        .map_err(|err| *err)?;
+                    let mut ctx_fragment = ctx.with_selection_set(selection_set);
+                    let mut deferred = Fields::default();
+                    let fields = if defer.is_some() {
+                        // The directives do not apply within a deferred fragment.
+                        ctx_fragment.incremental = false;
+                        &mut deferred
+                    } else {
+                        &mut *self
+                    };
+
                     let introspection_type_name = root.introspection_type_name();
 
                     let applies_concrete_object = type_condition.map_or(false, |condition| {
@@ -278,14 +349,48 @@ impl<'a> Fields<'a> {
                         //         # able to query InterfaceA fields.
                         //     }
                         // }
-                        root.collect_all_fields(&ctx.with_selection_set(selection_set), self)?;
+                        root.collect_all_fields(&ctx_fragment, fields)?;
                     } else if type_condition.map_or(true, |condition| T::type_name() == condition) {
                         // The fragment applies to an interface type.
-                        self.add_set(&ctx.with_selection_set(selection_set), root)?;
+                        fields.add_set(&ctx_fragment, root)?;
+                    }
+
+                    match defer {
+                        // Nothing is sent if the type condition does not apply.
+                        Some(defer) if !deferred.futures.is_empty() => {
+                            self.deferred
+                                .push(deferred.resolve_deferred(ctx, defer.label));
+                        }
+                        _ => {}
                     }
                 }
             }
         }
         Ok(())
     }
+
+    /// Resolve the fields of a deferred fragment on the object of the context and send them.
+    fn resolve_deferred(
+        self,
+        ctx: &ContextSelectionSet<'a>,
+        label: Option<String>,
+    ) -> BoxFuture<'a, ()> {
+        let ctx = ctx.clone();
+        let futures = self.futures.into_iter().map(|(future, _)| future);
+        Box::pin(async move {
+            let (data, error) = match futures::future::try_join_all(futures).await {
+                Ok(res) => (merge_fields(res), None),
+                Err(err) => (serde_json::Value::Null, Some(err)),
+            };
+            send(
+                &ctx,
+                Incremental {
+                    data: IncrementalData::Data(data),
+                    path: path(&ctx),
+                    label,
+                    error,
+                },
+            );
+        })
+    }
 }
@@ -1,6 +1,14 @@
-use crate::extensions::{ErrorLogger, Extension, ExtensionContext, ResolveInfo};
+use crate::context::QueryPathSegment;
+use crate::extensions::{resolve_with_middlewares, ExtensionContext, ResolveInfo};
+use crate::incremental::{
+    path, resolve_incremental, send, Incremental, IncrementalData, IncrementalDirective,
+    InitialValue,
+};
 use crate::parser::types::Field;
 use crate::{ContextSelectionSet, OutputValueType, Positioned, Result, Type};
+use futures::future::FutureExt;
+use futures::stream::{FuturesOrdered, StreamExt};
+use std::sync::Arc;
 
 /// Resolve an list by executing each of the items concurrently.
 pub async fn resolve_list<'a, T: OutputValueType + Send + Sync + 'a>(
@@ -8,41 +16,106 @@ pub async fn resolve_list<'a, T: OutputValueType + Send + Sync + 'a>(
     field: &Positioned<Field>,
     iter: impl IntoIterator<Item = T>,
 ) -> Result<serde_json::Value> {
-    let mut futures = Vec::new();
-
-    for (idx, item) in iter.into_iter().enumerate() {
-        let ctx_idx = ctx.with_index(idx);
-        futures.push(async move {
-            let resolve_info = ResolveInfo {
-                resolve_id: ctx_idx.resolve_id,
-                path_node: ctx_idx.path_node.as_ref().unwrap(),
-                parent_type: &Vec::<T>::type_name(),
-                return_type: &T::qualified_type_name(),
-            };
-            let ctx_extension = ExtensionContext {
-                schema_data: &ctx.schema_env.data,
-                query_data: &ctx.query_env.ctx_data,
-            };
+    let resolve_item = |idx: usize, item: T, incremental: bool, initial_value| async move {
+        let mut ctx_idx = ctx.with_index(idx);
+        ctx_idx.incremental = incremental;
+        ctx_idx.initial_value = initial_value;
+        let resolve_info = ResolveInfo {
+            resolve_id: ctx_idx.resolve_id,
+            path_node: ctx_idx.path_node.as_ref().unwrap(),
+            parent_type: &Vec::<T>::type_name(),
+            name: field.node.name.node.as_str(),
+            return_type: &T::qualified_type_name(),
+        };
+        let ctx_extension = ExtensionContext {
+            schema_data: &ctx.schema_env.data,
+            registry: &ctx.schema_env.registry,
+            query_data: &ctx.query_env.ctx_data,
+        };
 
-            ctx_idx
-                .query_env
-                .extensions
-                .lock()
-                .resolve_start(&ctx_extension, &resolve_info);
+        resolve_with_middlewares(
+            ctx_idx.query_env,
+            &ctx_extension,
+            &resolve_info,
+            OutputValueType::resolve(&item, &ctx_idx, field),
+        )
+        .await
+    };
 
-            let res = OutputValueType::resolve(&item, &ctx_idx, field)
-                .await
-                .log_error(&ctx_extension, &ctx_idx.query_env.extensions)?;
+    if !ctx.incremental {
+        let futures = iter
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| resolve_item(idx, item, false, None));
+        return Ok(futures::future::try_join_all(futures).await?.into());
+    }
 
-            ctx_idx
-                .query_env
-                .extensions
-                .lock()
-                .resolve_end(&ctx_extension, &resolve_info);
+    let stream = match ctx.path_node.as_ref().map(|node| &node.segment) {
+        // The items of a nested list are never streamed.
+        Some(QueryPathSegment::Index(_)) => None,
+        _ => {
+            IncrementalDirective::find(ctx, &field.node.directives, "stream").map_err(|err| *err)?
+        }
+    };
+    let initial_count = stream
+        .as_ref()
+        .map_or(usize::MAX, |stream| stream.initial_count);
 
-            Result::Ok(res)
-        });
+    let mut futures = Vec::new();
+    let remaining = {
+        // The iterator is not kept across the await points, it may not be `Send`.
+        let mut items = iter.into_iter().enumerate();
+        for (idx, item) in items.by_ref().take(initial_count) {
+            // The value of an item is used before its deferred fragments are resolved.
+            let initial_value = Arc::new(InitialValue::default());
+            futures.push((
+                resolve_item(idx, item, true, Some(initial_value.clone())).boxed(),
+                Some(((), initial_value)),
+            ));
+        }
+        items.collect::<Vec<_>>()
+    };
+    if remaining.is_empty() {
+        return resolve_incremental(ctx, futures, |(), value| value, None, Into::into).await;
     }
 
-    Ok(futures::future::try_join_all(futures).await?.into())
+    // The remaining items are resolved concurrently and sent in order, one by one. The directives
+    // do not apply within them.
+    let label = stream.and_then(|stream| stream.label);
+    let mut remaining = remaining
+        .into_iter()
+        .map(|(idx, item)| resolve_item(idx, item, false, None).map(move |res| (idx, res)))
+        .collect::<FuturesOrdered<_>>();
+    let streamed = async move {
+        while let Some((idx, res)) = remaining.next().await {
+            let mut path = path(ctx);
+            path.push(idx.into());
+            let (items, error) = match res {
+                Ok(item) => (vec![item], None),
+                Err(err) => (Vec::new(), Some(err)),
+            };
+            let failed = error.is_some();
+            send(
+                ctx,
+                Incremental {
+                    data: IncrementalData::Items(items),
+                    path,
+                    label: label.clone(),
+                    error,
+                },
+            );
+            if failed {
+                break;
+            }
+        }
+    };
+
+    resolve_incremental(
+        ctx,
+        futures,
+        |(), value| value,
+        Some(streamed.boxed()),
+        Into::into,
+    )
+    .await
 }
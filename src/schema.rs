@@ -1,24 +1,34 @@
 use crate::context::{Data, QueryEnvInner, ResolveId};
-use crate::extensions::{ErrorLogger, Extension, ExtensionContext, ExtensionFactory, Extensions};
+use crate::extensions::{
+    resolve_middlewares, BoxMiddleware, ErrorLogger, ExtensionAdapter, ExtensionContext,
+    ExtensionFactory, Extensions, MiddlewareFactory, NextExecute, NextParse, NextRequest,
+    NextSubscribe, NextValidate,
+};
+use crate::incremental::{IncrementalResponse, InitialValue};
+use crate::live::{is_live, LiveQueries};
 use crate::model::__DirectiveLocation;
 use crate::parser::parse_query;
 use crate::parser::types::{DocumentOperations, OperationType};
 use crate::registry::{MetaDirective, MetaInputValue, Registry};
+use crate::relay::{NodeInterface, NodeRoot};
 use crate::resolver_utils::{resolve_container, resolve_container_serial, ContainerType};
 use crate::subscription::collect_subscription_streams;
 use crate::types::QueryRoot;
-use crate::validation::{check_rules, CheckResult, ValidationMode};
+use crate::validation::{check_rules, ValidationMode, ValidationResult};
 use crate::{
     BatchRequest, BatchResponse, CacheControl, ContextBase, Error, ObjectType, Pos, QueryEnv,
-    QueryError, Request, Response, Result, SubscriptionType, Type, ID,
+    QueryError, Request, Response, Result, SchemaError, SubscriptionType, Type, Variables, ID,
 };
-use futures::stream::{self, Stream, StreamExt};
+use futures::channel::mpsc;
+use futures::future::{self, Either, FutureExt};
+use futures::stream::{self, BoxStream, Stream, StreamExt};
 use indexmap::map::IndexMap;
 use itertools::Itertools;
 use std::any::Any;
 use std::ops::Deref;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::task::Poll;
 
 /// Schema builder
 pub struct SchemaBuilder<Query, Mutation, Subscription> {
@@ -31,6 +41,7 @@ pub struct SchemaBuilder<Query, Mutation, Subscription> {
     complexity: Option<usize>,
     depth: Option<usize>,
     extensions: Vec<Box<dyn ExtensionFactory>>,
+    middlewares: Vec<Box<dyn MiddlewareFactory>>,
     enable_federation: bool,
 }
 
@@ -51,6 +62,22 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
         self
     }
 
+    /// Add the Relay `node(id:)` and `nodes(ids:)` fields to the query root, they decode the global
+    /// IDs and dispatch to the fetchers of the types implementing the `Node` interface.
+    ///
+    /// The interface is defined with `#[derive(Interface)]` and the `node` attribute, see
+    /// [`relay`](relay/index.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query root already has a `node` or `nodes` field.
+    pub fn node_interface<N: NodeInterface + 'static>(mut self) -> Self {
+        let node_type = N::create_type_info(&mut self.registry);
+        self.registry.create_node_fields(&node_type);
+        self.query.node_resolver = Some(Box::new(NodeRoot::<N>::default()));
+        self
+    }
+
     /// Set the maximum complexity a query can have. By default there is no limit.
     pub fn limit_complexity(mut self, complexity: usize) -> Self {
         self.complexity = Some(complexity);
@@ -74,7 +101,7 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
     /// struct Query;
     ///
     /// let schema = Schema::build(Query, EmptyMutation,EmptySubscription)
-    ///     .extension(extensions::Logger)
+    ///     .extension(extensions::ApolloTracing)
     ///     .finish();
     /// ```
     pub fn extension(mut self, extension: impl ExtensionFactory) -> Self {
@@ -82,6 +109,15 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
         self
     }
 
+    /// Add a middleware to the schema.
+    ///
+    /// Middlewares wrap the execution phases in the order they are added, the first one is the
+    /// outermost. The extensions are run inside all of them.
+    pub fn middleware(mut self, middleware: impl MiddlewareFactory) -> Self {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
     /// Add a global data that can be accessed in the `Schema`. You access it with `Context::data`.
     pub fn data<D: Any + Send + Sync>(mut self, data: D) -> Self {
         self.data.insert(data);
@@ -100,7 +136,33 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
         self
     }
 
+    /// Enable Apollo Federation 2.
+    ///
+    /// The SDL returned by `_service { sdl }` starts with an `extend schema @link(...)` header that
+    /// imports the federation 2 directives, and the `shareable`, `inaccessible`, `tag`,
+    /// `override_from`, `interface_object` and `unresolvable` attributes are exported.
+    pub fn enable_federation_2(mut self) -> Self {
+        self.enable_federation = true;
+        self.registry.enable_federation2 = true;
+        self
+    }
+
+    /// Ask the supergraph to preserve the custom directive `@name` from the specification at `url`,
+    /// by importing it with `@link` and adding `@composeDirective`. The `name` is given without `@`.
+    ///
+    /// This enables Apollo Federation 2.
+    pub fn compose_directive(mut self, url: impl Into<String>, name: impl Into<String>) -> Self {
+        self.registry
+            .compose_directives
+            .entry(url.into())
+            .or_insert_with(Vec::new)
+            .push(name.into());
+        self.enable_federation_2()
+    }
+
     /// Build schema.
+    ///
+    /// The types are not validated, use [`Schema::check`](struct.Schema.html#method.check) for that.
     pub fn finish(mut self) -> Schema<Query, Mutation, Subscription> {
         // federation
         if self.enable_federation || self.registry.has_entities() {
@@ -115,9 +177,11 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
             complexity: self.complexity,
             depth: self.depth,
             extensions: self.extensions,
+            middlewares: self.middlewares,
             env: SchemaEnv(Arc::new(SchemaEnvInner {
                 registry: self.registry,
                 data: self.data,
+                live_queries: Default::default(),
             })),
         }))
     }
@@ -127,6 +191,7 @@ impl<Query: ContainerType, Mutation: ContainerType, Subscription: SubscriptionTy
 pub struct SchemaEnvInner {
     pub registry: Registry,
     pub data: Data,
+    pub(crate) live_queries: LiveQueries,
 }
 
 #[doc(hidden)]
@@ -150,6 +215,7 @@ pub struct SchemaInner<Query, Mutation, Subscription> {
     pub(crate) complexity: Option<usize>,
     pub(crate) depth: Option<usize>,
     pub(crate) extensions: Vec<Box<dyn ExtensionFactory>>,
+    pub(crate) middlewares: Vec<Box<dyn MiddlewareFactory>>,
     pub(crate) env: SchemaEnv,
 }
 
@@ -208,6 +274,7 @@ where
             query: QueryRoot {
                 inner: query,
                 disable_introspection: false,
+                node_resolver: None,
             },
             mutation,
             subscription,
@@ -216,6 +283,7 @@ where
             complexity: None,
             depth: None,
             extensions: Default::default(),
+            middlewares: Default::default(),
             enable_federation: false,
         }
     }
@@ -236,6 +304,8 @@ where
             } else {
                 Some(Subscription::type_name().to_string())
             },
+            enable_federation2: false,
+            compose_directives: Default::default(),
         };
 
         registry.add_directive(MetaDirective {
@@ -254,6 +324,10 @@ where
                     ty: "Boolean!".to_string(),
                     default_value: None,
                     validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
                 });
                 args
             }
@@ -275,6 +349,10 @@ where
                     ty: "Boolean!".to_string(),
                     default_value: None,
                     validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
                 });
                 args
             }
@@ -287,6 +365,141 @@ where
             args: Default::default(),
         });
 
+        registry.add_directive(MetaDirective {
+            name: "deprecated",
+            description: Some("Marks an element of a GraphQL schema as no longer supported."),
+            locations: vec![
+                __DirectiveLocation::FIELD_DEFINITION,
+                __DirectiveLocation::ARGUMENT_DEFINITION,
+                __DirectiveLocation::INPUT_FIELD_DEFINITION,
+                __DirectiveLocation::ENUM_VALUE
+            ],
+            args: {
+                let mut args = IndexMap::new();
+                args.insert("reason", MetaInputValue {
+                    name: "reason",
+                    description: Some("Explains why this element was deprecated, usually also including a suggestion for how to access supported similar data."),
+                    ty: "String".to_string(),
+                    default_value: Some("\"No longer supported\"".to_string()),
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args
+            }
+        });
+
+        registry.add_directive(MetaDirective {
+            name: "specifiedBy",
+            description: Some("Exposes a URL that specifies the behaviour of this scalar."),
+            locations: vec![__DirectiveLocation::SCALAR],
+            args: {
+                let mut args = IndexMap::new();
+                args.insert(
+                    "url",
+                    MetaInputValue {
+                        name: "url",
+                        description: Some("The URL that specifies the behaviour of this scalar."),
+                        ty: "String!".to_string(),
+                        default_value: None,
+                        validator: None,
+                        deprecation: None,
+                        is_secret: false,
+                        inaccessible: false,
+                        tags: Default::default(),
+                    },
+                );
+                args
+            },
+        });
+
+        registry.add_directive(MetaDirective {
+            name: "defer",
+            description: Some("Directs the executor to deliver this fragment in a subsequent payload of `Schema::execute_incremental`."),
+            locations: vec![
+                __DirectiveLocation::FRAGMENT_SPREAD,
+                __DirectiveLocation::INLINE_FRAGMENT
+            ],
+            args: {
+                let mut args = IndexMap::new();
+                args.insert("label", MetaInputValue {
+                    name: "label",
+                    description: Some("Identifies the payload of this fragment."),
+                    ty: "String".to_string(),
+                    default_value: None,
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args.insert("if", MetaInputValue {
+                    name: "if",
+                    description: Some("Deferred when true."),
+                    ty: "Boolean!".to_string(),
+                    default_value: Some("true".to_string()),
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args
+            }
+        });
+
+        registry.add_directive(MetaDirective {
+            name: "stream",
+            description: Some("Directs the executor to deliver the items of this list field after the first `initialCount` one by one in the subsequent payloads of `Schema::execute_incremental`."),
+            locations: vec![__DirectiveLocation::FIELD],
+            args: {
+                let mut args = IndexMap::new();
+                args.insert("label", MetaInputValue {
+                    name: "label",
+                    description: Some("Identifies the payload of this list."),
+                    ty: "String".to_string(),
+                    default_value: None,
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args.insert("initialCount", MetaInputValue {
+                    name: "initialCount",
+                    description: Some("The number of items in the initial payload."),
+                    ty: "Int!".to_string(),
+                    default_value: Some("0".to_string()),
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args.insert("if", MetaInputValue {
+                    name: "if",
+                    description: Some("Streamed when true."),
+                    ty: "Boolean!".to_string(),
+                    default_value: Some("true".to_string()),
+                    validator: None,
+                    deprecation: None,
+                    is_secret: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                });
+                args
+            }
+        });
+
+        registry.add_directive(MetaDirective {
+            name: "live",
+            description: Some("Directs `Schema::execute_stream` to execute this query again when the objects it resolved are invalidated."),
+            locations: vec![__DirectiveLocation::QUERY],
+            args: Default::default(),
+        });
+
         // register scalars
         bool::create_type_info(&mut registry);
         i32::create_type_info(&mut registry);
@@ -319,23 +532,54 @@ where
         Self::create_registry().export_sdl(false)
     }
 
-    // TODO: Remove the allow
-    #[allow(clippy::type_complexity)]
-    async fn prepare_request(&self, request: Request) -> Result<(QueryEnvInner, CacheControl)> {
-        // create extension instances
-        let extensions = spin::Mutex::new(Extensions(
+    /// Validate the types of this schema.
+    ///
+    /// Returns all errors found, such as objects that do not provide the fields of the interfaces
+    /// they implement or union members that are not objects. A schema with errors can still be used,
+    /// but queries touching the broken types may fail.
+    pub fn check(&self) -> std::result::Result<(), Vec<SchemaError>> {
+        let errors = self.env.registry.check();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Create the extension instances and the middleware chain of a request, the extensions are
+    /// run by an adapter that is the innermost middleware.
+    fn create_middlewares(&self) -> (Arc<Extensions>, Arc<[BoxMiddleware]>) {
+        let extensions = Arc::new(Extensions::new(
             self.0
                 .extensions
                 .iter()
                 .map(|factory| factory.create())
                 .collect_vec(),
         ));
+        let mut middlewares = self
+            .0
+            .middlewares
+            .iter()
+            .map(|factory| factory.create())
+            .collect_vec();
+        if !self.0.extensions.is_empty() {
+            middlewares.push(Box::new(ExtensionAdapter(extensions.clone())));
+        }
+        (extensions, middlewares.into())
+    }
 
-        let request = extensions
-            .lock()
+    // TODO: Remove the allow
+    #[allow(clippy::type_complexity)]
+    async fn prepare_request(
+        &self,
+        request: Request,
+        (extensions, middlewares): &(Arc<Extensions>, Arc<[BoxMiddleware]>),
+    ) -> Result<(QueryEnvInner, CacheControl)> {
+        let mut request = extensions
             .prepare_request(
                 &ExtensionContext {
                     schema_data: &self.env.data,
+                    registry: &self.env.registry,
                     query_data: &Default::default(),
                 },
                 request,
@@ -344,44 +588,63 @@ where
 
         let ctx_extension = ExtensionContext {
             schema_data: &self.env.data,
+            registry: &self.env.registry,
             query_data: &request.data,
         };
 
+        // The phases return the public `Error`, boxing it would break the API.
+        #[allow(clippy::result_large_err)]
+        let parse_fn = |_: &ExtensionContext<'_>, query: &str, _: &Variables| {
+            parse_query(query).map_err(Into::<Error>::into)
+        };
+        let mut document = NextParse {
+            chain: middlewares,
+            parse_fn: &parse_fn,
+        }
+        .run(&ctx_extension, &request.query, &request.variables)
+        .await?;
+
         extensions
-            .lock()
-            .parse_start(&ctx_extension, &request.query, &request.variables);
-        let document = parse_query(&request.query)
-            .map_err(Into::<Error>::into)
-            .log_error(&ctx_extension, &extensions)?;
-        extensions.lock().parse_end(&ctx_extension, &document);
+            .prepare_document(
+                &ctx_extension,
+                &mut document,
+                request.operation_name.as_deref(),
+                &mut request.variables,
+            )
+            .log_error(&ctx_extension, extensions)?;
 
         // check rules
-        extensions.lock().validation_start(&ctx_extension);
-        let CheckResult {
+        let validate_fut = async {
+            check_rules(
+                &self.env.registry,
+                &document,
+                Some(&request.variables),
+                self.validation_mode,
+            )
+        };
+        let ValidationResult {
             cache_control,
             complexity,
             depth,
-        } = check_rules(
-            &self.env.registry,
-            &document,
-            Some(&request.variables),
-            self.validation_mode,
-        )
-        .log_error(&ctx_extension, &extensions)?;
-        extensions.lock().validation_end(&ctx_extension);
+        } = NextValidate {
+            chain: middlewares,
+            validate_fut: validate_fut.boxed(),
+        }
+        .run(&ctx_extension)
+        .await?;
 
         // check limit
         if let Some(limit_complexity) = self.complexity {
             if complexity > limit_complexity {
                 return Err(QueryError::TooComplex.into_error(Pos::default()))
-                    .log_error(&ctx_extension, &extensions);
+                    .log_error(&ctx_extension, extensions);
             }
         }
 
         if let Some(limit_depth) = self.depth {
             if depth > limit_depth {
                 return Err(QueryError::TooDeep.into_error(Pos::default()))
-                    .log_error(&ctx_extension, &extensions);
+                    .log_error(&ctx_extension, extensions);
             }
         }
 
@@ -408,22 +671,36 @@ where
             Ok(operation) => operation,
             Err(e) => {
                 let err = e.into_error(Pos::default());
-                extensions.lock().error(&ctx_extension, &err);
+                extensions.error(&ctx_extension, &err);
                 return Err(err);
             }
         };
 
         let env = QueryEnvInner {
-            extensions,
+            extensions: extensions.clone(),
+            middlewares: middlewares.clone(),
+            resolve_middlewares: resolve_middlewares(middlewares),
             variables: request.variables,
             operation,
             fragments: document.fragments,
             ctx_data: Arc::new(request.data),
+            live_dependencies: None,
+            incremental: None,
         };
         Ok((env, cache_control))
     }
 
     async fn execute_once(&self, env: QueryEnv) -> Response {
+        self.execute_operation(&env, None).await
+    }
+
+    /// Execute the operation, `@defer` and `@stream` apply if there is an initial value to receive
+    /// the data of the initial payload.
+    async fn execute_operation(
+        &self,
+        env: &QueryEnv,
+        initial_value: Option<Arc<InitialValue>>,
+    ) -> Response {
         // execute
         let inc_resolve_id = AtomicUsize::default();
         let ctx = ContextBase {
@@ -433,37 +710,46 @@ where
             item: &env.operation.node.selection_set,
             schema_env: &self.env,
             query_env: &env,
+            incremental: initial_value.is_some(),
+            initial_value,
         };
         let ctx_extension = ExtensionContext {
             schema_data: &self.env.data,
+            registry: &self.env.registry,
             query_data: &env.ctx_data,
         };
 
-        env.extensions.lock().execution_start(&ctx_extension);
-
-        let data = match &env.operation.node.ty {
-            OperationType::Query => resolve_container(&ctx, &self.query).await,
-            OperationType::Mutation => resolve_container_serial(&ctx, &self.mutation).await,
-            OperationType::Subscription => {
-                return Error::Query {
-                    pos: Pos::default(),
-                    path: None,
-                    err: QueryError::NotSupported,
+        let execute_fut = async {
+            let data = match &env.operation.node.ty {
+                OperationType::Query => resolve_container(&ctx, &self.query).await,
+                OperationType::Mutation => resolve_container_serial(&ctx, &self.mutation).await,
+                OperationType::Subscription => {
+                    return Error::Query {
+                        pos: Pos::default(),
+                        path: None,
+                        err: QueryError::NotSupported,
+                    }
+                    .into()
                 }
-                .into()
-            }
-        };
+            };
 
-        env.extensions.lock().execution_end(&ctx_extension);
-        let extensions = env.extensions.lock().result(&ctx_extension);
+            Response::from_result(data)
+        };
 
-        Response::from_result(data).extensions(extensions)
+        NextExecute {
+            chain: &env.middlewares,
+            execute_fut: execute_fut.boxed(),
+        }
+        .run(&ctx_extension)
+        .await
     }
 
-    /// Execute an GraphQL query.
-    pub async fn execute(&self, request: impl Into<Request>) -> Response {
-        let request = request.into();
-        match self.prepare_request(request).await {
+    async fn execute_request(
+        &self,
+        request: Request,
+        middlewares: &(Arc<Extensions>, Arc<[BoxMiddleware]>),
+    ) -> Response {
+        match self.prepare_request(request, middlewares).await {
             Ok((env, cache_control)) => self
                 .execute_once(QueryEnv::new(env))
                 .await
@@ -472,6 +758,25 @@ where
         }
     }
 
+    /// Execute an GraphQL query.
+    pub async fn execute(&self, request: impl Into<Request>) -> Response {
+        let request = request.into();
+        let middlewares = self.create_middlewares();
+        NextRequest {
+            chain: &middlewares.1,
+            request_fn: Box::new(|request| self.execute_request(request, &middlewares).boxed()),
+        }
+        .run(
+            &ExtensionContext {
+                schema_data: &self.env.data,
+                registry: &self.env.registry,
+                query_data: &Default::default(),
+            },
+            request,
+        )
+        .await
+    }
+
     /// Execute an GraphQL batch query.
     pub async fn execute_batch(&self, batch_request: BatchRequest) -> BatchResponse {
         match batch_request {
@@ -485,70 +790,283 @@ where
         }
     }
 
-    pub(crate) fn execute_stream_with_ctx_data(
+    /// Execute an GraphQL query with incremental delivery.
+    ///
+    /// The first payload contains the response without the fragments marked with `@defer` and
+    /// with only the first `initialCount` items of the list fields marked with `@stream`, the
+    /// following payloads contain the deferred fragments and the remaining items, one by one, as
+    /// they are resolved.
+    ///
+    /// The operation is executed once, the deferred fragments are resolved on the objects of the
+    /// initial payload and the remaining items with the list. The first payload is sent as soon as
+    /// its data is resolved, the extensions of the response are then sent with the last payload.
+    /// The resolve phase of a field ends when the deferred fragments and the streamed items below
+    /// it are resolved.
+    /// Fragments and lists marked within a deferred fragment or a streamed item are delivered with
+    /// it. The directives are ignored for mutations and subscriptions, which have a single
+    /// payload, and by [`execute`](#method.execute).
+    pub fn execute_incremental(
         &self,
-        request: impl Into<Request> + Send,
-        ctx_data: Arc<Data>,
-    ) -> impl Stream<Item = Response> + Send {
+        request: impl Into<Request>,
+    ) -> impl Stream<Item = IncrementalResponse> + Send {
         let schema = self.clone();
+        let request = request.into();
 
         async_stream::stream! {
-            let request = request.into();
-            let (mut env, cache_control) = match schema.prepare_request(request).await {
+            let middlewares = schema.create_middlewares();
+            let (mut env, cache_control) = match schema.prepare_request(request, &middlewares).await {
                 Ok(res) => res,
                 Err(err) => {
-                    yield Response::from(err);
+                    yield IncrementalResponse::complete(Response::from(err));
                     return;
                 }
             };
-            env.ctx_data = ctx_data;
-            let env = QueryEnv::new(env);
 
-            if env.operation.node.ty != OperationType::Subscription {
-                yield schema
-                    .execute_once(env)
+            if env.operation.node.ty != OperationType::Query {
+                let response = schema
+                    .execute_once(QueryEnv::new(env))
                     .await
                     .cache_control(cache_control);
+                yield IncrementalResponse::complete(response);
                 return;
             }
 
-            let resolve_id = AtomicUsize::default();
-            let ctx = env.create_context(
-                &schema.env,
-                None,
-                &env.operation.node.selection_set,
-                ResolveId::root(),
-                &resolve_id,
-            );
-            let ctx_extension = ExtensionContext {
-                schema_data: &schema.env.data,
-                query_data: &env.ctx_data,
+            let (sender, mut receiver) = mpsc::unbounded();
+            env.incremental = Some(sender);
+            let env = QueryEnv::new(env);
+            let initial_value = Arc::new(InitialValue::default());
+            let mut execution = schema
+                .execute_operation(&env, Some(initial_value.clone()))
+                .boxed();
+
+            // Wait for the data of the root or the end of the execution.
+            let initial = future::poll_fn(|cx| match execution.as_mut().poll(cx) {
+                Poll::Ready(response) => Poll::Ready(Either::Left(response)),
+                Poll::Pending => match initial_value.take() {
+                    Some(data) => Poll::Ready(Either::Right(data)),
+                    None => Poll::Pending,
+                },
+            })
+            .await;
+
+            let (response, initial_sent) = match initial {
+                Either::Left(response) => (response.cache_control(cache_control), false),
+                Either::Right(data) => {
+                    yield IncrementalResponse {
+                        initial: Some(Response::new(data).cache_control(cache_control)),
+                        incremental: Vec::new(),
+                        has_next: true,
+                        extensions: None,
+                    };
+                    let response = loop {
+                        match future::select(receiver.next(), execution).await {
+                            Either::Left((Some(result), fut)) => {
+                                yield IncrementalResponse {
+                                    initial: None,
+                                    incremental: vec![result],
+                                    has_next: true,
+                                    extensions: None,
+                                };
+                                execution = fut;
+                            }
+                            // The sender is owned by the environment.
+                            Either::Left((None, fut)) => break fut.await,
+                            Either::Right((response, _)) => break response,
+                        }
+                    };
+                    (response, true)
+                }
             };
 
-            env.extensions.lock().execution_start(&ctx_extension);
+            // The execution has ended, the remaining results are in the channel.
+            let mut results = Vec::new();
+            while let Some(Some(result)) = receiver.next().now_or_never() {
+                results.push(result);
+            }
+
+            let mut extensions = if initial_sent {
+                response.extensions
+            } else if response.is_err() || results.is_empty() {
+                yield IncrementalResponse::complete(response);
+                return;
+            } else {
+                yield IncrementalResponse {
+                    initial: Some(response),
+                    incremental: Vec::new(),
+                    has_next: true,
+                    extensions: None,
+                };
+                None
+            };
 
-            let mut streams = Vec::new();
-            if let Err(e) = collect_subscription_streams(&ctx, &schema.subscription, &mut streams) {
-                env.extensions.lock().execution_end(&ctx_extension);
-                yield Response::from(e);
+            if results.is_empty() {
+                yield IncrementalResponse {
+                    initial: None,
+                    incremental: Vec::new(),
+                    has_next: false,
+                    extensions,
+                };
                 return;
             }
+            let count = results.len();
+            for (idx, result) in results.into_iter().enumerate() {
+                let has_next = idx + 1 < count;
+                yield IncrementalResponse {
+                    initial: None,
+                    incremental: vec![result],
+                    has_next,
+                    extensions: if has_next { None } else { extensions.take() },
+                };
+            }
+        }
+    }
+
+    /// Executes again the live queries that resolved an object of the type, for the key `Type`, or
+    /// the object with the id, for the key `Type:id`.
+    ///
+    /// The id of an object is known if its `id` field is queried.
+    pub fn invalidate_live_queries(&self, key: &str) {
+        self.env.live_queries.invalidate(key);
+    }
+
+    pub(crate) fn execute_stream_with_ctx_data(
+        &self,
+        request: impl Into<Request> + Send,
+        ctx_data: Arc<Data>,
+    ) -> impl Stream<Item = Response> + Send {
+        let schema = self.clone();
+        let request = request.into();
+        let middlewares = self.create_middlewares();
+
+        let stream: BoxStream<'static, Response> = {
+            let middlewares = middlewares.clone();
+            let ctx_data = ctx_data.clone();
+            async_stream::stream! {
+                let (mut env, cache_control) = match schema.prepare_request(request, &middlewares).await {
+                    Ok(res) => res,
+                    Err(err) => {
+                        yield Response::from(err);
+                        return;
+                    }
+                };
+                env.ctx_data = ctx_data;
+
+                if env.operation.node.ty == OperationType::Query && is_live(&env.operation) {
+                    env.live_dependencies = Some(Default::default());
+                    let mut env = QueryEnv::new(env);
+                    let mut listener = schema.env.live_queries.listen();
+                    let mut last = None;
+                    loop {
+                        listener.begin();
+                        let resp = schema.execute_once(env.clone()).await;
+                        let dependencies = env
+                            .live_dependencies
+                            .as_ref()
+                            .map(|dependencies| std::mem::take(&mut *dependencies.lock().unwrap()))
+                            .unwrap_or_default();
+                        listener.end(dependencies);
 
-            env.extensions.lock().execution_end(&ctx_extension);
+                        // Only the changed results are sent.
+                        let result = (resp.data.clone(), serde_json::to_value(&resp.error).ok());
+                        if last.as_ref() != Some(&result) {
+                            last = Some(result);
+                            yield resp.cache_control(cache_control);
+                        }
+                        if listener.next().await.is_none() {
+                            return;
+                        }
 
-            let mut stream = stream::select_all(streams);
-            while let Some(data) = stream.next().await {
-                let is_err = data.is_err();
-                let extensions = env.extensions.lock().result(&ctx_extension);
-                yield Response::from_result(data).extensions(extensions);
-                if is_err {
-                    break;
+                        // The extensions keep the state of an execution, such as the resolves of
+                        // Apollo tracing, so each execution gets new instances.
+                        let (extensions, middlewares) = schema.create_middlewares();
+                        env = QueryEnv::new(QueryEnvInner {
+                            resolve_middlewares: resolve_middlewares(&middlewares),
+                            extensions,
+                            middlewares,
+                            variables: env.variables.clone(),
+                            operation: env.operation.clone(),
+                            fragments: env.fragments.clone(),
+                            ctx_data: env.ctx_data.clone(),
+                            live_dependencies: Some(Default::default()),
+                            incremental: None,
+                        });
+                    }
+                }
+
+                let env = QueryEnv::new(env);
+                if env.operation.node.ty != OperationType::Subscription {
+                    yield schema
+                        .execute_once(env)
+                        .await
+                        .cache_control(cache_control);
+                    return;
+                }
+
+                let resolve_id = AtomicUsize::default();
+                let ctx = env.create_context(
+                    &schema.env,
+                    None,
+                    &env.operation.node.selection_set,
+                    ResolveId::root(),
+                    &resolve_id,
+                );
+                let ctx_extension = ExtensionContext {
+                    schema_data: &schema.env.data,
+                    registry: &schema.env.registry,
+                    query_data: &env.ctx_data,
+                };
+
+                env.extensions.execution_start(&ctx_extension);
+
+                let mut streams = Vec::new();
+                if let Err(e) = collect_subscription_streams(&ctx, &schema.subscription, &mut streams) {
+                    env.extensions.execution_end(&ctx_extension);
+                    yield Response::from(e);
+                    return;
+                }
+
+                env.extensions.execution_end(&ctx_extension);
+                env.extensions.subscription_start(&ctx_extension);
+                let _end_guard = SubscriptionEndGuard {
+                    schema_env: schema.env.clone(),
+                    env: env.clone(),
+                };
+
+                let mut stream = stream::select_all(streams);
+                while let Some(data) = stream.next().await {
+                    let is_err = data.is_err();
+                    match &data {
+                        Ok(data) => env.extensions.subscription_event(&ctx_extension, data),
+                        Err(err) => env.extensions.error(&ctx_extension, err),
+                    }
+                    let extensions = env.extensions.result(&ctx_extension);
+                    yield Response::from_result(data).extensions(extensions);
+                    if is_err {
+                        break;
+                    }
                 }
             }
         }
+        .boxed();
+
+        NextSubscribe {
+            chain: &middlewares.1,
+        }
+        .run(
+            &ExtensionContext {
+                schema_data: &self.env.data,
+                registry: &self.env.registry,
+                query_data: &ctx_data,
+            },
+            stream,
+        )
     }
 
     /// Execute an GraphQL subscription.
+    ///
+    /// A query with the `@live` directive is executed again every time an object it resolved is
+    /// invalidated with [`invalidate_live_queries`](#method.invalidate_live_queries), a response is
+    /// sent when its result changes.
     pub fn execute_stream(
         &self,
         request: impl Into<Request>,
@@ -558,3 +1076,19 @@ where
         self.execute_stream_with_ctx_data(request, Arc::new(ctx_data))
     }
 }
+
+/// Calls `Extension::subscription_end` when the subscription stream ends or is dropped.
+struct SubscriptionEndGuard {
+    schema_env: SchemaEnv,
+    env: QueryEnv,
+}
+
+impl Drop for SubscriptionEndGuard {
+    fn drop(&mut self) {
+        self.env.extensions.subscription_end(&ExtensionContext {
+            schema_data: &self.schema_env.data,
+            registry: &self.schema_env.registry,
+            query_data: &self.env.ctx_data,
+        });
+    }
+}
@@ -1,28 +1,49 @@
 use crate::connection::edge::Edge;
 use crate::connection::page_info::PageInfo;
 use crate::parser::types::Field;
-use crate::resolver_utils::{resolve_container, ContainerType};
-use crate::types::connection::{CursorType, EmptyFields};
+use crate::resolver_utils::{resolve_container, resolve_list, ContainerType};
+use crate::types::connection::{
+    ConnectionNameType, ContextCursorType, DefaultConnectionName, DefaultEdgeName, EdgeNameType,
+    EmptyFields,
+};
 use crate::{
-    registry, Context, ContextSelectionSet, FieldResult, ObjectType, OutputValueType, Positioned,
-    Result, Type,
+    registry, Context, ContextSelectionSet, Error, FieldError, FieldResult, ObjectType,
+    OutputValueType, Positioned, QueryError, Result, Type,
 };
-use futures::{Stream, StreamExt, TryStreamExt};
+use futures::future::BoxFuture;
+use futures::{Future, FutureExt, Stream, StreamExt, TryStreamExt};
 use indexmap::map::IndexMap;
 use std::borrow::Cow;
+use std::marker::PhantomData;
+
+type TotalCountFn = Box<dyn Fn() -> BoxFuture<'static, FieldResult<usize>> + Send + Sync>;
 
 /// Connection type
 ///
 /// Connection is the result of a query for `connection::query`.
-pub struct Connection<C, T, EC = EmptyFields, EE = EmptyFields> {
+///
+/// The type names of the connection and its edges are `{NodeType}Connection` and `{NodeType}Edge`
+/// by default, they can be changed with the `Name` and `EdgeName` parameters.
+pub struct Connection<
+    C,
+    T,
+    EC = EmptyFields,
+    EE = EmptyFields,
+    Name = DefaultConnectionName,
+    EdgeName = DefaultEdgeName,
+> {
     /// All edges of the current page.
-    edges: Vec<Edge<C, T, EE>>,
+    edges: Vec<Edge<C, T, EE, EdgeName>>,
     additional_fields: EC,
     has_previous_page: bool,
     has_next_page: bool,
+    start_cursor: Option<C>,
+    end_cursor: Option<C>,
+    total_count: Option<TotalCountFn>,
+    _mark: PhantomData<Name>,
 }
 
-impl<C, T, EE> Connection<C, T, EmptyFields, EE> {
+impl<C, T, EE, Name, EdgeName> Connection<C, T, EmptyFields, EE, Name, EdgeName> {
     /// Create a new connection.
     pub fn new(has_previous_page: bool, has_next_page: bool) -> Self {
         Connection {
@@ -30,11 +51,15 @@ impl<C, T, EE> Connection<C, T, EmptyFields, EE> {
             has_previous_page,
             has_next_page,
             edges: Vec::new(),
+            start_cursor: None,
+            end_cursor: None,
+            total_count: None,
+            _mark: PhantomData,
         }
     }
 }
 
-impl<C, T, EC, EE> Connection<C, T, EC, EE> {
+impl<C, T, EC, EE, Name, EdgeName> Connection<C, T, EC, EE, Name, EdgeName> {
     /// Create a new connection, it can have some additional fields.
     pub fn with_additional_fields(
         has_previous_page: bool,
@@ -46,15 +71,19 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
             has_previous_page,
             has_next_page,
             edges: Vec::new(),
+            start_cursor: None,
+            end_cursor: None,
+            total_count: None,
+            _mark: PhantomData,
         }
     }
 }
 
-impl<C, T, EC, EE> Connection<C, T, EC, EE> {
+impl<C, T, EC, EE, Name, EdgeName> Connection<C, T, EC, EE, Name, EdgeName> {
     /// Convert the edge type and return a new `Connection`.
-    pub fn map<T2, EE2, F>(self, mut f: F) -> Connection<C, T2, EC, EE2>
+    pub fn map<T2, EE2, F>(self, mut f: F) -> Connection<C, T2, EC, EE2, Name, EdgeName>
     where
-        F: FnMut(Edge<C, T, EE>) -> Edge<C, T2, EE2>,
+        F: FnMut(Edge<C, T, EE, EdgeName>) -> Edge<C, T2, EE2, EdgeName>,
     {
         let mut new_edges = Vec::with_capacity(self.edges.len());
         for edge in self.edges {
@@ -65,11 +94,15 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
             additional_fields: self.additional_fields,
             has_previous_page: self.has_previous_page,
             has_next_page: self.has_next_page,
+            start_cursor: self.start_cursor,
+            end_cursor: self.end_cursor,
+            total_count: self.total_count,
+            _mark: PhantomData,
         }
     }
 
     /// Convert the node type and return a new `Connection`.
-    pub fn map_node<T2, F>(self, mut f: F) -> Connection<C, T2, EC, EE>
+    pub fn map_node<T2, F>(self, mut f: F) -> Connection<C, T2, EC, EE, Name, EdgeName>
     where
         F: FnMut(T) -> T2,
     {
@@ -77,13 +110,51 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
             cursor: edge.cursor,
             node: f(edge.node),
             additional_fields: edge.additional_fields,
+            _mark: PhantomData,
         })
     }
 
+    /// The edges of the page.
+    pub fn edges(&self) -> &[Edge<C, T, EE, EdgeName>] {
+        &self.edges
+    }
+
+    /// Returns `true` if there are items before the page.
+    pub fn has_previous_page(&self) -> bool {
+        self.has_previous_page
+    }
+
+    /// Returns `true` if there are items after the page.
+    pub fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
+
+    /// Set the `startCursor` of the page info, by default it is the cursor of the first edge.
+    pub fn set_start_cursor(&mut self, cursor: C) {
+        self.start_cursor = Some(cursor);
+    }
+
+    /// Set the `endCursor` of the page info, by default it is the cursor of the last edge.
+    pub fn set_end_cursor(&mut self, cursor: C) {
+        self.end_cursor = Some(cursor);
+    }
+
+    /// Set the function that counts the items of the connection, it is only called when the
+    /// `totalCount` field is queried.
+    ///
+    /// If it is not set, `totalCount` is resolved by the additional fields or is `null`.
+    pub fn set_total_count<F, R>(&mut self, f: F)
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: Future<Output = FieldResult<usize>> + Send + 'static,
+    {
+        self.total_count = Some(Box::new(move || f().boxed()));
+    }
+
     /// Append edges with `IntoIterator<Item = Edge<C, T, EE>>`
     pub fn append<I>(&mut self, iter: I)
     where
-        I: IntoIterator<Item = Edge<C, T, EE>>,
+        I: IntoIterator<Item = Edge<C, T, EE, EdgeName>>,
     {
         self.edges.extend(iter);
     }
@@ -91,7 +162,7 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
     /// Append edges with `IntoIterator<Item = Edge<C, T, EE>>`
     pub fn try_append<I>(&mut self, iter: I) -> FieldResult<()>
     where
-        I: IntoIterator<Item = FieldResult<Edge<C, T, EE>>>,
+        I: IntoIterator<Item = FieldResult<Edge<C, T, EE, EdgeName>>>,
     {
         for edge in iter {
             self.edges.push(edge?);
@@ -102,7 +173,7 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
     /// Append edges with `Stream<Item = FieldResult<Edge<C, T, EE>>>`
     pub async fn append_stream<S>(&mut self, stream: S)
     where
-        S: Stream<Item = Edge<C, T, EE>> + Unpin,
+        S: Stream<Item = Edge<C, T, EE, EdgeName>> + Unpin,
     {
         self.edges.extend(stream.collect::<Vec<_>>().await);
     }
@@ -110,22 +181,24 @@ impl<C, T, EC, EE> Connection<C, T, EC, EE> {
     /// Append edges with `Stream<Item = FieldResult<Edge<C, T, EE>>>`
     pub async fn try_append_stream<S>(&mut self, stream: S) -> FieldResult<()>
     where
-        S: Stream<Item = FieldResult<Edge<C, T, EE>>> + Unpin,
+        S: Stream<Item = FieldResult<Edge<C, T, EE, EdgeName>>> + Unpin,
     {
         self.edges.extend(stream.try_collect::<Vec<_>>().await?);
         Ok(())
     }
 }
 
-impl<C, T, EC, EE> Type for Connection<C, T, EC, EE>
+impl<C, T, EC, EE, Name, EdgeName> Type for Connection<C, T, EC, EE, Name, EdgeName>
 where
-    C: CursorType,
+    C: ContextCursorType,
     T: OutputValueType + Send + Sync,
     EC: ObjectType + Sync + Send,
     EE: ObjectType + Sync + Send,
+    Name: ConnectionNameType,
+    EdgeName: EdgeNameType,
 {
     fn type_name() -> Cow<'static, str> {
-        Cow::Owned(format!("{}Connection", T::type_name()))
+        Cow::Owned(Name::type_name::<T>())
     }
 
     fn create_type_info(registry: &mut registry::Registry) -> String {
@@ -157,6 +230,10 @@ where
                             external: false,
                             requires: None,
                             provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
                         },
                     );
 
@@ -166,7 +243,7 @@ where
                             name: "edges".to_string(),
                             description: Some("A list of edges."),
                             args: Default::default(),
-                            ty: <Option<Vec<Option<Edge<C, T, EE>>>> as Type>::create_type_info(
+                            ty: <Option<Vec<Option<Edge<C, T, EE, EdgeName>>>> as Type>::create_type_info(
                                 registry,
                             ),
                             deprecation: None,
@@ -174,6 +251,48 @@ where
                             external: false,
                             requires: None,
                             provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
+                        },
+                    );
+
+                    fields.insert(
+                        "nodes".to_string(),
+                        registry::MetaField {
+                            name: "nodes".to_string(),
+                            description: Some("A list of nodes."),
+                            args: Default::default(),
+                            ty: <Vec<T> as Type>::create_type_info(registry),
+                            deprecation: None,
+                            cache_control: Default::default(),
+                            external: false,
+                            requires: None,
+                            provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
+                        },
+                    );
+
+                    fields.insert(
+                        "totalCount".to_string(),
+                        registry::MetaField {
+                            name: "totalCount".to_string(),
+                            description: Some("The total number of items."),
+                            args: Default::default(),
+                            ty: <Option<i32> as Type>::create_type_info(registry),
+                            deprecation: None,
+                            cache_control: Default::default(),
+                            external: false,
+                            requires: None,
+                            provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
                         },
                     );
 
@@ -183,45 +302,87 @@ where
                 cache_control: Default::default(),
                 extends: false,
                 keys: None,
+                resolvable: true,
+                shareable: false,
+                inaccessible: false,
+                interface_object: false,
+                tags: Default::default(),
             }
         })
     }
 }
 
 #[async_trait::async_trait]
-impl<C, T, EC, EE> ContainerType for Connection<C, T, EC, EE>
+impl<C, T, EC, EE, Name, EdgeName> ContainerType for Connection<C, T, EC, EE, Name, EdgeName>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     EC: ObjectType + Sync + Send,
     EE: ObjectType + Sync + Send,
+    Name: ConnectionNameType,
+    EdgeName: EdgeNameType,
 {
     async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
         if ctx.item.node.name.node == "pageInfo" {
+            let encode = |cursor: &C| {
+                cursor.encode_cursor_with_ctx(ctx).map_err(|err| {
+                    FieldError::from(err).into_error_with_path(ctx.item.pos, ctx.path_node.as_ref())
+                })
+            };
             let page_info = PageInfo {
                 has_previous_page: self.has_previous_page,
                 has_next_page: self.has_next_page,
-                start_cursor: self.edges.first().map(|edge| edge.cursor.encode_cursor()),
-                end_cursor: self.edges.last().map(|edge| edge.cursor.encode_cursor()),
+                start_cursor: self
+                    .start_cursor
+                    .as_ref()
+                    .or_else(|| self.edges.first().map(|edge| &edge.cursor))
+                    .map(encode)
+                    .transpose()?,
+                end_cursor: self
+                    .end_cursor
+                    .as_ref()
+                    .or_else(|| self.edges.last().map(|edge| &edge.cursor))
+                    .map(encode)
+                    .transpose()?,
             };
             let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
             return OutputValueType::resolve(&page_info, &ctx_obj, ctx.item).await;
         } else if ctx.item.node.name.node == "edges" {
             let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
             return OutputValueType::resolve(&self.edges, &ctx_obj, ctx.item).await;
+        } else if ctx.item.node.name.node == "nodes" {
+            let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
+            return resolve_list(&ctx_obj, ctx.item, self.edges.iter().map(|edge| &edge.node))
+                .await;
+        } else if ctx.item.node.name.node == "totalCount" {
+            if let Some(total_count) = &self.total_count {
+                let total_count = total_count().await.map_err(|err| {
+                    err.into_error_with_path(ctx.item.pos, ctx.path_node.as_ref())
+                })?;
+                return Ok(total_count.into());
+            }
         }
 
-        self.additional_fields.resolve_field(ctx).await
+        match self.additional_fields.resolve_field(ctx).await {
+            // `totalCount` is null if it is neither set nor one of the additional fields.
+            Err(Error::Query {
+                err: QueryError::FieldNotFound { .. },
+                ..
+            }) if ctx.item.node.name.node == "totalCount" => Ok(serde_json::Value::Null),
+            res => res,
+        }
     }
 }
 
 #[async_trait::async_trait]
-impl<C, T, EC, EE> OutputValueType for Connection<C, T, EC, EE>
+impl<C, T, EC, EE, Name, EdgeName> OutputValueType for Connection<C, T, EC, EE, Name, EdgeName>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     EC: ObjectType + Sync + Send,
     EE: ObjectType + Sync + Send,
+    Name: ConnectionNameType,
+    EdgeName: EdgeNameType,
 {
     async fn resolve(
         &self,
@@ -232,11 +393,13 @@ where
     }
 }
 
-impl<C, T, EC, EE> ObjectType for Connection<C, T, EC, EE>
+impl<C, T, EC, EE, Name, EdgeName> ObjectType for Connection<C, T, EC, EE, Name, EdgeName>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     EC: ObjectType + Sync + Send,
     EE: ObjectType + Sync + Send,
+    Name: ConnectionNameType,
+    EdgeName: EdgeNameType,
 {
 }
@@ -1,7 +1,10 @@
-use crate::ID;
+use crate::{Context, ID};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::convert::Infallible;
 use std::fmt::Display;
 use std::num::ParseIntError;
+use std::ops::{Deref, DerefMut};
 
 /// Cursor type
 ///
@@ -18,6 +21,248 @@ pub trait CursorType: Sized {
     fn encode_cursor(&self) -> String;
 }
 
+/// Cursor type that is decoded and encoded with the context of the connection field
+///
+/// It is implemented for every `CursorType`, and by the cursors that need the schema data, such
+/// as `SignedCursor`. `Connection` and `Edge` output their cursors with it, and
+/// [`query_with_ctx`](fn.query_with_ctx.html) decodes them with it.
+pub trait ContextCursorType: Sized {
+    /// Error type for `decode_cursor_with_ctx` and `encode_cursor_with_ctx`.
+    type Error: Display;
+
+    /// Decode cursor from string, with the context of the connection field.
+    fn decode_cursor_with_ctx(ctx: &Context<'_>, s: &str) -> Result<Self, Self::Error>;
+
+    /// Encode cursor to string, with the context of the connection field.
+    fn encode_cursor_with_ctx(&self, ctx: &Context<'_>) -> Result<String, Self::Error>;
+}
+
+impl<T: CursorType> ContextCursorType for T {
+    type Error = T::Error;
+
+    fn decode_cursor_with_ctx(_ctx: &Context<'_>, s: &str) -> Result<Self, Self::Error> {
+        T::decode_cursor(s)
+    }
+
+    fn encode_cursor_with_ctx(&self, _ctx: &Context<'_>) -> Result<String, Self::Error> {
+        Ok(self.encode_cursor())
+    }
+}
+
+/// Cursor error
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CursorError {
+    /// The cursor is not base64 encoded.
+    #[error("The cursor is not correctly encoded")]
+    InvalidEncoding,
+
+    /// The value of the cursor can not be deserialized.
+    #[error("The cursor value is invalid: {0}")]
+    InvalidValue(String),
+
+    /// A composite cursor has a wrong number of components.
+    #[error("The cursor has {actual} components, expected {expected}")]
+    InvalidLength {
+        /// The expected number of components.
+        expected: usize,
+        /// The number of components of the cursor.
+        actual: usize,
+    },
+
+    /// A component of a composite cursor is invalid.
+    #[error("The cursor component {index} is invalid: {message}")]
+    InvalidComponent {
+        /// The index of the component.
+        index: usize,
+        /// The error message of the component.
+        message: String,
+    },
+
+    /// The signature of a `SignedCursor` does not match.
+    #[cfg(feature = "signed_cursor")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "signed_cursor")))]
+    #[error("The cursor signature is invalid")]
+    InvalidSignature,
+
+    /// The `CursorKey` of a `SignedCursor` is not in the schema data.
+    #[cfg(feature = "signed_cursor")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "signed_cursor")))]
+    #[error("The cursor key is not in the schema data")]
+    MissingKey,
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
+
+fn decode_base64(s: &str) -> Result<Vec<u8>, CursorError> {
+    base64::decode_config(s, base64::URL_SAFE_NO_PAD).map_err(|_| CursorError::InvalidEncoding)
+}
+
+fn decode_json<T: DeserializeOwned>(data: &[u8]) -> Result<T, CursorError> {
+    serde_json::from_slice(data).map_err(|err| CursorError::InvalidValue(err.to_string()))
+}
+
+/// A cursor that hides its value, encoded as base64 JSON.
+///
+/// It does not expose the offsets or keys to the clients, but they can still decode and forge it,
+/// use [`SignedCursor`](struct.SignedCursor.html) to detect tampering.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::connection::{CursorType, OpaqueCursor};
+///
+/// let cursor = OpaqueCursor((10, "a".to_string()));
+/// let s = cursor.encode_cursor();
+/// assert_eq!(OpaqueCursor::<(i32, String)>::decode_cursor(&s).unwrap(), cursor);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OpaqueCursor<T>(pub T);
+
+impl<T> Deref for OpaqueCursor<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for OpaqueCursor<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> CursorType for OpaqueCursor<T> {
+    type Error = CursorError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        Ok(OpaqueCursor(decode_json(&decode_base64(s)?)?))
+    }
+
+    fn encode_cursor(&self) -> String {
+        encode_base64(&serde_json::to_vec(&self.0).unwrap_or_default())
+    }
+}
+
+/// The HMAC key of the [`SignedCursor`](struct.SignedCursor.html)s, add it to the schema data.
+#[cfg(feature = "signed_cursor")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "signed_cursor")))]
+#[derive(Clone)]
+pub struct CursorKey(Vec<u8>);
+
+#[cfg(feature = "signed_cursor")]
+impl CursorKey {
+    /// Create a cursor key.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self(key.into())
+    }
+
+    fn mac(&self, data: &[u8]) -> hmac::Hmac<sha2::Sha256> {
+        use hmac::{Mac, NewMac};
+
+        let mut mac = hmac::Hmac::<sha2::Sha256>::new_varkey(&self.0)
+            .expect("HMAC can take a key of any size");
+        mac.update(data);
+        mac
+    }
+}
+
+/// A base64 JSON cursor signed with HMAC-SHA256, it can not be modified by the clients.
+///
+/// The key is the [`CursorKey`](struct.CursorKey.html) in the schema data, so the cursor is only a
+/// `ContextCursorType` and must be decoded with [`query_with_ctx`](fn.query_with_ctx.html). The
+/// connection field returns `CursorError::MissingKey` if the key is not in the schema data.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::connection::*;
+///
+/// struct QueryRoot;
+///
+/// #[Object]
+/// impl QueryRoot {
+///     async fn numbers(&self, ctx: &Context<'_>, after: Option<String>, first: Option<i32>) -> FieldResult<Connection<SignedCursor<usize>, i32>> {
+///         query_with_ctx(ctx, after, None, first, None, |after, _before, first, _last| async move {
+///             let start = after.map(|after: SignedCursor<usize>| *after + 1).unwrap_or(0);
+///             let end = start + first.unwrap_or(10);
+///             let mut connection = Connection::new(start > 0, true);
+///             connection.append((start..end).map(|n| Edge::new(SignedCursor(n), n as i32)));
+///             Ok(connection)
+///         }).await
+///     }
+/// }
+///
+/// let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+///     .data(CursorKey::new("secret"))
+///     .finish();
+/// ```
+#[cfg(feature = "signed_cursor")]
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "signed_cursor")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SignedCursor<T>(pub T);
+
+#[cfg(feature = "signed_cursor")]
+impl<T> Deref for SignedCursor<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "signed_cursor")]
+impl<T> DerefMut for SignedCursor<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "signed_cursor")]
+impl<T: Serialize + DeserializeOwned> SignedCursor<T> {
+    /// Decode a cursor signed with the key.
+    pub fn decode_with_key(key: &CursorKey, s: &str) -> Result<Self, CursorError> {
+        use hmac::Mac;
+
+        let mut parts = s.splitn(2, '.');
+        let (data, signature) = match (parts.next(), parts.next()) {
+            (Some(data), Some(signature)) => (decode_base64(data)?, decode_base64(signature)?),
+            _ => return Err(CursorError::InvalidEncoding),
+        };
+        key.mac(&data)
+            .verify(&signature)
+            .map_err(|_| CursorError::InvalidSignature)?;
+        Ok(SignedCursor(decode_json(&data)?))
+    }
+
+    /// Encode and sign the cursor with the key.
+    pub fn encode_with_key(&self, key: &CursorKey) -> String {
+        use hmac::Mac;
+
+        let data = serde_json::to_vec(&self.0).unwrap_or_default();
+        let signature = key.mac(&data).finalize().into_bytes();
+        format!("{}.{}", encode_base64(&data), encode_base64(&signature))
+    }
+}
+
+#[cfg(feature = "signed_cursor")]
+impl<T: Serialize + DeserializeOwned> ContextCursorType for SignedCursor<T> {
+    type Error = CursorError;
+
+    fn decode_cursor_with_ctx(ctx: &Context<'_>, s: &str) -> Result<Self, Self::Error> {
+        let key = ctx.data_opt::<CursorKey>().ok_or(CursorError::MissingKey)?;
+        Self::decode_with_key(key, s)
+    }
+
+    fn encode_cursor_with_ctx(&self, ctx: &Context<'_>) -> Result<String, Self::Error> {
+        let key = ctx.data_opt::<CursorKey>().ok_or(CursorError::MissingKey)?;
+        Ok(self.encode_with_key(key))
+    }
+}
+
 impl CursorType for usize {
     type Error = ParseIntError;
 
@@ -53,3 +298,42 @@ impl CursorType for ID {
         self.to_string()
     }
 }
+
+macro_rules! impl_composite_cursor {
+    ($len:expr, $(($name:ident, $idx:tt)),*) => {
+        /// A composite keyset cursor, for connections ordered by several columns.
+        ///
+        /// The components are encoded with their own `CursorType`, the cursor is the base64 JSON
+        /// array of them.
+        impl<$($name: CursorType),*> CursorType for ($($name,)*) {
+            type Error = CursorError;
+
+            fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+                let components: Vec<String> = decode_json(&decode_base64(s)?)?;
+                if components.len() != $len {
+                    return Err(CursorError::InvalidLength {
+                        expected: $len,
+                        actual: components.len(),
+                    });
+                }
+                Ok(($(
+                    $name::decode_cursor(&components[$idx]).map_err(|err| {
+                        CursorError::InvalidComponent {
+                            index: $idx,
+                            message: err.to_string(),
+                        }
+                    })?,
+                )*))
+            }
+
+            fn encode_cursor(&self) -> String {
+                let components = vec![$(self.$idx.encode_cursor()),*];
+                encode_base64(&serde_json::to_vec(&components).unwrap_or_default())
+            }
+        }
+    };
+}
+
+impl_composite_cursor!(2, (A, 0), (B, 1));
+impl_composite_cursor!(3, (A, 0), (B, 1), (C, 2));
+impl_composite_cursor!(4, (A, 0), (B, 1), (C, 2), (D, 3));
@@ -1,50 +1,68 @@
-use crate::connection::EmptyFields;
+use crate::connection::{DefaultEdgeName, EdgeNameType, EmptyFields};
 use crate::parser::types::Field;
 use crate::resolver_utils::{resolve_container, ContainerType};
-use crate::types::connection::CursorType;
+use crate::types::connection::ContextCursorType;
 use crate::{
-    registry, Context, ContextSelectionSet, ObjectType, OutputValueType, Positioned, Result, Type,
+    registry, Context, ContextSelectionSet, FieldError, ObjectType, OutputValueType, Positioned,
+    Result, Type,
 };
 use indexmap::map::IndexMap;
 use std::borrow::Cow;
+use std::marker::PhantomData;
 
 /// The edge type output by the data source
-pub struct Edge<C, T, E> {
+pub struct Edge<C, T, E, Name = DefaultEdgeName> {
     pub(crate) cursor: C,
     pub(crate) node: T,
     pub(crate) additional_fields: E,
+    pub(crate) _mark: PhantomData<Name>,
 }
 
-impl<C, T, E> Edge<C, T, E> {
+impl<C, T, E, Name> Edge<C, T, E, Name> {
     /// Create a new edge, it can have some additional fields.
     pub fn with_additional_fields(cursor: C, node: T, additional_fields: E) -> Self {
         Self {
             cursor,
             additional_fields,
             node,
+            _mark: PhantomData,
         }
     }
 }
 
-impl<C: CursorType, T> Edge<C, T, EmptyFields> {
+impl<C, T, E, Name> Edge<C, T, E, Name> {
+    /// The cursor of the edge.
+    pub fn cursor(&self) -> &C {
+        &self.cursor
+    }
+
+    /// The node of the edge.
+    pub fn node(&self) -> &T {
+        &self.node
+    }
+}
+
+impl<C: ContextCursorType, T, Name> Edge<C, T, EmptyFields, Name> {
     /// Create a new edge.
     pub fn new(cursor: C, node: T) -> Self {
         Self {
             cursor,
             node,
             additional_fields: EmptyFields,
+            _mark: PhantomData,
         }
     }
 }
 
-impl<C, T, E> Type for Edge<C, T, E>
+impl<C, T, E, Name> Type for Edge<C, T, E, Name>
 where
-    C: CursorType,
+    C: ContextCursorType,
     T: OutputValueType + Send + Sync,
     E: ObjectType + Sync + Send,
+    Name: EdgeNameType,
 {
     fn type_name() -> Cow<'static, str> {
-        Cow::Owned(format!("{}Edge", T::type_name()))
+        Cow::Owned(Name::type_name::<T>())
     }
 
     fn create_type_info(registry: &mut registry::Registry) -> String {
@@ -76,6 +94,10 @@ where
                             external: false,
                             requires: None,
                             provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
                         },
                     );
 
@@ -91,6 +113,10 @@ where
                             external: false,
                             requires: None,
                             provides: None,
+                            shareable: false,
+                            inaccessible: false,
+                            tags: Default::default(),
+                            override_from: None,
                         },
                     );
 
@@ -100,24 +126,33 @@ where
                 cache_control: Default::default(),
                 extends: false,
                 keys: None,
+                resolvable: true,
+                shareable: false,
+                inaccessible: false,
+                interface_object: false,
+                tags: Default::default(),
             }
         })
     }
 }
 
 #[async_trait::async_trait]
-impl<C, T, E> ContainerType for Edge<C, T, E>
+impl<C, T, E, Name> ContainerType for Edge<C, T, E, Name>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     E: ObjectType + Sync + Send,
+    Name: EdgeNameType,
 {
     async fn resolve_field(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
         if ctx.item.node.name.node == "node" {
             let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
             return OutputValueType::resolve(&self.node, &ctx_obj, ctx.item).await;
         } else if ctx.item.node.name.node == "cursor" {
-            return Ok(self.cursor.encode_cursor().into());
+            let cursor = self.cursor.encode_cursor_with_ctx(ctx).map_err(|err| {
+                FieldError::from(err).into_error_with_path(ctx.item.pos, ctx.path_node.as_ref())
+            })?;
+            return Ok(cursor.into());
         }
 
         self.additional_fields.resolve_field(ctx).await
@@ -125,11 +160,12 @@ where
 }
 
 #[async_trait::async_trait]
-impl<C, T, E> OutputValueType for Edge<C, T, E>
+impl<C, T, E, Name> OutputValueType for Edge<C, T, E, Name>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     E: ObjectType + Sync + Send,
+    Name: EdgeNameType,
 {
     async fn resolve(
         &self,
@@ -140,10 +176,11 @@ where
     }
 }
 
-impl<C, T, E> ObjectType for Edge<C, T, E>
+impl<C, T, E, Name> ObjectType for Edge<C, T, E, Name>
 where
-    C: CursorType + Send + Sync,
+    C: ContextCursorType + Send + Sync,
     T: OutputValueType + Send + Sync,
     E: ObjectType + Sync + Send,
+    Name: EdgeNameType,
 {
 }
@@ -4,13 +4,17 @@ mod connection_type;
 mod cursor;
 mod edge;
 mod page_info;
+mod pagination;
 
-use crate::{FieldResult, SimpleObject};
+use crate::{Context, FieldResult, OutputValueType, SimpleObject};
 pub use connection_type::Connection;
-pub use cursor::CursorType;
+pub use cursor::{ContextCursorType, CursorError, CursorType, OpaqueCursor};
+#[cfg(feature = "signed_cursor")]
+pub use cursor::{CursorKey, SignedCursor};
 pub use edge::Edge;
 use futures::Future;
 pub use page_info::PageInfo;
+pub use pagination::Pagination;
 use std::fmt::Display;
 
 /// Empty additional fields
@@ -18,6 +22,53 @@ use std::fmt::Display;
 #[graphql(internal)]
 pub struct EmptyFields;
 
+/// Connection name type
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::connection::*;
+///
+/// struct UserList;
+///
+/// impl ConnectionNameType for UserList {
+///     fn type_name<T: OutputValueType>() -> String {
+///         "UserList".to_string()
+///     }
+/// }
+///
+/// type Users = Connection<usize, i32, EmptyFields, EmptyFields, UserList>;
+/// ```
+pub trait ConnectionNameType: Send + Sync {
+    /// Returns the type name of the connection of the node type `T`.
+    fn type_name<T: OutputValueType>() -> String;
+}
+
+/// Edge name type
+pub trait EdgeNameType: Send + Sync {
+    /// Returns the type name of the edge of the node type `T`.
+    fn type_name<T: OutputValueType>() -> String;
+}
+
+/// The default connection name, `{NodeType}Connection`.
+pub struct DefaultConnectionName;
+
+impl ConnectionNameType for DefaultConnectionName {
+    fn type_name<T: OutputValueType>() -> String {
+        format!("{}Connection", T::type_name())
+    }
+}
+
+/// The default edge name, `{NodeType}Edge`.
+pub struct DefaultEdgeName;
+
+impl EdgeNameType for DefaultEdgeName {
+    fn type_name<T: OutputValueType>() -> String {
+        format!("{}Edge", T::type_name())
+    }
+}
+
 /// Parses the parameters and executes the query.
 ///
 /// # Examples
@@ -89,18 +140,77 @@ pub struct EmptyFields;
 ///     }));
 /// }
 /// ```
-pub async fn query<Cursor, Node, ConnectionFields, EdgeFields, F, R>(
+pub async fn query<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName, F, R>(
     after: Option<String>,
     before: Option<String>,
     first: Option<i32>,
     last: Option<i32>,
     f: F,
-) -> FieldResult<Connection<Cursor, Node, ConnectionFields, EdgeFields>>
+) -> FieldResult<Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>>
 where
     Cursor: CursorType + Send + Sync,
     <Cursor as CursorType>::Error: Display + Send + Sync + 'static,
     F: FnOnce(Option<Cursor>, Option<Cursor>, Option<usize>, Option<usize>) -> R,
-    R: Future<Output = FieldResult<Connection<Cursor, Node, ConnectionFields, EdgeFields>>>,
+    R: Future<
+        Output = FieldResult<
+            Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>,
+        >,
+    >,
+{
+    parse_and_query(after, before, first, last, Cursor::decode_cursor, f).await
+}
+
+/// Parses the parameters and executes the query, the cursors are decoded with the context.
+///
+/// This is the same as [`query`](fn.query.html), it is needed by the cursors that read the schema
+/// data, such as `SignedCursor`.
+pub async fn query_with_ctx<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName, F, R>(
+    ctx: &Context<'_>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    f: F,
+) -> FieldResult<Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>>
+where
+    Cursor: ContextCursorType + Send + Sync,
+    <Cursor as ContextCursorType>::Error: Display + Send + Sync + 'static,
+    F: FnOnce(Option<Cursor>, Option<Cursor>, Option<usize>, Option<usize>) -> R,
+    R: Future<
+        Output = FieldResult<
+            Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>,
+        >,
+    >,
+{
+    parse_and_query(
+        after,
+        before,
+        first,
+        last,
+        |s| Cursor::decode_cursor_with_ctx(ctx, s),
+        f,
+    )
+    .await
+}
+
+async fn parse_and_query<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName, D, E, F, R>(
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    decode: D,
+    f: F,
+) -> FieldResult<Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>>
+where
+    Cursor: Send + Sync,
+    D: Fn(&str) -> Result<Cursor, E>,
+    E: Display,
+    F: FnOnce(Option<Cursor>, Option<Cursor>, Option<usize>, Option<usize>) -> R,
+    R: Future<
+        Output = FieldResult<
+            Connection<Cursor, Node, ConnectionFields, EdgeFields, Name, EdgeName>,
+        >,
+    >,
 {
     if first.is_some() && last.is_some() {
         return Err("The \"first\" and \"last\" parameters cannot exist at the same time".into());
@@ -123,12 +233,16 @@ where
     };
 
     let before = match before {
-        Some(before) => Some(Cursor::decode_cursor(&before)?),
+        Some(before) => Some(
+            decode(&before).map_err(|err| format!("The \"before\" cursor is invalid: {}", err))?,
+        ),
         None => None,
     };
 
     let after = match after {
-        Some(after) => Some(Cursor::decode_cursor(&after)?),
+        Some(after) => Some(
+            decode(&after).map_err(|err| format!("The \"after\" cursor is invalid: {}", err))?,
+        ),
         None => None,
     };
 
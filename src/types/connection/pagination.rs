@@ -0,0 +1,308 @@
+use crate::connection::{Connection, CursorType, Edge, EmptyFields};
+use crate::FieldResult;
+use futures::Future;
+
+/// The pagination arguments of a connection field.
+///
+/// It is used by [`Connection::from_slice`](struct.Connection.html#method.from_slice),
+/// [`Connection::from_iter`](struct.Connection.html#method.from_iter) and
+/// [`Connection::from_offset_loader`](struct.Connection.html#method.from_offset_loader), which
+/// paginate the items with offset cursors as described in the
+/// [Relay specification](https://relay.dev/graphql/connections.htm#sec-Pagination-algorithm).
+#[derive(Debug, Clone, Default)]
+pub struct Pagination {
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    max_page_size: Option<usize>,
+    total_count: Option<usize>,
+}
+
+impl Pagination {
+    /// Create the pagination arguments.
+    pub fn new(
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Self {
+        Self {
+            after,
+            before,
+            first,
+            last,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of items of a page.
+    ///
+    /// `first` and `last` can not be greater than it, and it is the default of `first` when
+    /// neither of them is specified.
+    pub fn max_page_size(self, max_page_size: usize) -> Self {
+        Self {
+            max_page_size: Some(max_page_size),
+            ..self
+        }
+    }
+
+    /// Set the total number of items.
+    ///
+    /// `from_offset_loader` needs it for `last` without `before`, and to know if there are items
+    /// after `before`.
+    pub fn total_count(self, total_count: usize) -> Self {
+        Self {
+            total_count: Some(total_count),
+            ..self
+        }
+    }
+}
+
+/// The items to load and how to build the page from them.
+struct Page {
+    offset: usize,
+    limit: usize,
+    /// Items are loaded until this, one more is loaded to know if there is a next page.
+    first: Option<usize>,
+    last: Option<usize>,
+    has_previous_page: bool,
+    has_next_page: bool,
+}
+
+fn parse_count(
+    name: &str,
+    value: Option<i32>,
+    max_page_size: Option<usize>,
+) -> FieldResult<Option<usize>> {
+    match value {
+        Some(value) if value < 0 => {
+            Err(format!("The \"{}\" parameter must be a non-negative number", name).into())
+        }
+        Some(value) => match max_page_size {
+            Some(max_page_size) if value as usize > max_page_size => Err(format!(
+                "The \"{}\" parameter must not be greater than {}",
+                name, max_page_size
+            )
+            .into()),
+            _ => Ok(Some(value as usize)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_cursor(name: &str, cursor: Option<&str>) -> FieldResult<Option<usize>> {
+    match cursor {
+        Some(cursor) => match usize::decode_cursor(cursor) {
+            Ok(offset) => Ok(Some(offset)),
+            Err(err) => Err(format!("The \"{}\" cursor is invalid: {}", name, err).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+impl Pagination {
+    /// Returns `true` if the arguments are valid but the page can only be built with the total
+    /// count, which is the case of `last` without `before`.
+    fn needs_total_count(&self) -> bool {
+        self.first.is_none()
+            && self.last.is_some()
+            && self.before.is_none()
+            && self.total_count.is_none()
+            && self.clone().total_count(0).page().is_ok()
+    }
+
+    fn page(&self) -> FieldResult<Page> {
+        let after = parse_cursor("after", self.after.as_deref())?;
+        let before = parse_cursor("before", self.before.as_deref())?;
+        let mut first = parse_count("first", self.first, self.max_page_size)?;
+        let last = parse_count("last", self.last, self.max_page_size)?;
+        if first.is_none() && last.is_none() {
+            first = self.max_page_size;
+        }
+
+        // The edges after `after` and before `before`.
+        let mut start = after.map(|after| after.saturating_add(1)).unwrap_or(0);
+        let mut end = match (before, self.total_count) {
+            (Some(before), Some(total_count)) => Some(before.min(total_count)),
+            (Some(before), None) => Some(before),
+            (None, total_count) => total_count,
+        };
+        if let Some(end) = end {
+            start = start.min(end);
+        }
+
+        let mut page = Page {
+            offset: start,
+            limit: usize::MAX,
+            first: None,
+            last,
+            has_previous_page: after.is_some(),
+            has_next_page: match (before, self.total_count) {
+                (Some(before), Some(total_count)) => before < total_count,
+                _ => false,
+            },
+        };
+
+        match (first, end) {
+            (Some(first), Some(end_offset)) => {
+                page.has_next_page = end_offset - start > first;
+                end = Some(end_offset.min(start.saturating_add(first)));
+            }
+            (Some(first), None) => {
+                page.has_next_page = false;
+                page.first = Some(first);
+                page.limit = first.saturating_add(1);
+            }
+            (None, _) => {}
+        }
+
+        match (last, end) {
+            (Some(last), Some(end)) => {
+                page.has_previous_page = end - start > last;
+                page.offset = start.max(end.saturating_sub(last));
+                page.limit = end - page.offset;
+                page.last = None;
+            }
+            (Some(_), None) if first.is_none() => {
+                return Err(
+                    "The \"last\" parameter requires the \"before\" parameter or the total count"
+                        .into(),
+                );
+            }
+            (None, Some(end)) => page.limit = end - start,
+            _ => {}
+        }
+
+        Ok(page)
+    }
+}
+
+impl Page {
+    fn connection<T, Name, EdgeName>(
+        mut self,
+        mut items: Vec<T>,
+    ) -> Connection<usize, T, EmptyFields, EmptyFields, Name, EdgeName> {
+        if let Some(first) = self.first {
+            if items.len() > first {
+                items.truncate(first);
+                self.has_next_page = true;
+            }
+        }
+        if let Some(last) = self.last {
+            self.has_previous_page = items.len() > last;
+            if items.len() > last {
+                let skip = items.len() - last;
+                items.drain(..skip);
+                self.offset += skip;
+            }
+        }
+
+        let mut connection = Connection::new(self.has_previous_page, self.has_next_page);
+        connection.append(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(idx, item)| Edge::new(self.offset + idx, item)),
+        );
+        connection
+    }
+}
+
+impl<T, Name, EdgeName> Connection<usize, T, EmptyFields, EmptyFields, Name, EdgeName> {
+    /// Create the page of the items of a slice, the cursors are the offsets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use async_graphql::*;
+    /// use async_graphql::connection::*;
+    ///
+    /// struct QueryRoot;
+    ///
+    /// #[Object]
+    /// impl QueryRoot {
+    ///     async fn numbers(&self,
+    ///         after: Option<String>,
+    ///         before: Option<String>,
+    ///         first: Option<i32>,
+    ///         last: Option<i32>
+    ///     ) -> FieldResult<Connection<usize, i32>> {
+    ///         let numbers: Vec<i32> = (0..100).collect();
+    ///         Connection::from_slice(&numbers, Pagination::new(after, before, first, last).max_page_size(10))
+    ///     }
+    /// }
+    ///
+    /// #[async_std::main]
+    /// async fn main() {
+    ///     let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    ///
+    ///     assert_eq!(schema.execute("{ numbers(last: 2) { nodes pageInfo { hasPreviousPage hasNextPage } } }").await.into_result().unwrap().data, serde_json::json!({
+    ///         "numbers": {
+    ///             "nodes": [98, 99],
+    ///             "pageInfo": { "hasPreviousPage": true, "hasNextPage": false },
+    ///         },
+    ///     }));
+    /// }
+    /// ```
+    pub fn from_slice(items: &[T], pagination: Pagination) -> FieldResult<Self>
+    where
+        T: Clone,
+    {
+        let page = pagination.total_count(items.len()).page()?;
+        let items = items
+            .iter()
+            .skip(page.offset)
+            .take(page.limit)
+            .cloned()
+            .collect();
+        Ok(page.connection(items))
+    }
+
+    /// Create the page of the items of an iterator, the cursors are the offsets.
+    ///
+    /// The items before the page are skipped. The iterator is consumed until the end of the page,
+    /// unless `last` is specified without `before` and its exact size is not known.
+    pub fn from_iter<I>(items: I, pagination: Pagination) -> FieldResult<Self>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let items = items.into_iter();
+        let pagination = match items.size_hint() {
+            (lower, Some(upper)) if lower == upper => pagination.total_count(lower),
+            _ => pagination,
+        };
+        if pagination.needs_total_count() {
+            // The end of the page is needed, count the items.
+            let items = items.collect::<Vec<_>>();
+            let page = pagination.total_count(items.len()).page()?;
+            let items = items
+                .into_iter()
+                .skip(page.offset)
+                .take(page.limit)
+                .collect();
+            return Ok(page.connection(items));
+        }
+        let page = pagination.page()?;
+        let items = items.skip(page.offset).take(page.limit).collect();
+        Ok(page.connection(items))
+    }
+
+    /// Create a page with a function that loads `limit` items from `offset`, the cursors are the
+    /// offsets.
+    ///
+    /// At most one more item than the page size is loaded to know if there is a next page, and
+    /// `limit` is `usize::MAX` if the page size is not limited. The total count must be set with
+    /// [`Pagination::total_count`](struct.Pagination.html#method.total_count) to use `last`
+    /// without `before`.
+    pub async fn from_offset_loader<F, R>(pagination: Pagination, f: F) -> FieldResult<Self>
+    where
+        F: FnOnce(usize, usize) -> R,
+        R: Future<Output = FieldResult<Vec<T>>>,
+    {
+        let page = pagination.page()?;
+        let mut items = f(page.offset, page.limit).await?;
+        items.truncate(page.limit);
+        Ok(page.connection(items))
+    }
+}
@@ -38,6 +38,11 @@ impl Type for EmptyMutation {
             cache_control: Default::default(),
             extends: false,
             keys: None,
+            resolvable: true,
+            shareable: false,
+            inaccessible: false,
+            interface_object: false,
+            tags: Default::default(),
         })
     }
 }
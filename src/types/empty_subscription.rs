@@ -22,6 +22,11 @@ impl Type for EmptySubscription {
             cache_control: Default::default(),
             extends: false,
             keys: None,
+            resolvable: true,
+            shareable: false,
+            inaccessible: false,
+            interface_object: false,
+            tags: Default::default(),
         })
     }
 }
@@ -23,6 +23,19 @@ impl<T: Type> Type for BTreeSet<T> {
 }
 
 impl<T: InputValueType + Ord> InputValueType for BTreeSet<T> {
+    fn input_type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("[{}]", T::qualified_input_type_name()))
+    }
+
+    fn qualified_input_type_name() -> String {
+        format!("[{}]!", T::qualified_input_type_name())
+    }
+
+    fn create_input_type_info(registry: &mut registry::Registry) -> String {
+        T::create_input_type_info(registry);
+        Self::qualified_input_type_name()
+    }
+
     fn parse(value: Option<Value>) -> InputValueResult<Self> {
         match value.unwrap_or_default() {
             Value::List(values) => {
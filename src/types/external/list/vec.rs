@@ -22,6 +22,19 @@ impl<T: Type> Type for Vec<T> {
 }
 
 impl<T: InputValueType> InputValueType for Vec<T> {
+    fn input_type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("[{}]", T::qualified_input_type_name()))
+    }
+
+    fn qualified_input_type_name() -> String {
+        format!("[{}]!", T::qualified_input_type_name())
+    }
+
+    fn create_input_type_info(registry: &mut registry::Registry) -> String {
+        T::create_input_type_info(registry);
+        Self::qualified_input_type_name()
+    }
+
     fn parse(value: Option<Value>) -> InputValueResult<Self> {
         match value.unwrap_or_default() {
             Value::List(values) => {
@@ -62,6 +62,11 @@ impl<A: Type, B: Type> Type for MergedObject<A, B> {
                 cache_control: cc,
                 extends: false,
                 keys: None,
+                resolvable: true,
+                shareable: false,
+                inaccessible: false,
+                interface_object: false,
+                tags: Default::default(),
             }
         })
     }
@@ -1,6 +1,7 @@
 //! Useful GraphQL types.
 
 pub mod connection;
+pub mod relay;
 
 mod any;
 mod empty_mutation;
@@ -1,9 +1,10 @@
 use crate::model::{__Schema, __Type};
 use crate::parser::types::Field;
+use crate::relay::NodeResolver;
 use crate::resolver_utils::{resolve_container, ContainerType};
 use crate::{
     registry, Any, Context, ContextSelectionSet, Error, ObjectType, OutputValueType, Positioned,
-    QueryError, Result, SimpleObject, Type,
+    QueryError, Result, SimpleObject, Type, Value,
 };
 
 use indexmap::map::IndexMap;
@@ -19,6 +20,7 @@ struct Service {
 pub(crate) struct QueryRoot<T> {
     pub(crate) inner: T,
     pub(crate) disable_introspection: bool,
+    pub(crate) node_resolver: Option<Box<dyn NodeResolver>>,
 }
 
 impl<T: Type> Type for QueryRoot<T> {
@@ -44,6 +46,10 @@ impl<T: Type> Type for QueryRoot<T> {
                     external: false,
                     requires: None,
                     provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
                 },
             );
 
@@ -62,6 +68,10 @@ impl<T: Type> Type for QueryRoot<T> {
                                 ty: "String!".to_string(),
                                 default_value: None,
                                 validator: None,
+                                deprecation: None,
+                                is_secret: false,
+                                inaccessible: false,
+                                tags: Default::default(),
                             },
                         );
                         args
@@ -72,6 +82,10 @@ impl<T: Type> Type for QueryRoot<T> {
                     external: false,
                     requires: None,
                     provides: None,
+                    shareable: false,
+                    inaccessible: false,
+                    tags: Default::default(),
+                    override_from: None,
                 },
             );
         }
@@ -121,11 +135,41 @@ impl<T: ObjectType + Send + Sync> ContainerType for QueryRoot<T> {
             .await;
         } else if ctx.item.node.name.node == "_entities" {
             let representations: Vec<Any> = ctx.param_value("representations", None)?;
-            let mut res = Vec::new();
-            for item in representations {
-                res.push(self.inner.find_entity(ctx, &item.0).await?);
+            let count = representations.len();
+
+            // Group the representations by typename, so that they can be resolved in batches.
+            let mut groups: IndexMap<String, (Vec<usize>, Vec<Value>)> = IndexMap::new();
+            for (idx, Any(params)) in representations.into_iter().enumerate() {
+                let typename = match &params {
+                    Value::Object(obj) => match obj.get("__typename") {
+                        Some(Value::String(typename)) => typename.clone(),
+                        _ => return Err(QueryError::TypeNameNotExists.into_error(ctx.item.pos)),
+                    },
+                    _ => return Err(QueryError::EntityNotFound.into_error(ctx.item.pos)),
+                };
+                let group = groups.entry(typename).or_default();
+                group.0.push(idx);
+                group.1.push(params);
+            }
+
+            let groups_res = futures::future::try_join_all(
+                groups
+                    .values()
+                    .map(|(_, params)| self.inner.find_entities(ctx, params)),
+            )
+            .await?;
+
+            let mut res = vec![serde_json::Value::Null; count];
+            for ((indices, _), values) in groups.values().zip(groups_res) {
+                for (idx, value) in indices.iter().zip(values) {
+                    res[*idx] = value;
+                }
             }
             return Ok(res.into());
+        } else if ctx.item.node.name.node == "node" || ctx.item.node.name.node == "nodes" {
+            if let Some(node_resolver) = &self.node_resolver {
+                return node_resolver.resolve(ctx).await;
+            }
         } else if ctx.item.node.name.node == "_service" {
             let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
             return OutputValueType::resolve(
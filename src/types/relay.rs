@@ -0,0 +1,194 @@
+//! Relay global object identification
+//!
+//! https://relay.dev/graphql/objectidentification.htm
+
+use crate::{Context, FieldResult, InterfaceType, OutputValueType, Result, Type, ID};
+use std::fmt::{self, Display, Formatter};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// Global ID error
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GlobalIdError {
+    /// Not a base64 encoded `Type:id` string.
+    #[error("Invalid global ID")]
+    Invalid,
+
+    /// The global ID belongs to an object of another type.
+    #[error("Expected a global ID of type \"{expected}\", found \"{actual}\"")]
+    TypeMismatch {
+        /// The expected type name.
+        expected: String,
+        /// The type name of the global ID.
+        actual: String,
+    },
+
+    /// The ID of the object can not be parsed.
+    #[error("Invalid ID \"{0}\"")]
+    InvalidId(String),
+}
+
+/// A globally unique object identifier.
+///
+/// It is encoded as base64 `Type:id`, the `Display` implementation writes the encoded form, so it
+/// can be converted to an `ID` with `into`.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_graphql::*;
+/// use async_graphql::relay::GlobalId;
+///
+/// struct User {
+///     id: i32,
+/// }
+///
+/// #[Object]
+/// impl User {
+///     async fn id(&self) -> ID {
+///         GlobalId::new::<Self>(self.id).into()
+///     }
+/// }
+///
+/// let id = GlobalId::new::<User>(1);
+/// assert_eq!(id.encode(), "VXNlcjox");
+/// assert_eq!(GlobalId::decode_as::<User, i32>("VXNlcjox"), Ok(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobalId {
+    type_name: String,
+    id: String,
+}
+
+impl GlobalId {
+    /// Create the global ID of an object of type `T`.
+    pub fn new<T: Type>(id: impl Display) -> Self {
+        Self::with_type_name(T::type_name(), id)
+    }
+
+    /// Create the global ID of an object with the type name.
+    pub fn with_type_name(type_name: impl Into<String>, id: impl Display) -> Self {
+        Self {
+            type_name: type_name.into(),
+            id: id.to_string(),
+        }
+    }
+
+    /// The type name of the object.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The ID of the object, unique within its type.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Encode as base64 `Type:id`.
+    pub fn encode(&self) -> String {
+        base64::encode(format!("{}:{}", self.type_name, self.id))
+    }
+
+    /// Decode a base64 `Type:id` string.
+    pub fn decode(s: &str) -> std::result::Result<Self, GlobalIdError> {
+        let data = base64::decode(s).map_err(|_| GlobalIdError::Invalid)?;
+        let data = String::from_utf8(data).map_err(|_| GlobalIdError::Invalid)?;
+        let mut parts = data.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(type_name), Some(id)) if !type_name.is_empty() => {
+                Ok(Self::with_type_name(type_name, id))
+            }
+            _ => Err(GlobalIdError::Invalid),
+        }
+    }
+
+    /// Decode the global ID of an object of type `T` and parse its ID.
+    pub fn decode_as<T: Type, I: FromStr>(s: &str) -> std::result::Result<I, GlobalIdError> {
+        let global_id = Self::decode(s)?;
+        if global_id.type_name != T::type_name() {
+            return Err(GlobalIdError::TypeMismatch {
+                expected: T::type_name().to_string(),
+                actual: global_id.type_name,
+            });
+        }
+        global_id
+            .id
+            .parse()
+            .map_err(|_| GlobalIdError::InvalidId(global_id.id))
+    }
+}
+
+impl Display for GlobalId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+impl FromStr for GlobalId {
+    type Err = GlobalIdError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+/// An object that implements the `Node` interface.
+///
+/// The fetcher is called by the `node` and `nodes` root fields for the global IDs of this type.
+#[async_trait::async_trait]
+pub trait NodeType: Type + Sized {
+    /// Fetch the object with the ID of its global ID, returns `None` if it does not exist.
+    async fn fetch(ctx: &Context<'_>, id: &str) -> FieldResult<Option<Self>>;
+}
+
+/// A `Node` interface, implemented by `#[derive(Interface)]` with the `node` attribute.
+///
+/// Register it with [`SchemaBuilder::node_interface`](../struct.SchemaBuilder.html#method.node_interface)
+/// to add the `node(id:)` and `nodes(ids:)` fields to the query root.
+#[async_trait::async_trait]
+pub trait NodeInterface: InterfaceType + OutputValueType + Send + Sync + Sized {
+    /// Fetch the object with the global ID by calling the fetcher of its type.
+    async fn fetch_node(ctx: &Context<'_>, id: &GlobalId) -> FieldResult<Option<Self>>;
+}
+
+async fn fetch_node<N: NodeInterface>(ctx: &Context<'_>, id: &ID) -> FieldResult<Option<N>> {
+    let id = GlobalId::decode(id)?;
+    N::fetch_node(ctx, &id).await
+}
+
+/// Resolves the `node` and `nodes` root fields.
+#[async_trait::async_trait]
+pub(crate) trait NodeResolver: Send + Sync {
+    async fn resolve(&self, ctx: &Context<'_>) -> Result<serde_json::Value>;
+}
+
+pub(crate) struct NodeRoot<N>(PhantomData<fn() -> N>);
+
+impl<N> Default for NodeRoot<N> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[async_trait::async_trait]
+impl<N: NodeInterface> NodeResolver for NodeRoot<N> {
+    async fn resolve(&self, ctx: &Context<'_>) -> Result<serde_json::Value> {
+        let ctx_obj = ctx.with_selection_set(&ctx.item.node.selection_set);
+        if ctx.item.node.name.node == "node" {
+            let id: ID = ctx.param_value("id", None)?;
+            let node = fetch_node::<N>(ctx, &id)
+                .await
+                .map_err(|err| err.into_error_with_path(ctx.item.pos, ctx.path_node.as_ref()))?;
+            OutputValueType::resolve(&node, &ctx_obj, ctx.item).await
+        } else {
+            let ids: Vec<ID> = ctx.param_value("ids", None)?;
+            let nodes =
+                futures::future::try_join_all(ids.iter().map(|id| fetch_node::<N>(ctx, id)))
+                    .await
+                    .map_err(|err| {
+                        err.into_error_with_path(ctx.item.pos, ctx.path_node.as_ref())
+                    })?;
+            OutputValueType::resolve(&nodes, &ctx_obj, ctx.item).await
+        }
+    }
+}
@@ -1,7 +1,7 @@
-use crate::parser::types::UploadValue;
+use crate::parser::types::{UploadContent, UploadValue};
 use crate::{registry, InputValueError, InputValueResult, InputValueType, Type, Value};
 use std::borrow::Cow;
-use std::io::Read;
+use std::io::{self, Read};
 
 /// Uploaded file
 ///
@@ -42,36 +42,76 @@ use std::io::Read;
 /// --form 'map={ "0": ["variables.file"] }' \
 /// --form '0=@myFile.txt'
 /// ```
+///
+/// The files are spooled to temporary files before the request is executed, unless the streaming
+/// mode of [`MultipartOptions`](http/struct.MultipartOptions.html) is enabled.
 pub struct Upload(UploadValue);
 
 impl Upload {
     /// Filename
+    ///
+    /// It is empty for a file that is streamed, use
+    /// [`UploadStream::filename`](http/struct.UploadStream.html#method.filename).
     pub fn filename(&self) -> &str {
         self.0.filename.as_str()
     }
 
     /// Content type, such as `application/json`, `image/jpg` ...
+    ///
+    /// It is `None` for a file that is streamed, use
+    /// [`UploadStream::content_type`](http/struct.UploadStream.html#method.content_type).
     pub fn content_type(&self) -> Option<&str> {
         self.0.content_type.as_deref()
     }
 
     /// Returns the size of the file, in bytes.
-    pub fn size(&self) -> std::io::Result<u64> {
-        self.0.content.metadata().map(|meta| meta.len())
+    ///
+    /// The size of a file that is streamed is unknown, it returns an error.
+    pub fn size(&self) -> io::Result<u64> {
+        match &self.0.content {
+            UploadContent::File(file) => file.metadata().map(|meta| meta.len()),
+            _ => Err(io::Error::other("The size of a streamed file is unknown")),
+        }
     }
 
     /// Convert to a `Read`.
     ///
-    /// **Note**: this is a *synchronous/blocking* reader.
+    /// **Note**: this is a *synchronous/blocking* reader. A file that is streamed can only be read
+    /// with [`into_stream`](#method.into_stream), the reader returns an error.
     pub fn into_read(self) -> impl Read + Sync + Send + 'static {
-        self.0.content
+        UploadRead(self.0.content)
     }
 
     #[cfg(feature = "unblock")]
     #[cfg_attr(feature = "nightly", doc(cfg(feature = "unblock")))]
     /// Convert to a `AsyncRead`.
     pub fn into_async_read(self) -> impl futures::AsyncRead + Sync + Send + 'static {
-        blocking::Unblock::new(self.0.content)
+        blocking::Unblock::new(UploadRead(self.0.content))
+    }
+
+    #[cfg(feature = "multipart")]
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "multipart")))]
+    /// Convert to an `UploadStream`, which is an `AsyncRead`.
+    ///
+    /// This works with all the files, and it is the only way to read a file that is streamed with
+    /// [`MultipartOptions::streaming`](http/struct.MultipartOptions.html#method.streaming). A
+    /// streamed file is read directly from the request, this waits for the file to be received
+    /// and returns an error if its content type is not allowed.
+    pub async fn into_stream(self) -> io::Result<crate::http::UploadStream> {
+        crate::http::UploadStream::open(self.0).await
+    }
+}
+
+struct UploadRead(UploadContent);
+
+impl Read for UploadRead {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            UploadContent::File(file) => file.read(buf),
+            _ => Err(io::Error::other(
+                "A streamed file must be read with `Upload::into_stream`",
+            )),
+        }
     }
 }
 
@@ -85,6 +125,9 @@ impl Type for Upload {
             name: Self::type_name().to_string(),
             description: None,
             is_valid: |value| matches!(value, Value::Upload(_)),
+            specified_by_url: None,
+            inaccessible: false,
+            tags: Default::default(),
         })
     }
 }
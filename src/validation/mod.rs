@@ -13,9 +13,15 @@ use crate::registry::Registry;
 use crate::{CacheControl, Error, Result, Variables};
 use visitor::{visit, VisitorContext, VisitorNil};
 
-pub struct CheckResult {
+/// Validation results.
+pub struct ValidationResult {
+    /// Cache control of the query.
     pub cache_control: CacheControl,
+
+    /// Query complexity.
     pub complexity: usize,
+
+    /// Query depth.
     pub depth: usize,
 }
 
@@ -34,7 +40,7 @@ pub fn check_rules(
     doc: &ExecutableDocument,
     variables: Option<&Variables>,
     mode: ValidationMode,
-) -> Result<CheckResult> {
+) -> Result<ValidationResult> {
     let mut ctx = VisitorContext::new(registry, doc, variables);
     let mut cache_control = CacheControl::default();
     let mut complexity = 0;
@@ -93,7 +99,7 @@ pub fn check_rules(
             errors: ctx.errors.into(),
         });
     }
-    Ok(CheckResult {
+    Ok(ValidationResult {
         cache_control,
         complexity,
         depth: depth as usize,
@@ -58,7 +58,12 @@ impl<'a> Visitor<'a> for ArgumentsOfCorrectType<'a> {
                     if let Err(reason) = validator.is_valid(value) {
                         ctx.report_error(
                             vec![name.pos],
-                            format!("Invalid value for argument \"{}\", {}", arg.name, reason),
+                            if arg.is_secret {
+                                // The reason may contain the value.
+                                format!("Invalid value for argument \"{}\"", arg.name)
+                            } else {
+                                format!("Invalid value for argument \"{}\", {}", arg.name, reason)
+                            },
                         );
                         return;
                     }
@@ -78,7 +83,11 @@ impl<'a> Visitor<'a> for ArgumentsOfCorrectType<'a> {
             }) {
                 ctx.report_error(
                     vec![name.pos],
-                    format!("Invalid value for argument {}", reason),
+                    if arg.is_secret {
+                        format!("Invalid value for argument \"{}\"", arg.name)
+                    } else {
+                        format!("Invalid value for argument {}", reason)
+                    },
                 );
             }
         }
@@ -128,15 +128,19 @@ pub fn is_valid_input_value(
                         for field in input_fields.values() {
                             input_names.remove(field.name);
                             if let Some(value) = values.get(field.name) {
+                                let field_path_node = QueryPathNode {
+                                    parent: Some(&path_node),
+                                    segment: QueryPathSegment::Name(field.name),
+                                };
+
                                 if let Some(validator) = &field.validator {
                                     if let Err(reason) = validator.is_valid(value) {
-                                        return Some(valid_error(
-                                            &QueryPathNode {
-                                                parent: Some(&path_node),
-                                                segment: QueryPathSegment::Name(field.name),
-                                            },
-                                            reason,
-                                        ));
+                                        return Some(if field.is_secret {
+                                            // The reason may contain the value.
+                                            format!("\"{}\"", field_path_node)
+                                        } else {
+                                            valid_error(&field_path_node, reason)
+                                        });
                                     }
                                 }
 
@@ -144,12 +148,13 @@ pub fn is_valid_input_value(
                                     registry,
                                     &field.ty,
                                     value,
-                                    QueryPathNode {
-                                        parent: Some(&path_node),
-                                        segment: QueryPathSegment::Name(field.name),
-                                    },
+                                    field_path_node.clone(),
                                 ) {
-                                    return Some(reason);
+                                    return Some(if field.is_secret {
+                                        format!("\"{}\"", field_path_node)
+                                    } else {
+                                        reason
+                                    });
                                 }
                             } else if registry::MetaTypeName::create(&field.ty).is_non_null()
                                 && field.default_value.is_none()
@@ -92,3 +92,413 @@ pub async fn test_connection_additional_fields() {
         })
     );
 }
+
+#[async_std::test]
+pub async fn test_connection_nodes_and_total_count() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct QueryRoot {
+        counted: Arc<AtomicUsize>,
+    }
+
+    #[Object]
+    impl QueryRoot {
+        async fn numbers(&self) -> Connection<usize, i32> {
+            let mut connection = Connection::new(false, true);
+            connection.append((0..3).map(|n| Edge::new(n, n as i32)));
+            connection.set_end_cursor(10);
+            let counted = self.counted.clone();
+            connection.set_total_count(move || {
+                let counted = counted.clone();
+                async move {
+                    counted.fetch_add(1, Ordering::SeqCst);
+                    Ok(100)
+                }
+            });
+            connection
+        }
+
+        async fn uncounted(&self) -> Connection<usize, i32> {
+            Connection::new(false, false)
+        }
+    }
+
+    let counted = Arc::new(AtomicUsize::new(0));
+    let schema = Schema::new(
+        QueryRoot {
+            counted: counted.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    );
+
+    assert_eq!(
+        schema
+            .execute("{ numbers { nodes pageInfo { startCursor endCursor } } }")
+            .await
+            .data,
+        serde_json::json!({
+            "numbers": {
+                "nodes": [0, 1, 2],
+                "pageInfo": { "startCursor": "0", "endCursor": "10" },
+            },
+        })
+    );
+    // The total count is only computed when it is queried.
+    assert_eq!(counted.load(Ordering::SeqCst), 0);
+
+    assert_eq!(
+        schema
+            .execute("{ numbers { totalCount } uncounted { totalCount } }")
+            .await
+            .data,
+        serde_json::json!({
+            "numbers": { "totalCount": 100 },
+            "uncounted": { "totalCount": null },
+        })
+    );
+    assert_eq!(counted.load(Ordering::SeqCst), 1);
+}
+
+#[async_std::test]
+pub async fn test_connection_names() {
+    struct NumberList;
+
+    impl ConnectionNameType for NumberList {
+        fn type_name<T: OutputValueType>() -> String {
+            "NumberList".to_string()
+        }
+    }
+
+    struct NumberItem;
+
+    impl EdgeNameType for NumberItem {
+        fn type_name<T: OutputValueType>() -> String {
+            "NumberItem".to_string()
+        }
+    }
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn numbers(
+            &self,
+        ) -> Connection<usize, i32, EmptyFields, EmptyFields, NumberList, NumberItem> {
+            let mut connection = Connection::new(false, false);
+            connection.append(vec![Edge::new(0, 1)]);
+            connection
+        }
+    }
+
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema
+            .execute("{ numbers { __typename edges { __typename node } } }")
+            .await
+            .data,
+        serde_json::json!({
+            "numbers": {
+                "__typename": "NumberList",
+                "edges": [{ "__typename": "NumberItem", "node": 1 }],
+            },
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_opaque_and_composite_cursors() {
+    let cursor = OpaqueCursor((10, "a".to_string()));
+    let s = cursor.encode_cursor();
+    assert!(!s.contains("10"));
+    assert_eq!(OpaqueCursor::decode_cursor(&s), Ok(cursor));
+    assert_eq!(
+        OpaqueCursor::<i32>::decode_cursor("!"),
+        Err(CursorError::InvalidEncoding)
+    );
+    assert!(matches!(
+        OpaqueCursor::<i32>::decode_cursor(&OpaqueCursor("a".to_string()).encode_cursor()),
+        Err(CursorError::InvalidValue(_))
+    ));
+
+    let cursor = (10usize, "a".to_string());
+    let s = cursor.encode_cursor();
+    assert_eq!(<(usize, String)>::decode_cursor(&s), Ok(cursor));
+    assert_eq!(
+        <(usize, String, usize)>::decode_cursor(&s),
+        Err(CursorError::InvalidLength {
+            expected: 3,
+            actual: 2
+        })
+    );
+    assert!(matches!(
+        <(String, usize)>::decode_cursor(&s),
+        Err(CursorError::InvalidComponent { index: 1, .. })
+    ));
+}
+
+#[async_std::test]
+pub async fn test_signed_cursor() {
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn numbers(
+            &self,
+            ctx: &Context<'_>,
+            after: Option<String>,
+            first: Option<i32>,
+        ) -> FieldResult<Connection<SignedCursor<usize>, i32>> {
+            query_with_ctx(
+                ctx,
+                after,
+                None,
+                first,
+                None,
+                |after, _before, first, _last| async move {
+                    let start = after
+                        .map(|after: SignedCursor<usize>| *after + 1)
+                        .unwrap_or(0);
+                    let end = start + first.unwrap_or(2);
+                    let mut connection = Connection::new(start > 0, true);
+                    connection.append((start..end).map(|n| Edge::new(SignedCursor(n), n as i32)));
+                    Ok(connection)
+                },
+            )
+            .await
+        }
+    }
+
+    let key = CursorKey::new("secret");
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(key.clone())
+        .finish();
+
+    let data = schema
+        .execute("{ numbers { pageInfo { endCursor } } }")
+        .await
+        .into_result()
+        .unwrap()
+        .data;
+    let end_cursor = data["numbers"]["pageInfo"]["endCursor"].as_str().unwrap();
+    assert_eq!(
+        SignedCursor::<usize>::decode_with_key(&key, end_cursor),
+        Ok(SignedCursor(1))
+    );
+
+    let query = format!(r#"{{ numbers(after: "{}") {{ nodes }} }}"#, end_cursor);
+    assert_eq!(
+        schema.execute(&query).await.data,
+        serde_json::json!({ "numbers": { "nodes": [2, 3] } })
+    );
+
+    // A cursor signed with another key is rejected.
+    let forged = SignedCursor(5usize).encode_with_key(&CursorKey::new("other"));
+    let query = format!(r#"{{ numbers(after: "{}") {{ nodes }} }}"#, forged);
+    assert_eq!(
+        schema
+            .execute(&query)
+            .await
+            .into_result()
+            .unwrap_err()
+            .to_string(),
+        "Query error: Failed to resolve field: The \"after\" cursor is invalid: The cursor signature is invalid"
+    );
+
+    // The cursors can not be output without the key.
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema
+            .execute("{ numbers { edges { cursor } } }")
+            .await
+            .into_result()
+            .unwrap_err()
+            .to_string(),
+        "Query error: Failed to resolve field: The cursor key is not in the schema data"
+    );
+    assert_eq!(
+        schema
+            .execute("{ numbers { pageInfo { endCursor } } }")
+            .await
+            .into_result()
+            .unwrap_err()
+            .to_string(),
+        "Query error: Failed to resolve field: The cursor key is not in the schema data"
+    );
+}
+
+#[async_std::test]
+pub async fn test_connection_from_slice() {
+    fn page(
+        after: Option<usize>,
+        before: Option<usize>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> (Vec<i32>, bool, bool) {
+        let numbers: Vec<i32> = (0..10).collect();
+        let connection: Connection<usize, i32> = Connection::from_slice(
+            &numbers,
+            Pagination::new(
+                after.map(|after| after.encode_cursor()),
+                before.map(|before| before.encode_cursor()),
+                first,
+                last,
+            ),
+        )
+        .unwrap();
+        let has_previous_page = connection.has_previous_page();
+        let has_next_page = connection.has_next_page();
+        (
+            connection.edges().iter().map(|edge| *edge.node()).collect(),
+            has_previous_page,
+            has_next_page,
+        )
+    }
+
+    assert_eq!(
+        page(None, None, None, None),
+        ((0..10).collect(), false, false)
+    );
+    assert_eq!(page(None, None, Some(2), None), (vec![0, 1], false, true));
+    assert_eq!(page(Some(1), None, Some(2), None), (vec![2, 3], true, true));
+    assert_eq!(page(None, None, None, Some(2)), (vec![8, 9], true, false));
+    assert_eq!(page(None, Some(5), None, Some(2)), (vec![3, 4], true, true));
+    assert_eq!(
+        page(Some(1), Some(5), None, None),
+        (vec![2, 3, 4], true, true)
+    );
+    assert_eq!(
+        page(Some(1), Some(5), Some(2), None),
+        (vec![2, 3], true, true)
+    );
+    assert_eq!(
+        page(Some(1), Some(5), None, Some(2)),
+        (vec![3, 4], true, true)
+    );
+    assert_eq!(
+        page(Some(1), Some(5), Some(3), Some(2)),
+        (vec![3, 4], true, false)
+    );
+    assert_eq!(
+        page(Some(7), None, Some(5), None),
+        (vec![8, 9], true, false)
+    );
+    assert_eq!(page(Some(5), Some(3), None, None), (vec![], true, true));
+}
+
+#[async_std::test]
+pub async fn test_connection_max_page_size() {
+    let numbers: Vec<i32> = (0..10).collect();
+
+    let connection: Connection<usize, i32> =
+        Connection::from_slice(&numbers, Pagination::default().max_page_size(3)).unwrap();
+    assert_eq!(connection.edges().len(), 3);
+    assert!(connection.has_next_page());
+
+    let err = Connection::<usize, i32>::from_slice(
+        &numbers,
+        Pagination::new(None, None, Some(4), None).max_page_size(3),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.0, "The \"first\" parameter must not be greater than 3");
+
+    let err =
+        Connection::<usize, i32>::from_slice(&numbers, Pagination::new(None, None, None, Some(-1)))
+            .err()
+            .unwrap();
+    assert_eq!(
+        err.0,
+        "The \"last\" parameter must be a non-negative number"
+    );
+}
+
+#[async_std::test]
+pub async fn test_connection_from_iter_and_offset_loader() {
+    // The size of the iterator is not known.
+    let connection: Connection<usize, i32> = Connection::from_iter(
+        (0..10).filter(|_| true),
+        Pagination::new(None, None, None, Some(2)),
+    )
+    .unwrap();
+    assert_eq!(
+        connection
+            .edges()
+            .iter()
+            .map(|edge| *edge.node())
+            .collect::<Vec<_>>(),
+        vec![8, 9]
+    );
+
+    // Invalid arguments are returned without consuming an unbounded iterator.
+    for pagination in vec![
+        Pagination::new(None, None, Some(-1), None),
+        Pagination::new(None, None, None, Some(-1)),
+        Pagination::new(Some("x".to_string()), None, None, Some(2)),
+        Pagination::new(None, None, Some(4), None).max_page_size(3),
+    ] {
+        assert!(Connection::<usize, usize>::from_iter(0.., pagination).is_err());
+    }
+
+    let loads = std::sync::Mutex::new(Vec::new());
+    let load = |offset: usize, limit: usize| {
+        loads.lock().unwrap().push((offset, limit));
+        async move { Ok((offset..10).take(limit).map(|n| n as i32).collect()) }
+    };
+
+    let connection: Connection<usize, i32> = Connection::from_offset_loader(
+        Pagination::new(Some(2.encode_cursor()), None, Some(3), None),
+        load,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        connection
+            .edges()
+            .iter()
+            .map(|edge| *edge.node())
+            .collect::<Vec<_>>(),
+        vec![3, 4, 5]
+    );
+    assert!(connection.has_previous_page());
+    assert!(connection.has_next_page());
+
+    let connection: Connection<usize, i32> = Connection::from_offset_loader(
+        Pagination::new(None, Some(6.encode_cursor()), None, Some(2)),
+        load,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        connection
+            .edges()
+            .iter()
+            .map(|edge| *edge.node())
+            .collect::<Vec<_>>(),
+        vec![4, 5]
+    );
+    assert_eq!(*loads.lock().unwrap(), vec![(3, 4), (4, 2)]);
+
+    assert!(Connection::<usize, i32>::from_offset_loader(
+        Pagination::new(None, None, None, Some(2)),
+        load,
+    )
+    .await
+    .is_err());
+
+    let connection: Connection<usize, i32> = Connection::from_offset_loader(
+        Pagination::new(None, None, None, Some(2)).total_count(10),
+        load,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        connection
+            .edges()
+            .iter()
+            .map(|edge| *edge.node())
+            .collect::<Vec<_>>(),
+        vec![8, 9]
+    );
+}
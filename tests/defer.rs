@@ -0,0 +1,279 @@
+use async_graphql::http::{accepts_multipart_mixed, multipart_mixed_stream};
+use async_graphql::*;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(SimpleObject)]
+struct Item {
+    id: i32,
+    name: String,
+}
+
+struct User;
+
+#[Object]
+impl User {
+    async fn name(&self) -> &str {
+        "sunli"
+    }
+
+    async fn friends(&self) -> Vec<Item> {
+        (0..3)
+            .map(|id| Item {
+                id,
+                name: format!("friend{}", id),
+            })
+            .collect()
+    }
+
+    async fn slow(&self) -> i32 {
+        async_std::task::sleep(Duration::from_millis(10)).await;
+        1
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn user(&self, ctx: &Context<'_>) -> User {
+        if let Some(count) = ctx.data_opt::<Arc<AtomicUsize>>() {
+            count.fetch_add(1, Ordering::SeqCst);
+        }
+        User
+    }
+
+    async fn users(&self) -> Vec<User> {
+        vec![User, User]
+    }
+
+    async fn values(&self) -> Vec<Vec<i32>> {
+        vec![vec![1, 2], vec![3], vec![4, 5]]
+    }
+}
+
+async fn payloads(
+    schema: &Schema<QueryRoot, EmptyMutation, EmptySubscription>,
+    request: impl Into<Request>,
+) -> Vec<serde_json::Value> {
+    schema
+        .execute_incremental(request)
+        .map(|payload| serde_json::to_value(payload).unwrap())
+        .collect()
+        .await
+}
+
+#[async_std::test]
+pub async fn test_defer() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let query = r#"{
+        user {
+            name
+            ... @defer(label: "friends") { friends { id } }
+        }
+    }"#;
+
+    assert_eq!(
+        payloads(&schema, query).await,
+        vec![
+            json!({ "data": { "user": { "name": "sunli" } }, "hasNext": true }),
+            json!({
+                "incremental": [{
+                    "data": { "friends": [{ "id": 0 }, { "id": 1 }, { "id": 2 }] },
+                    "path": ["user"],
+                    "label": "friends",
+                }],
+                "hasNext": false,
+            }),
+        ]
+    );
+
+    // The directives are ignored by `execute`.
+    assert_eq!(
+        schema.execute(query).await.data,
+        json!({ "user": { "name": "sunli", "friends": [{ "id": 0 }, { "id": 1 }, { "id": 2 }] } })
+    );
+
+    // A fragment with a false `if` argument is in the initial payload.
+    let query = r#"query($defer: Boolean!) {
+        user { ...UserFields @defer(if: $defer) }
+    }
+    fragment UserFields on User { name }"#;
+    assert_eq!(
+        payloads(
+            &schema,
+            Request::new(query).variables(Variables::from_json(json!({ "defer": false })))
+        )
+        .await,
+        vec![json!({ "data": { "user": { "name": "sunli" } }, "hasNext": false })]
+    );
+    assert_eq!(
+        payloads(
+            &schema,
+            Request::new(query).variables(Variables::from_json(json!({ "defer": true })))
+        )
+        .await,
+        vec![
+            json!({ "data": { "user": {} }, "hasNext": true }),
+            json!({
+                "incremental": [{ "data": { "name": "sunli" }, "path": ["user"] }],
+                "hasNext": false,
+            }),
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_defer_in_list() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    let mut payloads = payloads(&schema, "{ users { ... @defer { name } } }").await;
+
+    assert_eq!(
+        payloads.remove(0),
+        json!({ "data": { "users": [{}, {}] }, "hasNext": true })
+    );
+    let mut results = Vec::new();
+    for mut payload in payloads {
+        results.extend(
+            payload["incremental"]
+                .as_array_mut()
+                .unwrap()
+                .drain(..)
+                .map(|result| (result["path"].clone(), result["data"].clone())),
+        );
+    }
+    results.sort_by_key(|(path, _)| path.to_string());
+    assert_eq!(
+        results,
+        vec![
+            (json!(["users", 0]), json!({ "name": "sunli" })),
+            (json!(["users", 1]), json!({ "name": "sunli" })),
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_stream() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+
+    assert_eq!(
+        payloads(
+            &schema,
+            r#"{ user { friends @stream(initialCount: 1, label: "rest") { name } } }"#
+        )
+        .await,
+        vec![
+            json!({ "data": { "user": { "friends": [{ "name": "friend0" }] } }, "hasNext": true }),
+            json!({
+                "incremental": [{
+                    "items": [{ "name": "friend1" }],
+                    "path": ["user", "friends", 1],
+                    "label": "rest",
+                }],
+                "hasNext": true,
+            }),
+            json!({
+                "incremental": [{
+                    "items": [{ "name": "friend2" }],
+                    "path": ["user", "friends", 2],
+                    "label": "rest",
+                }],
+                "hasNext": false,
+            }),
+        ]
+    );
+
+    // The inner lists of a nested list are not truncated.
+    assert_eq!(
+        payloads(&schema, "{ values @stream(initialCount: 2) }").await,
+        vec![
+            json!({ "data": { "values": [[1, 2], [3]] }, "hasNext": true }),
+            json!({
+                "incremental": [{ "items": [[4, 5]], "path": ["values", 2] }],
+                "hasNext": false,
+            }),
+        ]
+    );
+
+    // Nothing remains to be streamed.
+    assert_eq!(
+        payloads(
+            &schema,
+            "{ user { friends @stream(initialCount: 5) { id } } }"
+        )
+        .await,
+        vec![json!({
+            "data": { "user": { "friends": [{ "id": 0 }, { "id": 1 }, { "id": 2 }] } },
+            "hasNext": false,
+        })]
+    );
+
+    let resp = payloads(&schema, "{ values @stream(initialCount: -1) }").await;
+    assert_eq!(resp.len(), 1);
+    assert_eq!(resp[0]["hasNext"], json!(false));
+    assert!(resp[0]["errors"].is_array());
+}
+
+#[async_std::test]
+pub async fn test_single_execution() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(count.clone())
+        .finish();
+    let query = r#"{
+        user {
+            name
+            ... @defer { slow }
+            friends @stream(initialCount: 1) { id }
+        }
+    }"#;
+
+    // The initial payload and the streamed items are sent before the deferred fragment is
+    // resolved.
+    assert_eq!(
+        payloads(&schema, query).await,
+        vec![
+            json!({ "data": { "user": { "name": "sunli", "friends": [{ "id": 0 }] } }, "hasNext": true }),
+            json!({
+                "incremental": [{ "items": [{ "id": 1 }], "path": ["user", "friends", 1] }],
+                "hasNext": true,
+            }),
+            json!({
+                "incremental": [{ "items": [{ "id": 2 }], "path": ["user", "friends", 2] }],
+                "hasNext": true,
+            }),
+            json!({
+                "incremental": [{ "data": { "slow": 1 }, "path": ["user"] }],
+                "hasNext": false,
+            }),
+        ]
+    );
+    assert_eq!(count.load(Ordering::SeqCst), 1);
+}
+
+#[async_std::test]
+pub async fn test_multipart_mixed() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+
+    assert!(accepts_multipart_mixed(
+        "application/json, multipart/mixed; deferSpec=20220824"
+    ));
+    assert!(!accepts_multipart_mixed("application/json"));
+
+    let body =
+        multipart_mixed_stream(schema.execute_incremental("{ user { ... @defer { name } } }"))
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+    assert_eq!(
+        body,
+        "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n\
+         {\"data\":{\"user\":{}},\"hasNext\":true}\
+         \r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n\
+         {\"incremental\":[{\"data\":{\"name\":\"sunli\"},\"path\":[\"user\"]}],\"hasNext\":false}\
+         \r\n-----\r\n"
+    );
+}
@@ -0,0 +1,103 @@
+use async_graphql::*;
+
+#[async_std::test]
+pub async fn test_export_sdl() {
+    struct MyDate(String);
+
+    /// A date as described by RFC 3339
+    #[Scalar(specified_by_url = "https://tools.ietf.org/html/rfc3339")]
+    impl ScalarType for MyDate {
+        fn parse(value: Value) -> InputValueResult<Self> {
+            match value {
+                Value::String(s) => Ok(MyDate(s)),
+                _ => Err(InputValueError::ExpectedType(value)),
+            }
+        }
+
+        fn to_value(&self) -> Value {
+            Value::String(self.0.clone())
+        }
+    }
+
+    /// Colors
+    #[derive(Enum, Copy, Clone, Eq, PartialEq)]
+    enum Color {
+        /// Red color
+        Red,
+        #[graphql(deprecation = "Use \"Red\"")]
+        Blue,
+    }
+
+    /// An input
+    #[derive(InputObject)]
+    struct MyInput {
+        /// Field a
+        a: i32,
+        #[graphql(deprecation = "Use a")]
+        b: Option<i32>,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        /// Get the value
+        async fn value(
+            &self,
+            #[graphql(desc = "The input")] _input: MyInput,
+            #[graphql(default = 1, deprecation = "Not used")] _old: i32,
+        ) -> MyDate {
+            MyDate("2020-01-01".to_string())
+        }
+
+        #[graphql(deprecation = "Use value")]
+        async fn color(&self, _color: Option<Color>) -> Color {
+            Color::Red
+        }
+    }
+
+    assert_eq!(
+        Schema::<Query, EmptyMutation, EmptySubscription>::sdl(),
+        r#"type Query {
+	"""
+	Get the value
+	"""
+	value(
+		"""
+		The input
+		"""
+		input: MyInput!
+		old: Int! = 1 @deprecated(reason: "Not used")
+	): MyDate!
+	color(color: Color): Color! @deprecated(reason: "Use value")
+}
+"""
+An input
+"""
+input MyInput {
+	"""
+	Field a
+	"""
+	a: Int!
+	b: Int @deprecated(reason: "Use a")
+}
+"""
+A date as described by RFC 3339
+"""
+scalar MyDate @specifiedBy(url: "https://tools.ietf.org/html/rfc3339")
+"""
+Colors
+"""
+enum Color {
+	"""
+	Red color
+	"""
+	RED
+	BLUE @deprecated(reason: "Use \"Red\"")
+}
+schema {
+	query: Query
+}
+"#
+    );
+}
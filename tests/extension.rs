@@ -0,0 +1,187 @@
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory};
+use async_graphql::parser::types::{
+    ExecutableDocument, Field, Name, OperationType, Selection, SelectionSet,
+};
+use async_graphql::*;
+use futures::{Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+
+#[async_std::test]
+pub async fn test_prepare_document() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self, name: String) -> String {
+            name
+        }
+
+        async fn secret(&self) -> i32 {
+            unreachable!()
+        }
+    }
+
+    struct Rewrite;
+
+    impl ExtensionFactory for Rewrite {
+        fn create(&self) -> Box<dyn Extension> {
+            Box::new(Rewrite)
+        }
+    }
+
+    impl Extension for Rewrite {
+        fn prepare_document(
+            &self,
+            _ctx: &ExtensionContext<'_>,
+            document: &mut ExecutableDocument,
+            operation_name: Option<&str>,
+            variables: &mut Variables,
+        ) -> Result<()> {
+            let operation = match document.operations.get_mut(operation_name) {
+                Some(operation) => operation,
+                None => return Ok(()),
+            };
+            if operation.node.ty != OperationType::Query {
+                return Err(Error::Other("only queries are allowed".to_string()));
+            }
+
+            let items = &mut operation.node.selection_set.node.items;
+            items.retain(|selection| match &selection.node {
+                Selection::Field(field) => field.node.name.node != "secret",
+                _ => true,
+            });
+            let pos = operation.pos;
+            items.push(Positioned::new(
+                Selection::Field(Positioned::new(
+                    Field {
+                        alias: None,
+                        name: Positioned::new(Name::new("__typename".to_string()).unwrap(), pos),
+                        arguments: Vec::new(),
+                        directives: Vec::new(),
+                        selection_set: Positioned::new(SelectionSet::default(), pos),
+                    },
+                    pos,
+                )),
+                pos,
+            ));
+
+            for value in variables.0.values_mut() {
+                if let Value::String(s) = value {
+                    *s = s.to_lowercase();
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension(Rewrite)
+        .finish();
+
+    let query = r#"
+        query A { secret }
+        query B($name: String!) { value(name: $name) secret }
+    "#;
+    let resp = schema
+        .execute(
+            Request::new(query)
+                .operation_name("B")
+                .variables(Variables::from_json(serde_json::json!({ "name": "ABC" }))),
+        )
+        .await;
+    assert_eq!(
+        resp.into_result().unwrap().data,
+        serde_json::json!({
+            "value": "abc",
+            "__typename": "Query",
+        })
+    );
+
+    let resp = schema.execute("mutation { value }").await;
+    assert_eq!(
+        resp.into_result().unwrap_err(),
+        Error::Other("only queries are allowed".to_string())
+    );
+}
+
+#[async_std::test]
+pub async fn test_subscription_hooks() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        async fn values(&self, count: i32) -> impl Stream<Item = i32> {
+            futures::stream::iter(0..count)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Lifecycle(Arc<Mutex<Vec<String>>>);
+
+    impl Lifecycle {
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    impl ExtensionFactory for Lifecycle {
+        fn create(&self) -> Box<dyn Extension> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl Extension for Lifecycle {
+        fn subscription_start(&self, _ctx: &ExtensionContext<'_>) {
+            self.0.lock().unwrap().push("start".to_string());
+        }
+
+        fn subscription_event(&self, _ctx: &ExtensionContext<'_>, data: &serde_json::Value) {
+            self.0.lock().unwrap().push(format!("event {}", data));
+        }
+
+        fn subscription_end(&self, _ctx: &ExtensionContext<'_>) {
+            self.0.lock().unwrap().push("end".to_string());
+        }
+    }
+
+    let lifecycle = Lifecycle::default();
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
+        .extension(lifecycle.clone())
+        .finish();
+
+    let responses = schema
+        .execute_stream("subscription { values(count: 2) }")
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        lifecycle.take(),
+        vec![
+            "start",
+            r#"event {"values":0}"#,
+            r#"event {"values":1}"#,
+            "end",
+        ]
+    );
+
+    let mut stream = schema
+        .execute_stream("subscription { values(count: 10) }")
+        .boxed();
+    stream.next().await;
+    assert_eq!(lifecycle.take(), vec!["start", r#"event {"values":0}"#]);
+    drop(stream);
+    assert_eq!(lifecycle.take(), vec!["end"]);
+
+    // Queries don't call the subscription hooks.
+    schema.execute_stream("{ value }").collect::<Vec<_>>().await;
+    assert!(lifecycle.take().is_empty());
+}
@@ -87,3 +87,347 @@ pub async fn test_federation() {
         })
     );
 }
+
+#[async_std::test]
+pub async fn test_federation2_sdl() {
+    #[derive(SimpleObject)]
+    #[graphql(shareable, tag = "public", tag = "geo")]
+    struct Position {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(SimpleObject)]
+    #[graphql(unresolvable = "id")]
+    struct Account {
+        id: ID,
+    }
+
+    #[derive(Enum, Copy, Clone, Eq, PartialEq)]
+    #[graphql(tag = "public")]
+    enum Status {
+        Active,
+        #[graphql(inaccessible)]
+        Hidden,
+    }
+
+    struct Item {
+        id: ID,
+    }
+
+    #[Object]
+    impl Item {
+        async fn id(&self) -> &ID {
+            &self.id
+        }
+
+        #[graphql(override_from = "legacy")]
+        async fn price(&self) -> i32 {
+            todo!()
+        }
+
+        #[graphql(inaccessible, tag = "internal")]
+        async fn cost(&self) -> i32 {
+            todo!()
+        }
+
+        async fn position(&self) -> Position {
+            todo!()
+        }
+
+        async fn owner(&self) -> Account {
+            todo!()
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[graphql(entity)]
+        async fn find_item_by_id(&self, id: ID) -> Item {
+            Item { id }
+        }
+
+        async fn items(&self, #[graphql(inaccessible)] _status: Option<Status>) -> Vec<Item> {
+            todo!()
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .compose_directive("https://example.com/custom/v1.0", "custom")
+        .finish();
+    let data = schema
+        .execute("{ _service { sdl } }")
+        .await
+        .into_result()
+        .unwrap()
+        .data;
+    assert_eq!(
+        data["_service"]["sdl"].as_str().unwrap(),
+        r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key", "@requires", "@provides", "@external", "@shareable", "@inaccessible", "@override", "@tag", "@interfaceObject", "@composeDirective"])
+	@link(url: "https://example.com/custom/v1.0", import: ["@custom"])
+	@composeDirective(name: "@custom")
+type Query {
+	items(status: Status @inaccessible): [Item!]!
+}
+enum Status @tag(name: "public") {
+	ACTIVE
+	HIDDEN @inaccessible
+}
+type Item @key(fields: "id") {
+	id: ID!
+	price: Int! @override(from: "legacy")
+	cost: Int! @inaccessible @tag(name: "internal")
+	position: Position!
+	owner: Account!
+}
+type Position @shareable @tag(name: "public") @tag(name: "geo") {
+	x: Int!
+	y: Int!
+}
+type Account @key(fields: "id", resolvable: false) {
+	id: ID!
+}
+"#
+    );
+}
+
+#[async_std::test]
+pub async fn test_batch_entities() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Product {
+        upc: String,
+    }
+
+    #[Object(extends)]
+    impl Product {
+        #[graphql(external)]
+        async fn upc(&self) -> &str {
+            &self.upc
+        }
+    }
+
+    struct Query {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[Object]
+    impl Query {
+        #[graphql(entity)]
+        async fn find_user_by_id(&self, id: ID) -> User {
+            User { id }
+        }
+
+        #[graphql(entity)]
+        async fn find_products(&self, upcs: Vec<String>) -> Vec<Option<Product>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            upcs.into_iter()
+                .map(|upc| {
+                    if upc == "missing" {
+                        None
+                    } else {
+                        Some(Product { upc })
+                    }
+                })
+                .collect()
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let schema = Schema::new(
+        Query {
+            calls: calls.clone(),
+        },
+        EmptyMutation,
+        EmptySubscription,
+    );
+    let query = r#"{
+            _entities(representations: [
+                {__typename: "Product", upc: "1"},
+                {__typename: "User", id: "a"},
+                {__typename: "Product", upc: "missing"},
+                {__typename: "Product", upc: "2"},
+            ]) {
+                __typename
+                ... on Product {
+                    upc
+                }
+                ... on User {
+                    id
+                }
+            }
+        }"#;
+    assert_eq!(
+        schema.execute(query).await.into_result().unwrap().data,
+        serde_json::json!({
+            "_entities": [
+                {"__typename": "Product", "upc": "1"},
+                {"__typename": "User", "id": "a"},
+                null,
+                {"__typename": "Product", "upc": "2"},
+            ]
+        })
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+enum ProtoValue {
+    Varint(u64),
+    Bytes(Vec<u8>),
+}
+
+/// Decodes the fields of a protobuf message, only varints and length-delimited values are used by
+/// the trace.
+fn decode_message(mut data: &[u8]) -> Vec<(u64, ProtoValue)> {
+    fn varint(data: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = data[0];
+            *data = &data[1..];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    let mut fields = Vec::new();
+    while !data.is_empty() {
+        let key = varint(&mut data);
+        match key & 7 {
+            0 => fields.push((key >> 3, ProtoValue::Varint(varint(&mut data)))),
+            2 => {
+                let len = varint(&mut data) as usize;
+                fields.push((key >> 3, ProtoValue::Bytes(data[..len].to_vec())));
+                data = &data[len..];
+            }
+            wire_type => panic!("unexpected wire type {}", wire_type),
+        }
+    }
+    fields
+}
+
+fn proto_u64(message: &[(u64, ProtoValue)], field: u64) -> u64 {
+    message
+        .iter()
+        .find_map(|(n, value)| match value {
+            ProtoValue::Varint(value) if *n == field => Some(*value),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+fn proto_messages(message: &[(u64, ProtoValue)], field: u64) -> Vec<Vec<(u64, ProtoValue)>> {
+    message
+        .iter()
+        .filter_map(|(n, value)| match value {
+            ProtoValue::Bytes(data) if *n == field => Some(decode_message(data)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn proto_string(message: &[(u64, ProtoValue)], field: u64) -> String {
+    message
+        .iter()
+        .find_map(|(n, value)| match value {
+            ProtoValue::Bytes(data) if *n == field => {
+                Some(String::from_utf8(data.clone()).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+#[async_std::test]
+pub async fn test_federation_tracing() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+
+        async fn error(&self) -> FieldResult<Option<i32>> {
+            Err("oops".into())
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension(extensions::ApolloFederationTracing)
+        .finish();
+
+    let resp = schema.execute("{ value }").await;
+    assert_eq!(resp.data, serde_json::json!({ "value": 10 }));
+    assert!(resp
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("ftv1"))
+        .is_none());
+
+    let resp = schema
+        .execute(
+            Request::new("{ value renamed: value error }").data(extensions::IncludeFederatedTrace),
+        )
+        .await;
+    let trace = resp
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("ftv1"))
+        .and_then(|trace| trace.as_str())
+        .unwrap();
+    let trace = decode_message(&base64::decode(trace).unwrap());
+
+    // Timestamps are (seconds, nanos).
+    let timestamp = |field| {
+        let timestamp = &proto_messages(&trace, field)[0];
+        (proto_u64(timestamp, 1), proto_u64(timestamp, 2))
+    };
+    let (start_time, end_time) = (timestamp(4), timestamp(3));
+    assert!(start_time < end_time);
+    assert!(proto_u64(&trace, 11) > 0);
+
+    let root = proto_messages(&trace, 14);
+    assert_eq!(root.len(), 1);
+    let root = &root[0];
+    assert_eq!(proto_string(root, 1), "");
+    assert!(proto_messages(root, 11).is_empty());
+
+    let fields = proto_messages(root, 12);
+    assert_eq!(
+        fields
+            .iter()
+            .map(|field| (
+                proto_string(field, 1),
+                proto_string(field, 3),
+                proto_string(field, 13)
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("value".to_string(), "Int!".to_string(), "Query".to_string()),
+            (
+                "renamed".to_string(),
+                "Int!".to_string(),
+                "Query".to_string()
+            ),
+            ("error".to_string(), "Int".to_string(), "Query".to_string()),
+        ]
+    );
+    for field in &fields {
+        assert!(proto_u64(field, 8) <= proto_u64(field, 9));
+        assert!(proto_u64(field, 9) > 0);
+    }
+
+    assert!(proto_messages(&fields[0], 11).is_empty());
+    let errors = proto_messages(&fields[2], 11);
+    assert_eq!(errors.len(), 1);
+    assert!(proto_string(&errors[0], 1).contains("oops"));
+    let location = &proto_messages(&errors[0], 2)[0];
+    assert_eq!((proto_u64(location, 1), proto_u64(location, 2)), (1, 24));
+}
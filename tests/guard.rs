@@ -308,3 +308,76 @@ pub async fn test_guard_forward_arguments() {
         }
     );
 }
+
+#[async_std::test]
+pub async fn test_guard_subscription_revoked() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct Permission(Arc<AtomicBool>);
+
+    struct PermissionGuard;
+
+    #[async_trait::async_trait]
+    impl Guard for PermissionGuard {
+        async fn check(&self, ctx: &Context<'_>) -> FieldResult<()> {
+            if ctx.data_unchecked::<Permission>().0.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err("Forbidden".into())
+            }
+        }
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            1
+        }
+    }
+
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        #[graphql(guard(PermissionGuard()))]
+        async fn values(&self) -> impl Stream<Item = i32> {
+            futures::stream::iter(1..10)
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, Subscription);
+    let permission = Permission(Arc::new(AtomicBool::new(true)));
+    let mut stream = schema
+        .execute_stream(Request::new("subscription { values }").data(permission.clone()))
+        .boxed();
+    assert_eq!(
+        stream.next().await.unwrap().data,
+        serde_json::json!({ "values": 1 })
+    );
+    assert_eq!(
+        stream.next().await.unwrap().data,
+        serde_json::json!({ "values": 2 })
+    );
+
+    // The guard is checked again for every event.
+    permission.0.store(false, Ordering::SeqCst);
+    assert_eq!(
+        stream.next().await.unwrap().error.unwrap(),
+        Error::Query {
+            pos: Pos {
+                line: 1,
+                column: 16
+            },
+            path: Some(serde_json::json!(["values"])),
+            err: QueryError::FieldError {
+                err: "Forbidden".to_string(),
+                extended_error: None,
+            },
+        }
+    );
+    assert!(stream.next().await.is_none());
+}
@@ -295,3 +295,186 @@ pub async fn test_inputobject_flatten_multiple() {
         .unwrap()
     );
 }
+
+#[async_std::test]
+pub async fn test_input_object_process_with() {
+    mod processor {
+        pub fn string(input: &mut String) {
+            while let Some(ch) = input.pop() {
+                if !ch.is_whitespace() {
+                    input.push(ch);
+                    break;
+                }
+            }
+        }
+    }
+    #[derive(InputObject)]
+    struct MyInput {
+        #[graphql(process_with = "processor::string")]
+        a: String,
+
+        #[graphql(process_with = "str::make_ascii_uppercase", default = "abc")]
+        b: String,
+    }
+
+    struct MyOutput {
+        a: String,
+        b: String,
+    }
+
+    #[Object]
+    impl MyOutput {
+        async fn a(&self) -> &String {
+            &self.a
+        }
+
+        async fn b(&self) -> &String {
+            &self.b
+        }
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn a(&self, input: MyInput) -> MyOutput {
+            MyOutput {
+                a: input.a,
+                b: input.b,
+            }
+        }
+
+        async fn b(
+            &self,
+            #[graphql(process_with = "str::make_ascii_lowercase")] value: String,
+        ) -> String {
+            value
+        }
+    }
+
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+    let query = r#"{
+            a(input:{a:"  hello  "}) {
+                a b
+            }
+            b(value: "HELLO")
+        }"#;
+    assert_eq!(
+        schema.execute(query).await.data,
+        serde_json::json!({
+            "a": {
+                "a": "  hello",
+                "b": "ABC",
+            },
+            "b": "hello",
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_input_object_and_simple_object_same_struct() {
+    #[derive(SimpleObject, InputObject, Clone)]
+    #[graphql(input_name = "PointInput")]
+    struct Point {
+        x: i32,
+        #[graphql(default = 10)]
+        y: i32,
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn point(&self, point: Point) -> Point {
+            point
+        }
+
+        async fn points(&self, points: Vec<Option<Point>>) -> Vec<Option<Point>> {
+            points
+        }
+    }
+
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+
+    let query = r#"query($p: PointInput!) {
+            point(point: $p) { x y }
+            points(points: [{x: 1, y: 2}, null]) { x y }
+        }"#;
+    assert_eq!(
+        schema
+            .execute(
+                Request::new(query).variables(Variables::from_json(serde_json::json!({
+                    "p": { "x": 5 },
+                })))
+            )
+            .await
+            .into_result()
+            .unwrap()
+            .data,
+        serde_json::json!({
+            "point": { "x": 5, "y": 10 },
+            "points": [{ "x": 1, "y": 2 }, null],
+        })
+    );
+
+    let query = r#"{
+            point: __type(name: "Point") { kind }
+            pointInput: __type(name: "PointInput") { kind }
+            root: __type(name: "Root") {
+                fields {
+                    name
+                    args { type { kind ofType { kind name ofType { kind name } } } }
+                }
+            }
+        }"#;
+    assert_eq!(
+        schema.execute(query).await.into_result().unwrap().data,
+        serde_json::json!({
+            "point": { "kind": "OBJECT" },
+            "pointInput": { "kind": "INPUT_OBJECT" },
+            "root": {
+                "fields": [
+                    {
+                        "name": "point",
+                        "args": [{ "type": { "kind": "NON_NULL", "ofType": { "kind": "INPUT_OBJECT", "name": "PointInput", "ofType": null } } }],
+                    },
+                    {
+                        "name": "points",
+                        "args": [{ "type": { "kind": "NON_NULL", "ofType": { "kind": "LIST", "name": null, "ofType": { "kind": "INPUT_OBJECT", "name": "PointInput" } } } }],
+                    },
+                ]
+            }
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_input_object_and_simple_object_deprecation() {
+    #[derive(SimpleObject, InputObject)]
+    #[graphql(input_name = "PointInput")]
+    struct Point {
+        #[graphql(deprecation = "Use y")]
+        x: i32,
+        #[graphql(input_deprecation = "Use x")]
+        y: Option<i32>,
+    }
+
+    struct Root;
+
+    #[Object]
+    impl Root {
+        async fn point(&self, point: Point) -> Point {
+            point
+        }
+    }
+
+    // The required input field `x` is not deprecated.
+    let schema = Schema::new(Root, EmptyMutation, EmptySubscription);
+    assert!(schema.check().is_ok());
+
+    let sdl = Schema::<Root, EmptyMutation, EmptySubscription>::sdl();
+    assert!(sdl.contains("type Point {\n\tx: Int! @deprecated(reason: \"Use y\")\n\ty: Int\n}"));
+    assert!(
+        sdl.contains("input PointInput {\n\tx: Int!\n\ty: Int @deprecated(reason: \"Use x\")\n}")
+    );
+}
@@ -331,3 +331,202 @@ pub async fn test_interface_field_method() {
         })
     );
 }
+
+#[async_std::test]
+pub async fn test_interface_check() {
+    #[derive(SimpleObject)]
+    struct MyObj {
+        id: i32,
+        title: String,
+    }
+
+    #[derive(Interface)]
+    #[graphql(field(name = "id", type = "&i32"))]
+    enum Node {
+        MyObj(MyObj),
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn node(&self) -> Node {
+            MyObj {
+                id: 33,
+                title: "haha".to_string(),
+            }
+            .into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    assert_eq!(schema.check(), Ok(()));
+
+    #[derive(SimpleObject)]
+    struct BrokenObj {
+        #[graphql(name = "objId")]
+        id: i32,
+        #[graphql(name = "title")]
+        title_len: i32,
+        #[graphql(skip)]
+        title: String,
+    }
+
+    impl BrokenObj {
+        async fn title(&self, _: &Context<'_>) -> FieldResult<&String> {
+            Ok(&self.title)
+        }
+    }
+
+    #[derive(Interface)]
+    #[graphql(
+        field(name = "id", type = "&i32"),
+        field(name = "title", type = "&String")
+    )]
+    enum BrokenNode {
+        BrokenObj(BrokenObj),
+    }
+
+    struct BrokenQuery;
+
+    #[Object]
+    impl BrokenQuery {
+        async fn node(&self) -> BrokenNode {
+            BrokenObj {
+                id: 33,
+                title_len: 4,
+                title: "haha".to_string(),
+            }
+            .into()
+        }
+    }
+
+    let schema = Schema::new(BrokenQuery, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema.check(),
+        Err(vec![
+            SchemaError::MissingInterfaceField {
+                ty: "BrokenObj".to_string(),
+                interface: "BrokenNode".to_string(),
+                field: "id".to_string(),
+            },
+            SchemaError::InterfaceFieldTypeMismatch {
+                ty: "BrokenObj".to_string(),
+                interface: "BrokenNode".to_string(),
+                field: "title".to_string(),
+                expect: "String!".to_string(),
+                actual: "Int!".to_string(),
+            },
+        ])
+    );
+}
+
+#[async_std::test]
+pub async fn test_interface_implements_interface() {
+    #[derive(SimpleObject)]
+    struct MyObj {
+        id: i32,
+        title: String,
+    }
+
+    #[derive(Interface)]
+    #[graphql(field(name = "id", type = "&i32"))]
+    enum Entity {
+        MyObj(MyObj),
+    }
+
+    #[derive(Interface)]
+    #[graphql(
+        implements = "Entity",
+        field(name = "id", type = "&i32"),
+        field(name = "title", type = "&String")
+    )]
+    enum Node {
+        MyObj(MyObj),
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn node(&self) -> Node {
+            MyObj {
+                id: 33,
+                title: "haha".to_string(),
+            }
+            .into()
+        }
+
+        async fn entity(&self) -> Entity {
+            MyObj {
+                id: 34,
+                title: "hehe".to_string(),
+            }
+            .into()
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    assert_eq!(schema.check(), Ok(()));
+    assert!(Schema::<Query, EmptyMutation, EmptySubscription>::sdl()
+        .contains("interface Node implements Entity {"));
+
+    let query = r#"{
+            node { ...EntityFields ... on Node { title } }
+            entity { ... on Node { title } }
+            __type(name: "Node") { interfaces { name } possibleTypes { name } }
+        }
+
+        fragment EntityFields on Entity { id }"#;
+    assert_eq!(
+        schema.execute(query).await.into_result().unwrap().data,
+        serde_json::json!({
+            "node": { "id": 33, "title": "haha" },
+            "entity": { "title": "hehe" },
+            "__type": {
+                "interfaces": [{ "name": "Entity" }],
+                "possibleTypes": [{ "name": "MyObj" }],
+            },
+        })
+    );
+
+    #[derive(Interface)]
+    #[graphql(
+        implements = "Entity",
+        field(name = "id", type = "&i32"),
+        field(name = "title", type = "&String")
+    )]
+    enum OtherNode {
+        MyObj(MyObj),
+        OtherObj(OtherObj),
+    }
+
+    #[derive(SimpleObject)]
+    struct OtherObj {
+        id: i32,
+        title: String,
+    }
+
+    struct OtherQuery;
+
+    #[Object]
+    impl OtherQuery {
+        async fn node(&self) -> OtherNode {
+            OtherObj {
+                id: 33,
+                title: "haha".to_string(),
+            }
+            .into()
+        }
+    }
+
+    let schema = Schema::new(OtherQuery, EmptyMutation, EmptySubscription);
+    assert_eq!(
+        schema.check(),
+        Err(vec![SchemaError::MissingTransitiveInterface {
+            ty: "OtherObj".to_string(),
+            interface: "OtherNode".to_string(),
+            transitive: "Entity".to_string(),
+        }])
+    );
+}
@@ -512,6 +512,85 @@ pub async fn test_introspection_deprecation() {
     assert_eq!(res, res_json);
 }
 
+#[async_std::test]
+pub async fn test_introspection_input_value_deprecation() {
+    /// Custom date
+    struct MyDate(String);
+
+    #[Scalar(specified_by_url = "https://tools.ietf.org/html/rfc3339")]
+    impl ScalarType for MyDate {
+        fn parse(value: Value) -> InputValueResult<Self> {
+            match value {
+                Value::String(s) => Ok(MyDate(s)),
+                _ => Err(InputValueError::ExpectedType(value)),
+            }
+        }
+
+        fn to_value(&self) -> Value {
+            Value::String(self.0.clone())
+        }
+    }
+
+    #[derive(InputObject)]
+    struct MyInput {
+        a: i32,
+        #[graphql(deprecation = "Use a")]
+        b: Option<i32>,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(
+            &self,
+            _input: MyInput,
+            #[graphql(deprecation = "Not used")] _old: Option<i32>,
+        ) -> MyDate {
+            MyDate("2020-01-01".to_string())
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let query = r#"{
+            query: __type(name: "Query") {
+                fields {
+                    args { name }
+                    allArgs: args(includeDeprecated: true) { name isDeprecated deprecationReason }
+                }
+            }
+            input: __type(name: "MyInput") {
+                inputFields { name }
+                allInputFields: inputFields(includeDeprecated: true) { name isDeprecated deprecationReason }
+            }
+            date: __type(name: "MyDate") { specifiedByURL }
+            int: __type(name: "Int") { specifiedByURL }
+        }"#;
+    assert_eq!(
+        schema.execute(query).await.into_result().unwrap().data,
+        serde_json::json!({
+            "query": {
+                "fields": [{
+                    "args": [{ "name": "input" }],
+                    "allArgs": [
+                        { "name": "input", "isDeprecated": false, "deprecationReason": null },
+                        { "name": "old", "isDeprecated": true, "deprecationReason": "Not used" },
+                    ],
+                }],
+            },
+            "input": {
+                "inputFields": [{ "name": "a" }],
+                "allInputFields": [
+                    { "name": "a", "isDeprecated": false, "deprecationReason": null },
+                    { "name": "b", "isDeprecated": true, "deprecationReason": "Use a" },
+                ],
+            },
+            "date": { "specifiedByURL": "https://tools.ietf.org/html/rfc3339" },
+            "int": { "specifiedByURL": null },
+        })
+    );
+}
+
 #[async_std::test]
 pub async fn test_introspection_type_kind() {
     let schema = Schema::new(Query, Mutation, EmptySubscription);
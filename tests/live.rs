@@ -0,0 +1,135 @@
+use async_graphql::*;
+use futures::{FutureExt, StreamExt};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+type Names = Arc<Mutex<Vec<String>>>;
+
+struct User {
+    id: usize,
+}
+
+#[Object]
+impl User {
+    async fn id(&self) -> ID {
+        self.id.into()
+    }
+
+    async fn name(&self, ctx: &Context<'_>) -> String {
+        ctx.data_unchecked::<Names>().lock().unwrap()[self.id].clone()
+    }
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn user(&self, id: i32) -> User {
+        User { id: id as usize }
+    }
+
+    async fn value(&self) -> i32 {
+        10
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn rename(&self, ctx: &Context<'_>, id: i32, name: String) -> bool {
+        ctx.data_unchecked::<Names>().lock().unwrap()[id as usize] = name;
+        ctx.invalidate_live_queries(&format!("User:{}", id));
+        true
+    }
+}
+
+#[async_std::test]
+pub async fn test_live_query() {
+    let names: Names = Arc::new(Mutex::new(vec!["a".to_string(), "b".to_string()]));
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(names.clone())
+        .finish();
+
+    let mut stream = schema.execute_stream("query @live { user(id: 0) { id name } }");
+    assert_eq!(
+        stream.next().await.unwrap().data,
+        json!({ "user": { "id": "0", "name": "a" } })
+    );
+
+    // The query is executed again when the user is invalidated.
+    assert!(schema
+        .execute(r#"mutation { rename(id: 0, name: "c") }"#)
+        .await
+        .is_ok());
+    assert_eq!(
+        stream.next().await.unwrap().data,
+        json!({ "user": { "id": "0", "name": "c" } })
+    );
+
+    // Another user is invalidated.
+    assert!(schema
+        .execute(r#"mutation { rename(id: 1, name: "d") }"#)
+        .await
+        .is_ok());
+    assert!(stream.next().now_or_never().is_none());
+
+    // The result is unchanged.
+    schema.invalidate_live_queries("User");
+    assert!(stream.next().now_or_never().is_none());
+
+    // Invalidated by type.
+    names.lock().unwrap()[0] = "e".to_string();
+    schema.invalidate_live_queries("User");
+    assert_eq!(
+        stream.next().await.unwrap().data,
+        json!({ "user": { "id": "0", "name": "e" } })
+    );
+}
+
+#[async_std::test]
+pub async fn test_not_live_query() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+
+    let mut stream = schema.execute_stream("{ value }");
+    assert_eq!(stream.next().await.unwrap().data, json!({ "value": 10 }));
+    assert!(stream.next().await.is_none());
+
+    // The directive is ignored by `execute`.
+    assert_eq!(
+        schema.execute("query @live { value }").await.data,
+        json!({ "value": 10 })
+    );
+}
+
+#[cfg(feature = "apollo_tracing")]
+#[async_std::test]
+pub async fn test_live_query_extensions() {
+    use async_graphql::extensions::ApolloTracing;
+
+    let names: Names = Arc::new(Mutex::new(vec!["a".to_string()]));
+    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(names.clone())
+        .extension(ApolloTracing)
+        .finish();
+
+    // Each execution only traces its own resolvers.
+    let resolvers = |resp: Response| {
+        resp.extensions.unwrap()["tracing"]["execution"]["resolvers"]
+            .as_array()
+            .unwrap()
+            .len()
+    };
+    let mut stream = schema.execute_stream("query @live { user(id: 0) { id name } }");
+    assert_eq!(resolvers(stream.next().await.unwrap()), 3);
+    for name in &["b", "c"] {
+        names.lock().unwrap()[0] = name.to_string();
+        schema.invalidate_live_queries("User:0");
+        let resp = stream.next().await.unwrap();
+        assert_eq!(
+            resp.data,
+            json!({ "user": { "id": "0", "name": name.to_string() } })
+        );
+        assert_eq!(resolvers(resp), 3);
+    }
+}
@@ -0,0 +1,186 @@
+#![cfg(feature = "log")]
+
+use async_graphql::extensions::{Logger, LoggerField};
+use async_graphql::*;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::Duration;
+
+struct Capture;
+
+static RECORDS: Lazy<Mutex<Vec<(Level, String)>>> = Lazy::new(Default::default);
+
+impl Log for Capture {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target() == "async-graphql" {
+            RECORDS
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Returns the records that contain the operation name, as the tests run concurrently.
+fn take_records(operation_name: &str) -> Vec<(Level, String)> {
+    let mut records = RECORDS.lock().unwrap();
+    let (taken, rest) = records
+        .drain(..)
+        .partition(|(_, message)| message.contains(operation_name));
+    *records = rest;
+    taken
+}
+
+fn init() {
+    log::set_logger(&Capture).ok();
+    log::set_max_level(LevelFilter::Info);
+}
+
+#[derive(InputObject)]
+struct LoginInput {
+    username: String,
+    password: String,
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn login(&self, input: LoginInput, token: String) -> bool {
+        input.username == "admin" && input.password == "123456" && token == "abc"
+    }
+
+    async fn error(&self) -> FieldResult<i32> {
+        Err("oops".into())
+    }
+
+    async fn obj(&self) -> MyObj {
+        MyObj
+    }
+}
+
+struct MyObj;
+
+#[Object]
+impl MyObj {
+    async fn error(&self) -> FieldResult<i32> {
+        Err("oops".into())
+    }
+}
+
+#[async_std::test]
+pub async fn test_logger_text() {
+    init();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Logger::default().redact_variables("(?i)password|token"))
+        .finish();
+
+    let query = "query TextLogin($input: LoginInput!, $token: String!) { login(input: $input, token: $token) }";
+    let resp = schema
+        .execute(
+            Request::new(query).variables(Variables::from_json(serde_json::json!({
+                "input": { "username": "admin", "password": "123456" },
+                "token": "abc",
+            }))),
+        )
+        .await;
+    assert_eq!(resp.data, serde_json::json!({ "login": true }));
+
+    let records = take_records("TextLogin");
+    assert_eq!(records.len(), 1);
+    let (level, message) = &records[0];
+    assert_eq!(*level, Level::Info);
+    assert!(message.starts_with("[Query] operation: \"TextLogin\", query: \"query TextLogin"));
+    assert!(message.contains("duration: "));
+    assert!(!message.contains("123456"));
+    assert!(!message.contains("abc"));
+    assert!(message.contains("admin"));
+
+    assert!(schema
+        .execute("query TextError { obj { error } }")
+        .await
+        .is_err());
+    let records = take_records("TextError");
+    assert_eq!(records.len(), 1);
+    let (level, message) = &records[0];
+    assert_eq!(*level, Level::Error);
+    assert!(message
+        .starts_with("[QueryError] path: \"obj.error\", pos: [1:25], operation: \"TextError\""));
+}
+
+#[async_std::test]
+pub async fn test_logger_json() {
+    init();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(
+            Logger::default()
+                .fields(vec![LoggerField::OperationName, LoggerField::Variables])
+                .redact_variables("^token$")
+                .json(),
+        )
+        .finish();
+
+    let query = "query JsonLogin($input: LoginInput!, $token: String!) { login(input: $input, token: $token) }";
+    schema
+        .execute(
+            Request::new(query).variables(Variables::from_json(serde_json::json!({
+                "input": { "username": "admin", "password": "123456" },
+                "token": "abc",
+            }))),
+        )
+        .await;
+    let records = take_records("JsonLogin");
+    assert_eq!(records.len(), 1);
+    let record: serde_json::Value = serde_json::from_str(&records[0].1).unwrap();
+    assert_eq!(
+        record,
+        serde_json::json!({
+            "type": "query",
+            "operationName": "JsonLogin",
+            "variables": {
+                "input": { "username": "admin", "password": "123456" },
+                "token": "******",
+            },
+        })
+    );
+
+    schema.execute("query JsonError { obj { error } }").await;
+    let records = take_records("JsonError");
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].0, Level::Error);
+    let record: serde_json::Value = serde_json::from_str(&records[0].1).unwrap();
+    assert_eq!(
+        record,
+        serde_json::json!({
+            "type": "query_error",
+            "operationName": "JsonError",
+            "variables": {},
+            "message": "Failed to resolve field: oops",
+            "locations": [{ "line": 1, "column": 25 }],
+            "path": ["obj", "error"],
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_logger_slow_threshold() {
+    init();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Logger::default().slow_threshold(Duration::from_secs(60)))
+        .finish();
+
+    schema
+        .execute("query SlowFast { obj { __typename } }")
+        .await;
+    assert!(take_records("SlowFast").is_empty());
+
+    schema.execute("query SlowError { error }").await;
+    assert_eq!(take_records("SlowError").len(), 1);
+}
@@ -0,0 +1,169 @@
+use async_graphql::extensions::Metrics;
+use async_graphql::*;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn values(&self) -> Vec<MyObj> {
+        vec![MyObj, MyObj]
+    }
+
+    async fn error(&self) -> FieldResult<i32> {
+        Err("oops".into())
+    }
+}
+
+struct MyObj;
+
+#[Object]
+impl MyObj {
+    async fn value(&self) -> i32 {
+        20
+    }
+}
+
+fn lines(metrics: &Metrics) -> Vec<String> {
+    metrics.render().lines().map(ToString::to_string).collect()
+}
+
+#[async_std::test]
+pub async fn test_metrics() {
+    let metrics = Metrics::new().buckets(vec![1.0, 0.5]);
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(metrics.clone())
+        .finish();
+
+    assert!(schema
+        .execute("query A { value a: values { value } }")
+        .await
+        .is_ok());
+    assert!(schema.execute("query A { value }").await.is_ok());
+    assert!(schema.execute("{ value error }").await.is_err());
+
+    let lines = lines(&metrics);
+    for line in &[
+        "# TYPE graphql_operation_requests_total counter",
+        r#"graphql_operation_requests_total{operation="A"} 2"#,
+        r#"graphql_operation_requests_total{operation="{value,error}"} 1"#,
+        r#"graphql_operation_errors_total{operation="A"} 0"#,
+        r#"graphql_operation_errors_total{operation="{value,error}"} 1"#,
+        "# TYPE graphql_operation_duration_seconds histogram",
+        r#"graphql_operation_duration_seconds_bucket{operation="A",le="0.5"} 2"#,
+        r#"graphql_operation_duration_seconds_bucket{operation="A",le="1"} 2"#,
+        r#"graphql_operation_duration_seconds_bucket{operation="A",le="+Inf"} 2"#,
+        r#"graphql_operation_duration_seconds_count{operation="A"} 2"#,
+        r#"graphql_root_field_requests_total{field="Query.value"} 3"#,
+        r#"graphql_root_field_requests_total{field="Query.values"} 1"#,
+        r#"graphql_root_field_errors_total{field="Query.error"} 1"#,
+        r#"graphql_field_resolves_total{field="MyObj.value"} 2"#,
+        r#"graphql_field_resolves_total{field="Query.value"} 3"#,
+        r#"graphql_field_errors_total{field="Query.error"} 1"#,
+        r#"graphql_field_errors_total{field="Query.value"} 0"#,
+        r#"graphql_field_duration_seconds_count{field="MyObj.value"} 2"#,
+    ] {
+        assert!(lines.contains(&line.to_string()), "missing line: {}", line);
+    }
+}
+
+#[async_std::test]
+pub async fn test_metrics_max_unnamed_operations() {
+    let metrics = Metrics::new().max_unnamed_operations(1);
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(metrics.clone())
+        .finish();
+
+    schema.execute("{ value }").await;
+    schema.execute("{ values { value } }").await;
+    schema.execute("{ error }").await;
+    schema.execute("{ value }").await;
+    schema.execute("query B { value }").await;
+
+    let lines = lines(&metrics);
+    for line in &[
+        r#"graphql_operation_requests_total{operation="B"} 1"#,
+        r#"graphql_operation_requests_total{operation="{...}"} 2"#,
+        r#"graphql_operation_requests_total{operation="{value}"} 2"#,
+    ] {
+        assert!(lines.contains(&line.to_string()), "missing line: {}", line);
+    }
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.starts_with("graphql_operation_requests_total"))
+            .count(),
+        3
+    );
+}
+
+#[async_std::test]
+pub async fn test_metrics_unknown_operation_name() {
+    let metrics = Metrics::new();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(metrics.clone())
+        .finish();
+
+    // The operation names are sent by the client, only those of the document are labels.
+    schema
+        .execute(Request::new("query B { value }").operation_name("B"))
+        .await;
+    schema
+        .execute(Request::new("query B { value }").operation_name("C"))
+        .await;
+    schema
+        .execute(Request::new("{ value }").operation_name("D"))
+        .await;
+    schema
+        .execute(Request::new("{ value").operation_name("E"))
+        .await;
+
+    let lines = lines(&metrics);
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.starts_with("graphql_operation_requests_total"))
+            .cloned()
+            .collect::<Vec<_>>(),
+        vec![
+            r#"graphql_operation_requests_total{operation="B"} 1"#,
+            r#"graphql_operation_requests_total{operation="{...}"} 3"#,
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_metrics_buckets() {
+    let metrics = Metrics::new().buckets(vec![1.0, f64::NAN, 0.5, f64::INFINITY, 1.0]);
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(metrics.clone())
+        .finish();
+    schema.execute("query A { value }").await;
+
+    let lines = lines(&metrics);
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.starts_with("graphql_operation_duration_seconds_bucket"))
+            .cloned()
+            .collect::<Vec<_>>(),
+        vec![
+            r#"graphql_operation_duration_seconds_bucket{operation="A",le="0.5"} 1"#,
+            r#"graphql_operation_duration_seconds_bucket{operation="A",le="1"} 1"#,
+            r#"graphql_operation_duration_seconds_bucket{operation="A",le="+Inf"} 1"#,
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "The options of the metrics extension must be set before it is used")]
+pub fn test_metrics_options_after_clone() {
+    let metrics = Metrics::new();
+    let _schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(metrics.clone())
+        .finish();
+    let _ = metrics.buckets(vec![1.0]);
+}
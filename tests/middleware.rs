@@ -0,0 +1,416 @@
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, Middleware, MiddlewareFactory, NextExecute,
+    NextParse, NextRequest, NextResolve, NextSubscribe, NextValidate, ResolveInfo,
+};
+use async_graphql::parser::types::ExecutableDocument;
+use async_graphql::*;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use std::sync::{Arc, Mutex};
+
+type Calls = Arc<Mutex<Vec<String>>>;
+
+struct Recorder {
+    name: &'static str,
+    calls: Calls,
+}
+
+impl MiddlewareFactory for Recorder {
+    fn create(&self) -> Box<dyn Middleware> {
+        Box::new(Recorder {
+            name: self.name,
+            calls: self.calls.clone(),
+        })
+    }
+}
+
+impl Recorder {
+    fn record(&self, phase: &str) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("{}.{}", self.name, phase));
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for Recorder {
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    async fn request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextRequest<'_>,
+    ) -> Response {
+        self.record("request_start");
+        let resp = next.run(ctx, request).await;
+        self.record("request_end");
+        resp
+    }
+
+    async fn parse(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParse<'_>,
+    ) -> Result<ExecutableDocument> {
+        self.record("parse");
+        next.run(ctx, query, variables).await
+    }
+
+    async fn validate(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidate<'_>,
+    ) -> Result<ValidationResult> {
+        self.record("validate");
+        next.run(ctx).await
+    }
+
+    async fn execute(&self, ctx: &ExtensionContext<'_>, next: NextExecute<'_>) -> Response {
+        self.record("execute");
+        next.run(ctx).await
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> Result<serde_json::Value> {
+        self.record(&format!("resolve({})", info.path_node));
+        next.run(ctx, info).await
+    }
+}
+
+#[async_std::test]
+pub async fn test_middleware_order() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    let calls = Calls::default();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Recorder {
+            name: "a",
+            calls: calls.clone(),
+        })
+        .middleware(Recorder {
+            name: "b",
+            calls: calls.clone(),
+        })
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ value }").await.data,
+        serde_json::json!({ "value": 10 })
+    );
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "a.request_start",
+            "b.request_start",
+            "a.parse",
+            "b.parse",
+            "a.validate",
+            "b.validate",
+            "a.execute",
+            "b.execute",
+            "a.resolve(value)",
+            "b.resolve(value)",
+            "b.request_end",
+            "a.request_end",
+        ]
+    );
+}
+
+struct ExtensionRecorder(Calls);
+
+impl ExtensionFactory for ExtensionRecorder {
+    fn create(&self) -> Box<dyn Extension> {
+        Box::new(ExtensionRecorder(self.0.clone()))
+    }
+}
+
+impl ExtensionRecorder {
+    fn record(&self, phase: String) {
+        self.0.lock().unwrap().push(format!("ext.{}", phase));
+    }
+}
+
+impl Extension for ExtensionRecorder {
+    fn resolve_hooks(&self) -> bool {
+        true
+    }
+
+    fn parse_start(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        _query_source: &str,
+        _variables: &Variables,
+    ) {
+        self.record("parse_start".to_string());
+    }
+
+    fn parse_end(&self, _ctx: &ExtensionContext<'_>, _document: &ExecutableDocument) {
+        self.record("parse_end".to_string());
+    }
+
+    fn validation_start(&self, _ctx: &ExtensionContext<'_>) {
+        self.record("validation_start".to_string());
+    }
+
+    fn validation_end(&self, _ctx: &ExtensionContext<'_>) {
+        self.record("validation_end".to_string());
+    }
+
+    fn execution_start(&self, _ctx: &ExtensionContext<'_>) {
+        self.record("execution_start".to_string());
+    }
+
+    fn execution_end(&self, _ctx: &ExtensionContext<'_>) {
+        self.record("execution_end".to_string());
+    }
+
+    fn resolve_start(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+    ) -> extensions::ResolveState {
+        self.record(format!("resolve_start({})", info.path_node));
+        None
+    }
+
+    fn resolve_end(
+        &self,
+        _ctx: &ExtensionContext<'_>,
+        info: &ResolveInfo<'_>,
+        _state: extensions::ResolveState,
+    ) {
+        self.record(format!("resolve_end({})", info.path_node));
+    }
+}
+
+#[async_std::test]
+pub async fn test_middleware_extension_adapter() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    let calls = Calls::default();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension(ExtensionRecorder(calls.clone()))
+        .middleware(Recorder {
+            name: "a",
+            calls: calls.clone(),
+        })
+        .finish();
+
+    assert_eq!(
+        schema.execute("{ value }").await.data,
+        serde_json::json!({ "value": 10 })
+    );
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "a.request_start",
+            "a.parse",
+            "ext.parse_start",
+            "ext.parse_end",
+            "a.validate",
+            "ext.validation_start",
+            "ext.validation_end",
+            "a.execute",
+            "ext.execution_start",
+            "a.resolve(value)",
+            "ext.resolve_start(value)",
+            "ext.resolve_end(value)",
+            "ext.execution_end",
+            "a.request_end",
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_middleware_short_circuit() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn value(&self) -> i32 {
+            unreachable!()
+        }
+    }
+
+    struct Cached;
+
+    #[async_trait::async_trait]
+    impl Middleware for Cached {
+        async fn request(
+            &self,
+            ctx: &ExtensionContext<'_>,
+            request: Request,
+            next: NextRequest<'_>,
+        ) -> Response {
+            if request.query == "{ value }" {
+                Response::new(serde_json::json!({ "value": 100 }))
+            } else {
+                next.run(ctx, request).await
+            }
+        }
+    }
+
+    impl MiddlewareFactory for Cached {
+        fn create(&self) -> Box<dyn Middleware> {
+            Box::new(Cached)
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Cached)
+        .finish();
+    assert_eq!(
+        schema.execute("{ value }").await.data,
+        serde_json::json!({ "value": 100 })
+    );
+}
+
+#[async_std::test]
+pub async fn test_middleware_modify_resolved_value() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn name(&self) -> &str {
+            "abc"
+        }
+
+        async fn names(&self) -> Vec<&str> {
+            vec!["a", "b"]
+        }
+
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    /// Uppercases the resolved strings if the flag, returned by `resolve_hooks`, is set.
+    struct Uppercase(bool);
+
+    #[async_trait::async_trait]
+    impl Middleware for Uppercase {
+        fn resolve_hooks(&self) -> bool {
+            self.0
+        }
+
+        async fn resolve(
+            &self,
+            ctx: &ExtensionContext<'_>,
+            info: &ResolveInfo<'_>,
+            next: NextResolve<'_>,
+        ) -> Result<serde_json::Value> {
+            async_std::task::yield_now().await;
+            match next.run(ctx, info).await? {
+                serde_json::Value::String(s) => Ok(s.to_uppercase().into()),
+                value => Ok(value),
+            }
+        }
+    }
+
+    impl MiddlewareFactory for Uppercase {
+        fn create(&self) -> Box<dyn Middleware> {
+            Box::new(Uppercase(self.0))
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Uppercase(true))
+        .finish();
+    assert_eq!(
+        schema.execute("{ name names value }").await.data,
+        serde_json::json!({
+            "name": "ABC",
+            "names": ["A", "B"],
+            "value": 10,
+        })
+    );
+
+    // The resolve hook is skipped if the middleware does not opt in.
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(Uppercase(false))
+        .finish();
+    assert_eq!(
+        schema.execute("{ name names value }").await.data,
+        serde_json::json!({
+            "name": "abc",
+            "names": ["a", "b"],
+            "value": 10,
+        })
+    );
+}
+
+#[async_std::test]
+pub async fn test_middleware_subscribe() {
+    struct Query;
+
+    #[Object]
+    impl Query {}
+
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        async fn values(&self) -> impl Stream<Item = i32> {
+            futures::stream::iter(0..3)
+        }
+    }
+
+    struct Take(usize);
+
+    impl Middleware for Take {
+        fn subscribe<'s>(
+            &self,
+            ctx: &ExtensionContext<'_>,
+            stream: BoxStream<'s, Response>,
+            next: NextSubscribe<'_>,
+        ) -> BoxStream<'s, Response> {
+            next.run(ctx, stream.take(self.0).boxed())
+        }
+    }
+
+    impl MiddlewareFactory for Take {
+        fn create(&self) -> Box<dyn Middleware> {
+            Box::new(Take(self.0))
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
+        .middleware(Take(2))
+        .finish();
+    let values = schema
+        .execute_stream("subscription { values }")
+        .map(|resp| resp.into_result().unwrap().data)
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        values,
+        vec![
+            serde_json::json!({ "values": 0 }),
+            serde_json::json!({ "values": 1 }),
+        ]
+    );
+}
@@ -0,0 +1,230 @@
+#![cfg(feature = "opentelemetry")]
+
+use async_graphql::extensions::OpenTelemetry;
+use async_graphql::*;
+use futures::{Stream, StreamExt};
+use opentelemetry::metrics::MeterProvider as _;
+use opentelemetry::trace::{SpanKind, Status, TracerProvider as _};
+use opentelemetry::{Key, Value as AttributeValue};
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::metrics::data::{Histogram, Sum};
+use opentelemetry_sdk::metrics::{MeterProvider, PeriodicReader};
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn value(&self) -> i32 {
+        10
+    }
+
+    async fn error(&self) -> FieldResult<i32> {
+        Err("oops".into())
+    }
+}
+
+fn attribute<'a>(span: &'a SpanData, key: &'static str) -> Option<&'a AttributeValue> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key == Key::from_static_str(key))
+        .map(|kv| &kv.value)
+}
+
+fn find_span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+    spans.iter().find(|span| span.name == name).unwrap()
+}
+
+#[async_std::test]
+pub async fn test_opentelemetry_spans() {
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(OpenTelemetry::new(provider.tracer("test")))
+        .finish();
+    let resp = schema
+        .execute(Request::new("query A { value } query B { value }").operation_name("A"))
+        .await;
+    assert_eq!(resp.data, serde_json::json!({ "value": 10 }));
+
+    provider.force_flush();
+    let spans = exporter.get_finished_spans().unwrap();
+    let request = find_span(&spans, "query A");
+    assert_eq!(request.span_kind, SpanKind::Server);
+    assert_eq!(request.status, Status::Unset);
+    assert_eq!(
+        attribute(request, "graphql.operation.name"),
+        Some(&AttributeValue::from("A"))
+    );
+    assert_eq!(
+        attribute(request, "graphql.operation.type"),
+        Some(&AttributeValue::from("query"))
+    );
+    assert!(attribute(request, "graphql.document").is_some());
+
+    for name in &["parse", "validation", "execute"] {
+        assert_eq!(
+            find_span(&spans, name).parent_span_id,
+            request.span_context.span_id()
+        );
+    }
+    let field = find_span(&spans, "field");
+    assert_eq!(
+        field.parent_span_id,
+        find_span(&spans, "execute").span_context.span_id()
+    );
+    assert_eq!(
+        attribute(field, "graphql.field.path"),
+        Some(&AttributeValue::from("value"))
+    );
+    assert_eq!(
+        attribute(field, "graphql.field.parent_type"),
+        Some(&AttributeValue::from("Query"))
+    );
+    assert_eq!(
+        attribute(field, "graphql.field.return_type"),
+        Some(&AttributeValue::from("Int!"))
+    );
+}
+
+#[async_std::test]
+pub async fn test_opentelemetry_errors() {
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(OpenTelemetry::new(provider.tracer("test")))
+        .finish();
+    assert!(schema.execute("{ error }").await.is_err());
+
+    provider.force_flush();
+    let spans = exporter.get_finished_spans().unwrap();
+    for name in &["query", "field"] {
+        let span = find_span(&spans, name);
+        assert!(matches!(span.status, Status::Error { .. }));
+        let event = span.events.iter().next().unwrap();
+        assert_eq!(event.name, "exception");
+        assert!(event.attributes[0].value.as_str().contains("oops"));
+    }
+
+    exporter.reset();
+    assert!(schema.execute("{ error").await.is_err());
+    provider.force_flush();
+    let spans = exporter.get_finished_spans().unwrap();
+    assert!(matches!(
+        find_span(&spans, "parse").status,
+        Status::Error { .. }
+    ));
+    assert!(matches!(
+        find_span(&spans, "GraphQL Operation").status,
+        Status::Error { .. }
+    ));
+}
+
+#[async_std::test]
+pub async fn test_opentelemetry_subscription() {
+    struct Subscription;
+
+    #[Subscription]
+    impl Subscription {
+        async fn values(&self) -> impl Stream<Item = i32> {
+            futures::stream::iter(0..3)
+        }
+    }
+
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+
+    let schema = Schema::build(Query, EmptyMutation, Subscription)
+        .middleware(OpenTelemetry::new(provider.tracer("test")))
+        .finish();
+    let responses = schema
+        .execute_stream("subscription { values }")
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(responses.len(), 3);
+
+    provider.force_flush();
+    let spans = exporter.get_finished_spans().unwrap();
+    let subscription = find_span(&spans, "subscription");
+    assert_eq!(subscription.span_kind, SpanKind::Server);
+    assert_eq!(
+        attribute(subscription, "graphql.operation.type"),
+        Some(&AttributeValue::from("subscription"))
+    );
+    for name in &["parse", "validation"] {
+        assert_eq!(
+            find_span(&spans, name).parent_span_id,
+            subscription.span_context.span_id()
+        );
+    }
+    let events = spans
+        .iter()
+        .filter(|span| span.name == "subscription event")
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 3);
+    for event in events {
+        assert_eq!(event.parent_span_id, subscription.span_context.span_id());
+        assert!(event.start_time >= subscription.start_time);
+        assert!(event.end_time <= subscription.end_time);
+    }
+}
+
+#[async_std::test]
+pub async fn test_opentelemetry_metrics() {
+    let exporter = InMemoryMetricsExporter::default();
+    let meter_provider = MeterProvider::builder()
+        .with_reader(PeriodicReader::builder(exporter.clone(), runtime::AsyncStd).build())
+        .build();
+    let tracer_provider = TracerProvider::builder().build();
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .middleware(
+            OpenTelemetry::new(tracer_provider.tracer("test"))
+                .with_meter(&meter_provider.meter("test")),
+        )
+        .finish();
+    assert!(schema.execute("{ value }").await.is_ok());
+    assert!(schema.execute("{ error }").await.is_err());
+    meter_provider.force_flush().unwrap();
+
+    let metrics = exporter.get_finished_metrics().unwrap();
+    let metrics = &metrics.last().unwrap().scope_metrics[0].metrics;
+
+    let duration = metrics
+        .iter()
+        .find(|metric| metric.name == "graphql.request.duration")
+        .unwrap();
+    assert_eq!(duration.unit.as_str(), "s");
+    let histogram = duration
+        .data
+        .as_any()
+        .downcast_ref::<Histogram<f64>>()
+        .unwrap();
+    assert_eq!(
+        histogram
+            .data_points
+            .iter()
+            .map(|point| point.count)
+            .sum::<u64>(),
+        2
+    );
+
+    let errors = metrics
+        .iter()
+        .find(|metric| metric.name == "graphql.request.errors")
+        .unwrap();
+    let sum = errors.data.as_any().downcast_ref::<Sum<u64>>().unwrap();
+    assert_eq!(sum.data_points.len(), 1);
+    assert_eq!(sum.data_points[0].value, 1);
+}
@@ -0,0 +1,133 @@
+use async_graphql::pubsub::{LagPolicy, PubSub, SimpleBroker};
+use async_graphql::*;
+use futures::{FutureExt, Stream, StreamExt};
+use std::time::Duration;
+
+#[async_std::test]
+pub async fn test_pubsub_subscription() {
+    #[derive(SimpleObject, Clone)]
+    struct Event {
+        value: i32,
+    }
+
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn value(&self) -> i32 {
+            10
+        }
+    }
+
+    struct MutationRoot;
+
+    #[Object]
+    impl MutationRoot {
+        async fn publish(&self, ctx: &Context<'_>, value: i32) -> FieldResult<bool> {
+            let broker = ctx.data_unchecked::<Box<dyn PubSub<Event>>>();
+            broker.publish(Event { value }).await?;
+            Ok(true)
+        }
+    }
+
+    struct SubscriptionRoot;
+
+    #[Subscription]
+    impl SubscriptionRoot {
+        async fn events(&self, ctx: &Context<'_>) -> impl Stream<Item = Event> {
+            ctx.data_unchecked::<Box<dyn PubSub<Event>>>().subscribe()
+        }
+    }
+
+    let broker = SimpleBroker::<Event>::new();
+    let schema = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(Box::new(broker.clone()) as Box<dyn PubSub<Event>>)
+        .finish();
+
+    let mut stream1 = schema.execute_stream("subscription { events { value } }");
+    let mut stream2 = schema.execute_stream("subscription { events { value } }");
+    // The subscription resolvers run on the first poll.
+    assert!(stream1.next().now_or_never().is_none());
+    assert!(stream2.next().now_or_never().is_none());
+    assert_eq!(broker.subscriber_count(), 2);
+
+    for i in 0..3 {
+        assert!(schema
+            .execute(format!("mutation {{ publish(value: {}) }}", i))
+            .await
+            .is_ok());
+    }
+    for stream in [&mut stream1, &mut stream2].iter_mut() {
+        for i in 0..3 {
+            assert_eq!(
+                stream.next().await.unwrap().data,
+                serde_json::json!({ "events": { "value": i } })
+            );
+        }
+    }
+
+    drop(stream1);
+    assert_eq!(broker.subscriber_count(), 1);
+    drop(stream2);
+    assert_eq!(broker.subscriber_count(), 0);
+}
+
+#[async_std::test]
+pub async fn test_pubsub_lag_policy() {
+    async fn published(policy: LagPolicy) -> Vec<i32> {
+        let broker = SimpleBroker::new().capacity(2).lag_policy(policy);
+        let mut stream = broker.subscribe();
+        for i in 0..4 {
+            broker.publish(i).await;
+        }
+        let mut values = Vec::new();
+        while let Some(Some(value)) = stream.next().now_or_never() {
+            values.push(value);
+        }
+        values
+    }
+
+    assert_eq!(published(LagPolicy::DropOldest).await, vec![2, 3]);
+    assert_eq!(published(LagPolicy::DropNewest).await, vec![0, 1]);
+    assert_eq!(published(LagPolicy::Disconnect).await, vec![0, 1]);
+}
+
+#[async_std::test]
+pub async fn test_pubsub_disconnect() {
+    let broker = SimpleBroker::new()
+        .capacity(1)
+        .lag_policy(LagPolicy::Disconnect);
+    let mut stream = broker.subscribe();
+    broker.publish(1).await;
+    broker.publish(2).await;
+    assert_eq!(broker.subscriber_count(), 0);
+    assert_eq!(stream.next().await, Some(1));
+    assert_eq!(stream.next().await, None);
+}
+
+#[async_std::test]
+pub async fn test_pubsub_wait() {
+    let broker = SimpleBroker::new().capacity(1).lag_policy(LagPolicy::Wait);
+    let mut stream = broker.subscribe();
+
+    let publisher = async_std::task::spawn({
+        let broker = broker.clone();
+        async move {
+            for i in 0..3 {
+                broker.publish(i).await;
+            }
+        }
+    });
+    async_std::task::sleep(Duration::from_millis(50)).await;
+    // The publisher waits for the subscriber.
+    assert_eq!(stream.next().await, Some(0));
+    assert_eq!(stream.next().await, Some(1));
+    assert_eq!(stream.next().await, Some(2));
+    publisher.await;
+
+    // A dropped subscriber doesn't block the publishers.
+    broker.publish(3).await;
+    drop(stream);
+    broker.publish(4).await;
+    broker.publish(5).await;
+}
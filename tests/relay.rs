@@ -0,0 +1,161 @@
+use async_graphql::relay::{GlobalId, GlobalIdError, NodeType};
+use async_graphql::*;
+use serde_json::json;
+
+struct User {
+    id: i32,
+}
+
+#[Object]
+impl User {
+    async fn id(&self) -> ID {
+        GlobalId::new::<Self>(self.id).into()
+    }
+
+    async fn name(&self) -> String {
+        format!("user{}", self.id)
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeType for User {
+    async fn fetch(_ctx: &Context<'_>, id: &str) -> FieldResult<Option<Self>> {
+        let id: i32 = id.parse()?;
+        Ok(if id < 10 { Some(User { id }) } else { None })
+    }
+}
+
+struct Post {
+    id: String,
+}
+
+#[Object]
+impl Post {
+    async fn id(&self) -> ID {
+        GlobalId::new::<Self>(&self.id).into()
+    }
+
+    async fn title(&self) -> &str {
+        &self.id
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeType for Post {
+    async fn fetch(_ctx: &Context<'_>, id: &str) -> FieldResult<Option<Self>> {
+        Ok(Some(Post { id: id.to_string() }))
+    }
+}
+
+#[derive(Interface)]
+#[graphql(node)]
+enum Node {
+    User(User),
+    Post(Post),
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn value(&self) -> i32 {
+        10
+    }
+}
+
+#[async_std::test]
+pub async fn test_global_id() {
+    let id = GlobalId::new::<User>(1);
+    assert_eq!(id.type_name(), "User");
+    assert_eq!(id.id(), "1");
+    assert_eq!(id.encode(), "VXNlcjox");
+    assert_eq!(ID::from(id.clone()), ID("VXNlcjox".to_string()));
+    assert_eq!(GlobalId::decode("VXNlcjox"), Ok(id));
+
+    assert_eq!(GlobalId::decode_as::<User, i32>("VXNlcjox"), Ok(1));
+    assert_eq!(
+        GlobalId::decode_as::<Post, i32>("VXNlcjox"),
+        Err(GlobalIdError::TypeMismatch {
+            expected: "Post".to_string(),
+            actual: "User".to_string(),
+        })
+    );
+    assert_eq!(
+        GlobalId::decode_as::<Post, i32>(&GlobalId::new::<Post>("a").encode()),
+        Err(GlobalIdError::InvalidId("a".to_string()))
+    );
+    assert_eq!(GlobalId::decode("!"), Err(GlobalIdError::Invalid));
+    assert_eq!(GlobalId::decode("VXNlcg=="), Err(GlobalIdError::Invalid));
+}
+
+#[async_std::test]
+pub async fn test_node() {
+    let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .node_interface::<Node>()
+        .finish();
+    let user_id = GlobalId::new::<User>(1).encode();
+    let post_id = GlobalId::new::<Post>("hello").encode();
+
+    let query = format!(
+        r#"{{
+            node(id: "{}") {{ id ... on User {{ name }} }}
+            nodes(ids: ["{}", "{}", "{}"]) {{
+                __typename
+                ... on User {{ name }}
+                ... on Post {{ title }}
+            }}
+        }}"#,
+        user_id,
+        post_id,
+        GlobalId::new::<User>(20).encode(),
+        GlobalId::with_type_name("Comment", 1).encode(),
+    );
+    assert_eq!(
+        schema.execute(&query).await.into_result().unwrap().data,
+        json!({
+            "node": { "id": user_id, "name": "user1" },
+            "nodes": [{ "__typename": "Post", "title": "hello" }, null, null],
+        })
+    );
+
+    assert!(schema
+        .execute(r#"{ node(id: "abc") { id } }"#)
+        .await
+        .is_err());
+
+    assert_eq!(
+        schema
+            .execute(r#"{ __type(name: "QueryRoot") { fields { name } } }"#)
+            .await
+            .data,
+        json!({ "__type": { "fields": [
+            { "name": "value" },
+            { "name": "node" },
+            { "name": "nodes" },
+        ] } })
+    );
+}
+
+#[async_std::test]
+pub async fn test_node_fields_not_registered() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, EmptySubscription);
+    assert!(schema
+        .execute(r#"{ node(id: "VXNlcjox") { id } }"#)
+        .await
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "already has a \"node\" field")]
+pub fn test_node_field_conflict() {
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {
+        async fn node(&self) -> i32 {
+            10
+        }
+    }
+
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription).node_interface::<Node>();
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "response_cache")]
+
+use async_graphql::extensions::response_cache::{LruCacheStorage, ResponseCache, SessionKey};
+use async_graphql::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+struct Counter(Arc<AtomicI32>);
+
+impl Counter {
+    fn get(&self) -> i32 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct Query;
+
+#[Object(cache_control(max_age = 60))]
+impl Query {
+    async fn value(&self, ctx: &Context<'_>, n: Option<i32>) -> i32 {
+        ctx.data_unchecked::<Counter>()
+            .0
+            .fetch_add(1, Ordering::SeqCst);
+        n.unwrap_or(10)
+    }
+
+    #[graphql(cache_control(private))]
+    async fn me(&self, ctx: &Context<'_>) -> String {
+        ctx.data_unchecked::<Counter>()
+            .0
+            .fetch_add(1, Ordering::SeqCst);
+        "me".to_string()
+    }
+
+    async fn obj(&self) -> MyObj {
+        MyObj
+    }
+}
+
+struct MyObj;
+
+#[Object(cache_control(max_age = 60))]
+impl MyObj {
+    async fn value(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data_unchecked::<Counter>()
+            .0
+            .fetch_add(1, Ordering::SeqCst);
+        20
+    }
+}
+
+struct UncachedQuery;
+
+#[Object]
+impl UncachedQuery {
+    async fn value(&self, ctx: &Context<'_>) -> i32 {
+        ctx.data_unchecked::<Counter>()
+            .0
+            .fetch_add(1, Ordering::SeqCst);
+        10
+    }
+}
+
+struct Mutation;
+
+#[Object]
+impl Mutation {
+    async fn action(&self, ctx: &Context<'_>) -> bool {
+        ctx.data_unchecked::<Counter>()
+            .0
+            .fetch_add(1, Ordering::SeqCst);
+        true
+    }
+}
+
+fn build_schema(
+    cache: &ResponseCache<LruCacheStorage>,
+) -> (Schema<Query, Mutation, EmptySubscription>, Counter) {
+    let counter = Counter::default();
+    let schema = Schema::build(Query, Mutation, EmptySubscription)
+        .data(counter.clone())
+        .middleware(cache.clone())
+        .finish();
+    (schema, counter)
+}
+
+#[async_std::test]
+pub async fn test_response_cache_public() {
+    let cache = ResponseCache::new(LruCacheStorage::new(16));
+    let (schema, counter) = build_schema(&cache);
+
+    for _ in 0..2 {
+        let resp = schema.execute("{ value }").await;
+        assert_eq!(resp.data, serde_json::json!({ "value": 10 }));
+        assert_eq!(
+            resp.cache_control,
+            CacheControl {
+                public: true,
+                max_age: 60
+            }
+        );
+    }
+    assert_eq!(counter.get(), 1);
+
+    // The variables are part of the key.
+    let query = "query Q($n: Int) { value(n: $n) }";
+    for n in &[1, 2, 1] {
+        let resp = schema
+            .execute(
+                Request::new(query).variables(Variables::from_json(serde_json::json!({ "n": n }))),
+            )
+            .await;
+        assert_eq!(resp.data, serde_json::json!({ "value": n }));
+    }
+    assert_eq!(counter.get(), 3);
+}
+
+#[async_std::test]
+pub async fn test_response_cache_private() {
+    let cache = ResponseCache::new(LruCacheStorage::new(16));
+    let (schema, counter) = build_schema(&cache);
+
+    // Private responses are never cached without a session.
+    schema.execute("{ me }").await;
+    schema.execute("{ me }").await;
+    assert_eq!(counter.get(), 2);
+
+    let resp = schema
+        .execute(Request::new("{ me }").data(SessionKey("a".to_string())))
+        .await;
+    assert_eq!(resp.data, serde_json::json!({ "me": "me" }));
+    assert!(!resp.cache_control.public);
+    assert_eq!(counter.get(), 3);
+
+    let resp = schema
+        .execute(Request::new("{ me }").data(SessionKey("a".to_string())))
+        .await;
+    assert_eq!(resp.data, serde_json::json!({ "me": "me" }));
+    assert!(!resp.cache_control.public);
+    assert_eq!(counter.get(), 3);
+
+    // Another session and the requests without session don't see the response.
+    schema
+        .execute(Request::new("{ me }").data(SessionKey("b".to_string())))
+        .await;
+    assert_eq!(counter.get(), 4);
+    schema.execute("{ me }").await;
+    assert_eq!(counter.get(), 5);
+}
+
+#[async_std::test]
+pub async fn test_response_cache_not_cached() {
+    let cache = ResponseCache::new(LruCacheStorage::new(16));
+    let (schema, counter) = build_schema(&cache);
+
+    schema.execute("mutation { action }").await;
+    schema.execute("mutation { action }").await;
+    assert_eq!(counter.get(), 2);
+
+    // Responses without max age are not cached.
+    let counter = Counter::default();
+    let schema = Schema::build(UncachedQuery, EmptyMutation, EmptySubscription)
+        .data(counter.clone())
+        .middleware(cache.clone())
+        .finish();
+    schema.execute("{ value }").await;
+    schema.execute("{ value }").await;
+    assert_eq!(counter.get(), 2);
+}
+
+#[async_std::test]
+pub async fn test_response_cache_invalidate() {
+    let cache = ResponseCache::new(LruCacheStorage::new(16));
+    let (schema, counter) = build_schema(&cache);
+
+    schema.execute("{ value }").await;
+    schema.execute("{ obj { value } }").await;
+    assert_eq!(counter.get(), 2);
+
+    cache.invalidate("MyObj").await;
+    schema.execute("{ value }").await;
+    assert_eq!(counter.get(), 2);
+    schema.execute("{ obj { value } }").await;
+    assert_eq!(counter.get(), 3);
+
+    cache.invalidate("Query").await;
+    schema.execute("{ value }").await;
+    schema.execute("{ obj { value } }").await;
+    assert_eq!(counter.get(), 5);
+}
@@ -0,0 +1,185 @@
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory};
+use async_graphql::parser::types::ExecutableDocument;
+use async_graphql::*;
+use std::sync::{Arc, Mutex};
+
+#[async_std::test]
+pub async fn test_secret_argument_error() {
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn login(&self, username: String, #[graphql(secret)] password: i32) -> bool {
+            username == "admin" && password == 123456
+        }
+    }
+
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .validation_mode(ValidationMode::Fast)
+        .finish();
+    let query = r#"query($password: Int!) { login(username: "admin", password: $password) }"#;
+    assert_eq!(
+        schema
+            .execute(Request::new(query).variables(Variables::from_json(
+                serde_json::json!({ "password": "abc" })
+            )))
+            .await
+            .into_result()
+            .unwrap_err(),
+        Error::Query {
+            pos: Pos {
+                line: 1,
+                column: 61,
+            },
+            path: None,
+            err: QueryError::ExpectedInputType {
+                expect: "Int!".to_string(),
+                actual: Value::String("******".to_string()),
+            },
+        }
+    );
+}
+
+#[async_std::test]
+pub async fn test_secret_validator_error() {
+    use async_graphql::validators::IntGreaterThan;
+
+    #[derive(InputObject)]
+    struct LoginInput {
+        username: String,
+        #[graphql(secret, validator(IntGreaterThan(value = "10")))]
+        pin: i32,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn login(
+            &self,
+            username: String,
+            #[graphql(secret, validator(IntGreaterThan(value = "10")))] pin: i32,
+        ) -> bool {
+            username == "admin" && pin == 123456
+        }
+
+        async fn login2(&self, input: LoginInput) -> bool {
+            input.username == "admin" && input.pin == 123456
+        }
+    }
+
+    let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+    let error = |column, message: &str| Error::Rule {
+        errors: vec![RuleError {
+            locations: vec![Pos { line: 1, column }],
+            message: message.to_string(),
+        }]
+        .into(),
+    };
+
+    assert_eq!(
+        schema
+            .execute(r#"{ login(username: "admin", pin: 5) }"#)
+            .await
+            .into_result()
+            .unwrap_err(),
+        error(28, "Invalid value for argument \"pin\""),
+    );
+    assert_eq!(
+        schema
+            .execute(r#"{ login2(input: { username: "admin", pin: 5 }) }"#)
+            .await
+            .into_result()
+            .unwrap_err(),
+        error(10, "Invalid value for argument \"input.pin\""),
+    );
+}
+
+#[async_std::test]
+pub async fn test_stringify_execute_doc() {
+    #[derive(InputObject)]
+    struct LoginInput {
+        username: String,
+        #[graphql(secret)]
+        password: String,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn login(&self, input: LoginInput) -> bool {
+            input.username == "admin" && input.password == "123456"
+        }
+
+        async fn login2(&self, username: String, #[graphql(secret)] password: String) -> bool {
+            username == "admin" && password == "123456"
+        }
+    }
+
+    type Captured = Arc<Mutex<Option<(String, Variables)>>>;
+
+    struct Capture(Captured);
+
+    impl ExtensionFactory for Capture {
+        fn create(&self) -> Box<dyn Extension> {
+            Box::new(CaptureExtension(self.0.clone(), Default::default()))
+        }
+    }
+
+    struct CaptureExtension(Captured, Mutex<Variables>);
+
+    impl Extension for CaptureExtension {
+        fn parse_start(
+            &self,
+            _ctx: &ExtensionContext<'_>,
+            _query_source: &str,
+            variables: &Variables,
+        ) {
+            *self.1.lock().unwrap() = variables.clone();
+        }
+
+        fn parse_end(&self, ctx: &ExtensionContext<'_>, document: &ExecutableDocument) {
+            *self.0.lock().unwrap() = Some((
+                ctx.stringify_execute_doc(document),
+                ctx.redact_variables(document, &self.1.lock().unwrap()),
+            ));
+        }
+    }
+
+    let captured = Captured::default();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .extension(Capture(captured.clone()))
+        .finish();
+
+    let query = r#"
+        query($input: LoginInput!, $password: String!) {
+            a: login(input: $input)
+            b: login(input: { username: "admin", password: "123456" })
+            c: login2(username: "admin", password: $password)
+        }
+    "#;
+    schema
+        .execute(
+            Request::new(query).variables(Variables::from_json(serde_json::json!({
+                "input": { "username": "admin", "password": "123456" },
+                "password": "123456",
+            }))),
+        )
+        .await
+        .into_result()
+        .unwrap();
+
+    let (doc, variables) = captured.lock().unwrap().take().unwrap();
+    assert_eq!(
+        doc,
+        r#"query($input: LoginInput!, $password: String!) { a: login(input: $input) b: login(input: {password: "******", username: "admin"}) c: login2(username: "admin", password: $password) }"#
+    );
+    assert_eq!(
+        variables.into_value().into_json().unwrap(),
+        serde_json::json!({
+            "input": { "username": "admin", "password": "******" },
+            "password": "******",
+        })
+    );
+}
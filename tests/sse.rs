@@ -0,0 +1,188 @@
+use async_graphql::http::{sse_stream, SseConnections, SseError};
+use async_graphql::*;
+use futures::{FutureExt, Stream, StreamExt};
+use std::time::Duration;
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn value(&self) -> i32 {
+        10
+    }
+}
+
+struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    async fn values(&self, end: i32) -> impl Stream<Item = i32> {
+        futures::stream::iter(0..end)
+    }
+
+    async fn forever(&self) -> impl Stream<Item = i32> {
+        futures::stream::pending()
+    }
+}
+
+fn request(query: &str, operation_id: &str) -> Request {
+    let mut request = Request::new(query);
+    request
+        .extensions
+        .insert("operationId".to_string(), operation_id.into());
+    request
+}
+
+#[async_std::test]
+pub async fn test_sse_distinct_connection() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+
+    let events = sse_stream(&schema, "{ value }")
+        .map(|event| event.to_string())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        events,
+        vec![
+            "event: next\ndata: {\"data\":{\"value\":10}}\n\n",
+            "event: complete\ndata: \n\n",
+        ]
+    );
+
+    let events = sse_stream(&schema, "subscription { values(end: 2) }")
+        .map(|event| event.to_string())
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        events,
+        vec![
+            "event: next\ndata: {\"data\":{\"values\":0}}\n\n",
+            "event: next\ndata: {\"data\":{\"values\":1}}\n\n",
+            "event: complete\ndata: \n\n",
+        ]
+    );
+}
+
+#[async_std::test]
+pub async fn test_sse_single_connection() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+    let connections = SseConnections::new();
+
+    assert_eq!(
+        connections
+            .execute(&schema, "unknown", request("{ value }", "1"))
+            .unwrap_err(),
+        SseError::UnknownToken
+    );
+
+    let token = connections.reserve();
+    assert_eq!(
+        connections
+            .execute(&schema, &token, Request::new("{ value }"))
+            .unwrap_err(),
+        SseError::MissingOperationId
+    );
+
+    // The operations executed before connecting are sent when the stream is opened.
+    connections
+        .execute(&schema, &token, request("{ value }", "1"))
+        .unwrap();
+    let mut stream = connections.connect(&token).unwrap().boxed();
+    assert_eq!(
+        connections.connect(&token).err(),
+        Some(SseError::AlreadyConnected)
+    );
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: next\ndata: {\"id\":\"1\",\"payload\":{\"data\":{\"value\":10}}}\n\n"
+    );
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: complete\ndata: {\"id\":\"1\"}\n\n"
+    );
+
+    connections
+        .execute(
+            &schema,
+            &token,
+            request("subscription { values(end: 2) }", "2"),
+        )
+        .unwrap();
+    for value in 0..2 {
+        assert_eq!(
+            stream.next().await.unwrap().to_string(),
+            format!(
+                "event: next\ndata: {{\"id\":\"2\",\"payload\":{{\"data\":{{\"values\":{}}}}}}}\n\n",
+                value
+            )
+        );
+    }
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: complete\ndata: {\"id\":\"2\"}\n\n"
+    );
+
+    // A stopped operation doesn't complete.
+    connections
+        .execute(&schema, &token, request("subscription { forever }", "3"))
+        .unwrap();
+    assert_eq!(
+        connections
+            .execute(&schema, &token, request("subscription { forever }", "3"))
+            .unwrap_err(),
+        SseError::DuplicateOperationId("3".to_string())
+    );
+    assert!(stream.next().now_or_never().is_none());
+    connections.stop(&token, "3").unwrap();
+    assert!(stream.next().now_or_never().is_none());
+
+    // The operation id can be reused after the operation is stopped.
+    connections
+        .execute(&schema, &token, request("{ value }", "3"))
+        .unwrap();
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: next\ndata: {\"id\":\"3\",\"payload\":{\"data\":{\"value\":10}}}\n\n"
+    );
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: complete\ndata: {\"id\":\"3\"}\n\n"
+    );
+
+    // Dropping the stream closes the connection.
+    drop(stream);
+    assert_eq!(
+        connections
+            .execute(&schema, &token, request("{ value }", "4"))
+            .unwrap_err(),
+        SseError::UnknownToken
+    );
+}
+
+#[async_std::test]
+pub async fn test_sse_reservation_timeout() {
+    let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+    let connections = SseConnections::new().reservation_timeout(Duration::from_millis(50));
+
+    let expired = connections.reserve();
+    let connected = connections.reserve();
+    assert_ne!(expired, connected);
+    let mut stream = connections.connect(&connected).unwrap().boxed();
+    connections
+        .execute(&schema, &expired, request("{ value }", "1"))
+        .unwrap();
+
+    // The token that is not connected in time is forgotten, the connected one is kept.
+    async_std::task::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        connections.connect(&expired).err(),
+        Some(SseError::UnknownToken)
+    );
+    connections
+        .execute(&schema, &connected, request("{ value }", "1"))
+        .unwrap();
+    assert_eq!(
+        stream.next().await.unwrap().to_string(),
+        "event: next\ndata: {\"id\":\"1\",\"payload\":{\"data\":{\"value\":10}}}\n\n"
+    );
+}
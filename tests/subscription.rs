@@ -425,3 +425,43 @@ pub async fn test_subscription_fieldresult() {
 
     assert!(stream.next().await.is_none());
 }
+
+#[async_std::test]
+pub async fn test_subscription_filter() {
+    struct QueryRoot;
+
+    #[Object]
+    impl QueryRoot {}
+
+    struct MinValue(i32);
+
+    fn above_min(ctx: &Context<'_>, value: &i32) -> bool {
+        *value >= ctx.data_unchecked::<MinValue>().0
+    }
+
+    struct SubscriptionRoot;
+
+    #[Subscription]
+    impl SubscriptionRoot {
+        #[graphql(filter = "above_min")]
+        async fn values(&self) -> impl Stream<Item = i32> {
+            futures::stream::iter(vec![1, 5, 2, 8, 3])
+        }
+    }
+
+    let schema = Schema::new(QueryRoot, EmptyMutation, SubscriptionRoot);
+    for (min, values) in &[(3, vec![5, 8, 3]), (6, vec![8])] {
+        let data = schema
+            .execute_stream(Request::new("subscription { values }").data(MinValue(*min)))
+            .map(|resp| resp.into_result().unwrap().data)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            data,
+            values
+                .iter()
+                .map(|value| serde_json::json!({ "values": value }))
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "multipart")]
+
+use async_graphql::http::{receive_body, MultipartOptions};
+use async_graphql::*;
+use futures::AsyncReadExt;
+
+const BOUNDARY: &str = "boundary";
+
+fn multipart_body(operations: &str, map: &str, files: &[(&str, &str, &str, &str)]) -> Vec<u8> {
+    let mut body = format!(
+        "--{b}\r\nContent-Disposition: form-data; name=\"operations\"\r\n\r\n{}\r\n\
+         --{b}\r\nContent-Disposition: form-data; name=\"map\"\r\n\r\n{}\r\n",
+        operations,
+        map,
+        b = BOUNDARY
+    );
+    for (name, filename, content_type, content) in files {
+        body += &format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+             Content-Type: {}\r\n\r\n{}\r\n",
+            name,
+            filename,
+            content_type,
+            content,
+            b = BOUNDARY
+        );
+    }
+    body += &format!("--{}--\r\n", BOUNDARY);
+    body.into_bytes()
+}
+
+async fn receive(
+    body: Vec<u8>,
+    opts: MultipartOptions,
+) -> std::result::Result<Request, ParseRequestError> {
+    receive_body(
+        Some(format!("multipart/form-data; boundary={}", BOUNDARY)),
+        futures::io::Cursor::new(body),
+        opts,
+    )
+    .await
+}
+
+struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn value(&self) -> i32 {
+        10
+    }
+}
+
+struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn upload(&self, file: Upload) -> FieldResult<String> {
+        let mut stream = file.into_stream().await?;
+        let mut content = String::new();
+        stream.read_to_string(&mut content).await?;
+        Ok(format!(
+            "{}:{}:{}",
+            stream.filename(),
+            stream.content_type().unwrap_or_default(),
+            content
+        ))
+    }
+}
+
+fn files_body() -> Vec<u8> {
+    multipart_body(
+        r#"{ "query": "mutation($a: Upload!, $b: Upload!) { b: upload(file: $b) a: upload(file: $a) }", "variables": { "a": null, "b": null } }"#,
+        r#"{ "0": ["variables.a"], "1": ["variables.b"] }"#,
+        &[
+            ("0", "a.txt", "text/plain", "hello"),
+            ("1", "b.png", "image/png", "world"),
+        ],
+    )
+}
+
+#[async_std::test]
+pub async fn test_upload() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    for streaming in &[false, true] {
+        let request = receive(
+            files_body(),
+            MultipartOptions::default().streaming(*streaming),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            schema.execute(request).await.data,
+            serde_json::json!({
+                "b": "b.png:image/png:world",
+                "a": "a.txt:text/plain:hello",
+            })
+        );
+    }
+}
+
+#[async_std::test]
+pub async fn test_upload_content_types() {
+    let schema = Schema::new(QueryRoot, MutationRoot, EmptySubscription);
+
+    match receive(
+        files_body(),
+        MultipartOptions::default().allowed_content_types(vec!["image/*"]),
+    )
+    .await
+    {
+        Err(ParseRequestError::ContentTypeNotAllowed {
+            var_path,
+            content_type,
+        }) => {
+            assert_eq!(var_path, "variables.a");
+            assert_eq!(content_type.as_deref(), Some("text/plain"));
+        }
+        _ => panic!("expected a content type error"),
+    }
+
+    let request = receive(
+        files_body(),
+        MultipartOptions::default()
+            .allowed_content_types(vec!["image/*"])
+            .upload_content_types("variables.a", vec!["text/plain"]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        schema.execute(request).await.data,
+        serde_json::json!({
+            "b": "b.png:image/png:world",
+            "a": "a.txt:text/plain:hello",
+        })
+    );
+
+    let request = receive(
+        files_body(),
+        MultipartOptions::default()
+            .streaming(true)
+            .allowed_content_types(vec!["text/plain"]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        schema
+            .execute(request)
+            .await
+            .into_result()
+            .unwrap_err()
+            .to_string(),
+        "Query error: Failed to resolve field: The content type of the file \"variables.b\" is not allowed"
+    );
+}